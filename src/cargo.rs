@@ -1,14 +1,101 @@
 use eyre::{Context, ContextCompat, Result, bail};
+use log::warn;
+use semver::{Op, VersionReq};
 use std::fs;
-use std::path::Path;
-use toml_edit::{DocumentMut, Item, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+use crate::output::status;
+
+/// How often `cargo update` is retried after a network error; set once at startup from --lock-retries
+static LOCK_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_LOCK_RETRIES);
+
+/// Retries by default, so a transient registry error gets three attempts in all
+pub const DEFAULT_LOCK_RETRIES: u32 = 2;
+
+/// Wait before the first retry; each later retry waits twice as long
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Phrases in cargo's stderr that mean the registry couldn't be reached, rather than a manifest problem
+const NETWORK_ERRORS: &[&str] = &[
+    "could not resolve host",
+    "couldn't resolve host",
+    "temporary failure in name resolution",
+    "failed to connect",
+    "connection refused",
+    "connection reset",
+    "connection timed out",
+    "operation timed out",
+    "timed out",
+    "timeout was reached",
+    "network failure",
+    "spurious network error",
+    "failed to download",
+    "ssl connect error",
+];
 
 /// Read the version from Cargo.toml
 /// Returns None if version field is missing
 pub fn read_version(cargo_toml_path: &Path) -> Result<Option<String>> {
+    let cargo_toml_path = version_manifest(cargo_toml_path)?;
+    let content =
+        fs::read_to_string(&cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+
+    read_version_from_str(&content)
+}
+
+/// The manifest that holds the version for a Cargo.toml: the workspace root's when the
+/// package inherits version.workspace = true from a workspace in a parent directory,
+/// otherwise the Cargo.toml itself
+pub fn version_manifest(cargo_toml_path: &Path) -> Result<PathBuf> {
     let content =
         fs::read_to_string(cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+    let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
+
+    let inherits = doc
+        .get("package")
+        .and_then(|p| p.get("version"))
+        .is_some_and(is_workspace_version);
+    if !inherits || doc.get("workspace").is_some() {
+        return Ok(cargo_toml_path.to_path_buf());
+    }
+
+    // The member's own manifest has no [workspace], so the walk moves on to its parents
+    let member_dir = cargo_toml_path.parent().unwrap_or(Path::new("."));
+    match find_workspace_root(member_dir)? {
+        Some(root) => Ok(root.join("Cargo.toml")),
+        None => Ok(cargo_toml_path.to_path_buf()),
+    }
+}
 
+/// Walk up from a directory to the nearest one whose Cargo.toml has a [workspace] table
+/// Stops at the enclosing git repository root; None if there is no such workspace
+pub fn find_workspace_root(dir: &Path) -> Result<Option<PathBuf>> {
+    for ancestor in dir.ancestors() {
+        let cargo_toml = cargo_toml_path(ancestor);
+        if cargo_toml.exists() {
+            let content =
+                fs::read_to_string(&cargo_toml).context(format!("Failed to read {}", cargo_toml.display()))?;
+            let doc = content
+                .parse::<DocumentMut>()
+                .context(format!("Failed to parse {}", cargo_toml.display()))?;
+            if doc.get("workspace").is_some() {
+                return Ok(Some(ancestor.to_path_buf()));
+            }
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+/// Read the version from Cargo.toml contents (e.g. a committed revision)
+/// Returns None if version field is missing
+pub fn read_version_from_str(content: &str) -> Result<Option<String>> {
     let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
 
     // Try [package] version first
@@ -33,20 +120,90 @@ pub fn read_version(cargo_toml_path: &Path) -> Result<Option<String>> {
     Ok(None)
 }
 
+/// Find the item at a dotted key path (e.g. `package.metadata.release.version`)
+fn item_at<'a>(doc: &'a DocumentMut, key: &str) -> Option<&'a Item> {
+    key.split('.').try_fold(doc.as_item(), |item, part| item.get(part))
+}
+
+/// Read the version stored at a dotted key path instead of `package.version` (--version-key)
+/// Returns None if the key is missing
+pub fn read_version_at(cargo_toml_path: &Path, key: &str) -> Result<Option<String>> {
+    let content =
+        fs::read_to_string(cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+    let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
+
+    match item_at(&doc, key) {
+        Some(item) => {
+            let version = item
+                .as_str()
+                .with_context(|| format!("{} in {} is not a string", key, cargo_toml_path.display()))?;
+            Ok(Some(version.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Write the version at a dotted key path, creating any missing tables (--version-key)
+pub fn write_version_at(cargo_toml_path: &Path, key: &str, new_version: &str) -> Result<()> {
+    let content =
+        fs::read_to_string(cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+    let updated = set_version_at(&content, key, new_version)
+        .context(format!("Failed to update {}", cargo_toml_path.display()))?;
+
+    fs::write(cargo_toml_path, updated).context(format!("Failed to write {}", cargo_toml_path.display()))?;
+    Ok(())
+}
+
+/// The Cargo.toml contents with the version at a dotted key path set, creating any missing tables
+pub fn set_version_at(content: &str, key: &str, new_version: &str) -> Result<String> {
+    let mut doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
+
+    let (parents, last) = match key.rsplit_once('.') {
+        Some((parents, last)) => (parents.split('.').collect(), last),
+        None => (Vec::new(), key),
+    };
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for part in parents {
+        if table.get(part).is_none() {
+            table.insert(part, toml_edit::table());
+        }
+        table = table
+            .get_mut(part)
+            .and_then(Item::as_table_like_mut)
+            .with_context(|| format!("{} in Cargo.toml is not a table", part))?;
+    }
+    table.insert(last, toml_edit::value(new_version));
+
+    Ok(with_original_line_endings(content, &doc.to_string()))
+}
+
+/// Serialized edits use LF; put back the original's line endings (CRLF or LF) and final newline,
+/// so the diff only shows the lines that changed
+pub fn with_original_line_endings(original: &str, updated: &str) -> String {
+    let mut updated = updated.replace("\r\n", "\n");
+    if original.ends_with('\n') && !updated.ends_with('\n') {
+        updated.push('\n');
+    } else if !original.is_empty() && !original.ends_with('\n') {
+        updated.truncate(updated.trim_end_matches('\n').len());
+    }
+
+    if original.contains("\r\n") { updated.replace('\n', "\r\n") } else { updated }
+}
+
 /// Check if a version field uses workspace = true (either inline table or dotted key)
 fn is_workspace_version(version: &Item) -> bool {
     // Check inline table syntax: version = { workspace = true }
-    if let Some(table) = version.as_inline_table() {
-        if table.get("workspace").is_some_and(|w| w.as_bool() == Some(true)) {
-            return true;
-        }
+    if let Some(table) = version.as_inline_table()
+        && table.get("workspace").is_some_and(|w| w.as_bool() == Some(true))
+    {
+        return true;
     }
     // Check dotted key syntax: version.workspace = true
     // This gets parsed as a regular table by toml_edit
-    if let Some(table) = version.as_table_like() {
-        if table.get("workspace").is_some_and(|w| w.as_bool() == Some(true)) {
-            return true;
-        }
+    if let Some(table) = version.as_table_like()
+        && table.get("workspace").is_some_and(|w| w.as_bool() == Some(true))
+    {
+        return true;
     }
     false
 }
@@ -63,17 +220,41 @@ fn read_workspace_version(doc: &DocumentMut) -> Result<Option<String>> {
     Ok(None)
 }
 
+/// Get [package].name from a parsed manifest
+fn package_name(doc: &DocumentMut) -> Option<&str> {
+    doc.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str())
+}
+
+/// Read the package name from Cargo.toml
+/// Returns None for workspace-only manifests (no [package] section)
+pub fn read_package_name(cargo_toml_path: &Path) -> Result<Option<String>> {
+    let content =
+        fs::read_to_string(cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+
+    let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
+
+    Ok(package_name(&doc).map(|n| n.to_string()))
+}
+
 /// Check if this is a workspace-only manifest (has [workspace] but no [package])
 fn is_workspace_only(doc: &DocumentMut) -> bool {
     doc.get("workspace").is_some() && doc.get("package").is_none()
 }
 
-/// Update the version in Cargo.toml
+/// Update the version in Cargo.toml (the workspace root's, for a member inheriting its version)
 /// Creates the version field if it doesn't exist
 pub fn write_version(cargo_toml_path: &Path, new_version: &str) -> Result<()> {
+    let cargo_toml_path = version_manifest(cargo_toml_path)?;
     let content =
-        fs::read_to_string(cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+        fs::read_to_string(&cargo_toml_path).context(format!("Failed to read {}", cargo_toml_path.display()))?;
+    let updated = set_version(&content, new_version)?;
+
+    fs::write(&cargo_toml_path, updated).context(format!("Failed to write {}", cargo_toml_path.display()))?;
+    Ok(())
+}
 
+/// The Cargo.toml contents with the version set, without writing anything (used to preview a dry run)
+pub fn set_version(content: &str, new_version: &str) -> Result<String> {
     let mut doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
 
     // Check if this is a workspace-only manifest (no [package] section)
@@ -95,9 +276,7 @@ pub fn write_version(cargo_toml_path: &Path, new_version: &str) -> Result<()> {
             bail!("[workspace] is not a table");
         }
 
-        fs::write(cargo_toml_path, doc.to_string())
-            .context(format!("Failed to write {}", cargo_toml_path.display()))?;
-        return Ok(());
+        return Ok(with_original_line_endings(content, &doc.to_string()));
     }
 
     // Check if this is a workspace member with version.workspace = true
@@ -132,16 +311,13 @@ pub fn write_version(cargo_toml_path: &Path, new_version: &str) -> Result<()> {
         }
     }
 
-    fs::write(cargo_toml_path, doc.to_string()).context(format!("Failed to write {}", cargo_toml_path.display()))?;
-
-    Ok(())
+    Ok(with_original_line_endings(content, &doc.to_string()))
 }
 
 /// Sync Cargo.lock with Cargo.toml by running cargo update
 /// Only runs if Cargo.lock exists (to avoid creating one in library-only projects)
 pub fn sync_lockfile(dir: &Path) -> Result<()> {
-    let lockfile = dir.join("Cargo.lock");
-    if !lockfile.exists() {
+    if find_lockfile(dir).is_none() {
         return Ok(());
     }
 
@@ -152,44 +328,120 @@ pub fn sync_lockfile(dir: &Path) -> Result<()> {
 
     // Check if this is a workspace-only manifest
     if is_workspace_only(&doc) {
-        // For workspaces, just run cargo update to sync all workspace members
-        let output = std::process::Command::new("cargo")
-            .args(["update", "--workspace"])
-            .current_dir(dir)
-            .output()
-            .context("Failed to run cargo update")?;
+        // For workspaces, sync the members; --workspace leaves registry dependencies alone
+        return cargo_update(dir, &["--workspace"]).wrap_err(LOCK_OUT_OF_DATE);
+    }
 
-        if !output.status.success() {
-            bail!(
-                "cargo update --workspace failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
-        }
+    // For regular packages, get the package name
+    let package_name = package_name(&doc).context("Failed to get package name from Cargo.toml")?;
+
+    // Pin the package to its new version so no other lockfile entry is re-resolved
+    // (a member inheriting its version from a parent workspace has no literal version to pin)
+    match read_version_from_str(&content)? {
+        Some(version) => cargo_update(dir, &["-p", package_name, "--precise", &version]),
+        None => cargo_update(dir, &["-p", package_name]),
+    }
+    .wrap_err(LOCK_OUT_OF_DATE)
+}
 
+/// What went wrong when the sync fails after the version was written, and the way out
+const LOCK_OUT_OF_DATE: &str = "The new version is written to Cargo.toml but Cargo.lock is out of date. \
+                                Pass --no-lock to skip the lockfile sync and update Cargo.lock yourself";
+
+/// Check that `cargo update` can resolve the lockfile for the current Cargo.toml, without writing it
+/// (dry-run preflight); it can't see problems that only the bumped version would cause
+/// Nothing to check without a Cargo.lock
+pub fn check_lockfile_sync(dir: &Path) -> Result<()> {
+    if find_lockfile(dir).is_none() {
         return Ok(());
     }
 
-    // For regular packages, get the package name
-    let package_name = doc
-        .get("package")
-        .and_then(|p| p.get("name"))
-        .and_then(|n| n.as_str())
-        .context("Failed to get package name from Cargo.toml")?;
+    let cargo_toml = dir.join("Cargo.toml");
+    let content = fs::read_to_string(&cargo_toml).context(format!("Failed to read {}", cargo_toml.display()))?;
+    let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
+    if is_workspace_only(&doc) {
+        return cargo_update(dir, &["--workspace", "--dry-run"]);
+    }
 
-    // Run cargo update -p <package> to sync just this package in the lock file
-    let output = std::process::Command::new("cargo")
-        .args(["update", "-p", package_name])
-        .current_dir(dir)
-        .output()
-        .context("Failed to run cargo update")?;
+    let package_name = package_name(&doc).context("Failed to get package name from Cargo.toml")?;
+    cargo_update(dir, &["-p", package_name, "--dry-run"])
+}
+
+/// Run `cargo update` offline first, so air-gapped machines work, then with the network
+/// Bumping only changes local packages, so the offline run normally succeeds
+fn cargo_update(dir: &Path, args: &[&str]) -> Result<()> {
+    let run = |offline: bool| {
+        std::process::Command::new("cargo")
+            .arg("update")
+            .args(args)
+            .args(offline.then_some("--offline"))
+            .current_dir(dir)
+            .output()
+            .context("Failed to run cargo update")
+    };
+
+    if run(true)?.status.success() {
+        return Ok(());
+    }
+
+    // Only network errors are worth another try; a broken manifest fails the same way every time
+    let retries = LOCK_RETRIES.load(Ordering::Relaxed);
+    let mut attempt = 0;
+    let output = loop {
+        let output = run(false)?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() || attempt >= retries || !is_network_failure(&stderr) {
+            break output;
+        }
+        // Backoff doubles up to 64s, so a large --lock-retries can't overflow it
+        let delay = RETRY_DELAY * 2u32.saturating_pow(attempt.min(6));
+        attempt += 1;
+        status!("cargo update hit a network error; retrying in {}s ({}/{})", delay.as_secs(), attempt, retries);
+        thread::sleep(delay);
+    };
 
     if !output.status.success() {
-        bail!("cargo update failed: {}", String::from_utf8_lossy(&output.stderr));
+        bail!(
+            "cargo update {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
     Ok(())
 }
 
+/// Set how often `cargo update` is retried after a network error (--lock-retries)
+pub fn set_lock_retries(retries: u32) {
+    LOCK_RETRIES.store(retries, Ordering::Relaxed);
+}
+
+/// Whether cargo's stderr reports a registry/network problem (worth retrying) rather than a manifest error
+fn is_network_failure(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    NETWORK_ERRORS.iter().any(|phrase| stderr.contains(phrase))
+}
+
+/// Find the Cargo.lock for a manifest directory: alongside it, or in a parent
+/// workspace directory (stopping at the enclosing git repository root)
+pub fn find_lockfile(dir: &Path) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        let lockfile = ancestor.join("Cargo.lock");
+        if lockfile.exists() {
+            return Some(lockfile);
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Whether the manifest directory has a Cargo.lock (its own or a parent workspace's) to keep in sync
+pub fn has_lockfile(dir: &Path) -> bool {
+    find_lockfile(dir).is_some()
+}
+
 /// Check if Cargo.toml exists at the given path
 pub fn cargo_toml_exists(dir: &Path) -> bool {
     dir.join("Cargo.toml").exists()
@@ -201,6 +453,20 @@ pub struct IndependentVersionMember {
     pub name: String,
     pub path: String,
     pub version: String,
+    /// False for `publish = false` (or an empty registry list): internal crates that get no release tags
+    pub publish: bool,
+}
+
+/// Whether a [package] table is kept off registries (`publish = false` or `publish = []`)
+fn is_unpublished(package: &Item) -> bool {
+    package
+        .get("publish")
+        .is_some_and(|p| p.as_bool() == Some(false) || p.as_array().is_some_and(|a| a.is_empty()))
+}
+
+/// Whether members with independent versions all share one version (trivially true for none or one)
+pub fn versions_consistent(members: &[IndependentVersionMember]) -> bool {
+    members.windows(2).all(|pair| pair[0].version == pair[1].version)
 }
 
 /// Check if workspace members have independent versions (not using version.workspace = true)
@@ -211,29 +477,18 @@ pub fn check_workspace_independent_versions(dir: &Path) -> Result<Vec<Independen
         .context(format!("Failed to read {}", cargo_toml.display()))?;
     let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
 
-    // Only check if this is a workspace
-    let workspace = match doc.get("workspace") {
-        Some(ws) => ws,
-        None => return Ok(vec![]), // Not a workspace, nothing to check
-    };
-
-    // Get workspace members
-    let members = match workspace.get("members").and_then(|m| m.as_array()) {
-        Some(arr) => arr,
-        None => return Ok(vec![]), // No members defined
-    };
+    // Only check if this is a workspace with members defined
+    if doc.get("workspace").and_then(|ws| ws.get("members")).is_none() {
+        return Ok(vec![]);
+    }
 
     let mut independent_versions = Vec::new();
 
-    for member in members.iter() {
-        let member_path = match member.as_str() {
-            Some(p) => p,
-            None => continue,
-        };
-
-        let member_cargo_toml = dir.join(member_path).join("Cargo.toml");
+    // Globbed members (e.g. `crates/*`) are expanded to each crate directory
+    for member_path in workspace_member_paths(dir)? {
+        let member_cargo_toml = dir.join(&member_path).join("Cargo.toml");
         if !member_cargo_toml.exists() {
-            continue; // Member might use glob pattern or doesn't exist yet
+            continue; // Member doesn't exist yet
         }
 
         let member_content = fs::read_to_string(&member_cargo_toml)
@@ -243,36 +498,291 @@ pub fn check_workspace_independent_versions(dir: &Path) -> Result<Vec<Independen
             .context(format!("Failed to parse {}", member_cargo_toml.display()))?;
 
         // Check if this member has an independent version
-        if let Some(package) = member_doc.get("package") {
-            if let Some(version) = package.get("version") {
-                // Check if it's NOT using workspace = true
-                let uses_workspace = is_workspace_version(version);
-
-                if !uses_workspace {
-                    // This member has an independent version
-                    if let Some(v) = version.as_str() {
-                        let name = package
-                            .get("name")
-                            .and_then(|n| n.as_str())
-                            .unwrap_or(member_path)
-                            .to_string();
-
-                        independent_versions.push(IndependentVersionMember {
-                            name,
-                            path: member_path.to_string(),
-                            version: v.to_string(),
-                        });
-                    }
-                }
-            }
+        if let Some(package) = member_doc.get("package")
+            && let Some(version) = package.get("version")
+            // Check if it's NOT using workspace = true
+            && !is_workspace_version(version)
+            // This member has an independent version
+            && let Some(v) = version.as_str()
+        {
+            let name = package
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or(&member_path)
+                .to_string();
+
+            independent_versions.push(IndependentVersionMember {
+                name,
+                path: member_path.clone(),
+                version: v.to_string(),
+                publish: !is_unpublished(package),
+            });
         }
     }
 
     Ok(independent_versions)
 }
 
+/// List the member directories of a workspace, expanding glob and brace patterns
+/// (e.g. `crates/*`, `{core,cli}`) and leaving out anything under `[workspace].exclude`
+fn workspace_member_paths(dir: &Path) -> Result<Vec<String>> {
+    let cargo_toml = dir.join("Cargo.toml");
+    let content = fs::read_to_string(&cargo_toml).context(format!("Failed to read {}", cargo_toml.display()))?;
+    let doc = content.parse::<DocumentMut>().context("Failed to parse Cargo.toml")?;
+
+    let Some(members) = doc.get("workspace").and_then(|w| w.get("members")).and_then(|m| m.as_array()) else {
+        bail!("{} is not a workspace (no [workspace].members)", cargo_toml.display());
+    };
+    let excluded: Vec<&str> = doc
+        .get("workspace")
+        .and_then(|w| w.get("exclude"))
+        .and_then(|e| e.as_array())
+        .map(|e| e.iter().filter_map(|p| p.as_str()).map(|p| p.trim_end_matches('/')).collect())
+        .unwrap_or_default();
+
+    let mut paths = Vec::new();
+    for pattern in members.iter().filter_map(|m| m.as_str()).flat_map(expand_braces) {
+        if !pattern.contains(['*', '?', '[']) {
+            paths.push(pattern);
+            continue;
+        }
+
+        let full = format!("{}/{}", glob::Pattern::escape(&dir.to_string_lossy()), pattern);
+        let mut matches = Vec::new();
+        for entry in glob::glob(&full).context(format!("Invalid workspace member pattern '{}'", pattern))? {
+            let path = entry.context(format!("Failed to read workspace members matching '{}'", pattern))?;
+            if path.is_dir()
+                && let Ok(relative) = path.strip_prefix(dir)
+            {
+                matches.push(relative.to_string_lossy().to_string());
+            }
+        }
+        matches.sort();
+        paths.extend(matches);
+    }
+
+    paths.retain(|path| {
+        !excluded
+            .iter()
+            .any(|exclude| path == exclude || path.starts_with(&format!("{}/", exclude)))
+    });
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Expand the first `{a,b}` group of a pattern into one pattern per alternative, recursively
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let Some(close) = pattern[open..].find('}').map(|i| open + i) else {
+        return vec![pattern.to_string()];
+    };
+
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|alternative| {
+            expand_braces(&format!("{}{}{}", &pattern[..open], alternative, &pattern[close + 1..]))
+        })
+        .collect()
+}
+
+/// Read and parse every workspace member manifest that exists on disk
+fn member_manifests(workspace_dir: &Path) -> Result<Vec<(PathBuf, DocumentMut)>> {
+    let mut manifests = Vec::new();
+    for member_path in workspace_member_paths(workspace_dir)? {
+        let manifest = workspace_dir.join(&member_path).join("Cargo.toml");
+        if !manifest.exists() {
+            continue;
+        }
+
+        let content = fs::read_to_string(&manifest).context(format!("Failed to read {}", manifest.display()))?;
+        let doc = content
+            .parse::<DocumentMut>()
+            .context(format!("Failed to parse {}", manifest.display()))?;
+        manifests.push((manifest, doc));
+    }
+    Ok(manifests)
+}
+
+/// Resolve a workspace member's Cargo.toml by package name
+/// Errors if no member has that name or if it inherits the workspace version
+pub fn find_member_manifest(workspace_dir: &Path, name: &str) -> Result<PathBuf> {
+    let Some((manifest, doc)) = member_manifests(workspace_dir)?
+        .into_iter()
+        .find(|(_, doc)| package_name(doc) == Some(name))
+    else {
+        bail!("No workspace member named '{}' in {}", name, workspace_dir.display());
+    };
+
+    if doc.get("package").and_then(|p| p.get("version")).is_some_and(is_workspace_version) {
+        bail!(
+            "Package '{}' uses version.workspace = true; bump the workspace instead of using --package",
+            name
+        );
+    }
+    Ok(manifest)
+}
+
+/// Names of the published members sharing the workspace version (version.workspace = true)
+/// Members with `publish = false` (or an empty registry list) are left out unless `include_unpublished`
+pub fn shared_version_members(workspace_dir: &Path, include_unpublished: bool) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for (_, doc) in member_manifests(workspace_dir)? {
+        let Some(package) = doc.get("package") else {
+            continue;
+        };
+        let inherits = package.get("version").is_some_and(is_workspace_version);
+        if inherits
+            && (include_unpublished || !is_unpublished(package))
+            && let Some(name) = package_name(&doc)
+        {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// Dependency tables that can hold path dependencies on a sibling crate
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Rewrite a version requirement to a new version, keeping its operator and precision ("^1.2" → "^1.3")
+/// Returns None for requirements that can't simply be moved forward: compound ranges (">=1.0, <2.0"),
+/// upper bounds, wildcards, or anything that doesn't parse
+fn updated_requirement(requirement: &str, new_version: &str) -> Option<String> {
+    let parsed = VersionReq::parse(requirement).ok()?;
+    let [comparator] = parsed.comparators.as_slice() else {
+        return None;
+    };
+    if !matches!(comparator.op, Op::Caret | Op::Tilde | Op::Exact | Op::GreaterEq) {
+        return None;
+    }
+
+    let operator_len = requirement.len() - requirement.trim_start_matches(['^', '~', '=', '>', ' ']).len();
+    let operator = &requirement[..operator_len];
+
+    // Pre-releases only match exactly, so always write them in full
+    if new_version.contains('-') {
+        return Some(format!("{}{}", operator, new_version));
+    }
+    let precision = 1 + usize::from(comparator.minor.is_some()) + usize::from(comparator.patch.is_some());
+    let truncated: Vec<&str> = new_version.split('.').take(precision).collect();
+    Some(format!("{}{}", operator, truncated.join(".")))
+}
+
+/// Point the `version` of path dependencies on the crate at `crate_dir` at the new version
+/// `base_dir` is the directory the table's `path` values are relative to; returns whether anything changed
+fn update_dependency_table(
+    deps: &mut dyn TableLike,
+    base_dir: &Path,
+    crate_name: &str,
+    crate_dir: &Path,
+    new_version: &str,
+    manifest: &Path,
+) -> bool {
+    let mut touched = false;
+    for (key, dep) in deps.iter_mut() {
+        let Some(dep) = dep.as_table_like_mut() else {
+            continue;
+        };
+        // The dependency may be renamed with `package = "..."`
+        let name = dep.get("package").and_then(|p| p.as_str()).unwrap_or(key.get());
+        if name != crate_name {
+            continue;
+        }
+
+        let points_at_crate = dep
+            .get("path")
+            .and_then(|p| p.as_str())
+            .and_then(|p| base_dir.join(p).canonicalize().ok())
+            .is_some_and(|p| p == crate_dir);
+        if !points_at_crate {
+            continue;
+        }
+
+        if let Some(version) = dep.get_mut("version")
+            && let Some(Value::String(requirement)) = version.as_value_mut()
+        {
+            let Some(updated) = updated_requirement(requirement.value(), new_version) else {
+                let message = format!(
+                    "Left {} requirement '{}' in {} unchanged; update it by hand if {} should match",
+                    crate_name,
+                    requirement.value(),
+                    manifest.display(),
+                    new_version
+                );
+                warn!("{}", message);
+                status!("Warning: {}", message);
+                continue;
+            };
+            if *requirement.value() != updated {
+                let decor = requirement.decor().clone();
+                *requirement = toml_edit::Formatted::new(updated);
+                *requirement.decor_mut() = decor;
+                touched = true;
+            }
+        }
+    }
+    touched
+}
+
+/// Update the `version` of path dependencies on `crate_name` in every workspace member,
+/// and in the root manifest's `[workspace.dependencies]`
+/// Only dependencies whose `path` points at that crate are touched; other keys are preserved
+/// Returns the manifests that changed
+pub fn update_internal_dependents(dir: &Path, crate_name: &str, new_version: &str) -> Result<Vec<PathBuf>> {
+    let mut manifests = member_manifests(dir)?;
+    let Some(crate_dir) = manifests
+        .iter()
+        .find(|(_, doc)| package_name(doc) == Some(crate_name))
+        .and_then(|(manifest, _)| manifest.parent())
+        .and_then(|d| d.canonicalize().ok())
+    else {
+        bail!("No workspace member named '{}' in {}", crate_name, dir.display());
+    };
+
+    // The root manifest may also be a member (members = ["."]); edit that copy so both changes land together
+    let root = cargo_toml_path(dir);
+    let root_canonical = root.canonicalize().ok();
+    if !manifests.iter().any(|(manifest, _)| manifest.canonicalize().ok() == root_canonical) {
+        let content = fs::read_to_string(&root).context(format!("Failed to read {}", root.display()))?;
+        let doc = content
+            .parse::<DocumentMut>()
+            .context(format!("Failed to parse {}", root.display()))?;
+        manifests.push((root.clone(), doc));
+    }
+
+    let mut changed = Vec::new();
+    for (manifest, doc) in manifests.iter_mut() {
+        let member_dir = manifest.parent().unwrap_or(dir).to_path_buf();
+        let mut touched = false;
+
+        for section in DEPENDENCY_TABLES {
+            if let Some(deps) = doc.get_mut(section).and_then(|d| d.as_table_like_mut()) {
+                touched |= update_dependency_table(deps, &member_dir, crate_name, &crate_dir, new_version, manifest);
+            }
+        }
+        if let Some(deps) = doc
+            .get_mut("workspace")
+            .and_then(|w| w.get_mut("dependencies"))
+            .and_then(|d| d.as_table_like_mut())
+        {
+            touched |= update_dependency_table(deps, &member_dir, crate_name, &crate_dir, new_version, manifest);
+        }
+
+        if touched {
+            let original = fs::read_to_string(&*manifest).context(format!("Failed to read {}", manifest.display()))?;
+            let updated = with_original_line_endings(&original, &doc.to_string());
+            fs::write(&*manifest, updated).context(format!("Failed to write {}", manifest.display()))?;
+            changed.push(manifest.clone());
+        }
+    }
+
+    Ok(changed)
+}
+
 /// Get the path to Cargo.toml in the given directory
-pub fn cargo_toml_path(dir: &Path) -> std::path::PathBuf {
+pub fn cargo_toml_path(dir: &Path) -> PathBuf {
     dir.join("Cargo.toml")
 }
 
@@ -338,6 +848,85 @@ name = "test"
         assert_eq!(version, Some("2.0.0".to_string()));
     }
 
+    #[test]
+    fn test_read_and_write_version_inherited_from_parent_workspace() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let root = create_cargo_toml(
+            dir.path(),
+            "[workspace]\nmembers = [\"crates/app\"]\n\n[workspace.package]\nversion = \"3.1.0\"\n",
+        );
+        let member_dir = dir.path().join("crates/app");
+        fs::create_dir_all(&member_dir).unwrap();
+        let member = create_cargo_toml(&member_dir, "[package]\nname = \"app\"\nversion.workspace = true\n");
+
+        assert_eq!(find_workspace_root(&member_dir).unwrap().as_deref(), Some(dir.path()));
+        assert_eq!(version_manifest(&member).unwrap(), root);
+        assert_eq!(read_version(&member).unwrap().as_deref(), Some("3.1.0"));
+
+        write_version(&member, "3.2.0").unwrap();
+        assert_eq!(read_version(&root).unwrap().as_deref(), Some("3.2.0"));
+        assert!(fs::read_to_string(&member).unwrap().contains("version.workspace = true"));
+    }
+
+    #[test]
+    fn test_find_workspace_root_stops_at_repo_root() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(dir.path(), "[workspace]\nmembers = [\"repo\"]\n");
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        let member = create_cargo_toml(&repo, "[package]\nname = \"app\"\nversion.workspace = true\n");
+
+        assert_eq!(find_workspace_root(&repo).unwrap(), None);
+        assert_eq!(version_manifest(&member).unwrap(), member);
+        assert_eq!(read_version(&member).unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_version_preserves_crlf_and_final_newline() {
+        let crlf = "[package]\r\nname = \"app\"\r\nversion = \"1.0.0\"\r\n";
+        assert_eq!(set_version(crlf, "1.0.1").unwrap(), crlf.replace("1.0.0", "1.0.1"));
+
+        let no_newline = "[package]\nname = \"app\"\nversion = \"1.0.0\"";
+        assert_eq!(set_version(no_newline, "1.0.1").unwrap(), no_newline.replace("1.0.0", "1.0.1"));
+
+        // A line added by the edit follows the file's style too
+        let created = set_version("[package]\r\nname = \"app\"\r\n", "0.1.0").unwrap();
+        assert_eq!(created, "[package]\r\nname = \"app\"\r\nversion = \"0.1.0\"\r\n");
+
+        let nested = set_version_at(crlf, "package.metadata.release.version", "2.0.0").unwrap();
+        assert!(!nested.replace("\r\n", "").contains('\n'), "MUST not mix in LF endings: {:?}", nested);
+    }
+
+    #[test]
+    fn test_read_package_name() {
+        let dir = TempDir::new().unwrap();
+        let path = create_cargo_toml(
+            dir.path(),
+            r#"
+[package]
+name = "mycrate"
+version = "1.2.3"
+"#,
+        );
+
+        assert_eq!(read_package_name(&path).unwrap(), Some("mycrate".to_string()));
+    }
+
+    #[test]
+    fn test_read_package_name_workspace_only() {
+        let dir = TempDir::new().unwrap();
+        let path = create_cargo_toml(
+            dir.path(),
+            r#"
+[workspace]
+members = ["crate-a"]
+"#,
+        );
+
+        assert_eq!(read_package_name(&path).unwrap(), None);
+    }
+
     #[test]
     fn test_write_version() {
         let dir = TempDir::new().unwrap();
@@ -477,6 +1066,182 @@ serde = "1.0"
         assert!(!content.contains("\n[package]"), "Should not create [package] section");
     }
 
+    #[test]
+    fn test_sync_lockfile_works_offline() {
+        let dir = TempDir::new().unwrap();
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(&manifest, "[package]\nname = \"locked\"\nversion = \"0.1.0\"\nedition = \"2021\"\n").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        fs::write(
+            dir.path().join("Cargo.lock"),
+            "version = 4\n\n[[package]]\nname = \"locked\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        write_version(&manifest, "0.2.0").unwrap();
+        sync_lockfile(dir.path()).unwrap();
+
+        let lock = fs::read_to_string(dir.path().join("Cargo.lock")).unwrap();
+        assert!(lock.contains("version = \"0.2.0\""), "{}", lock);
+    }
+
+    #[test]
+    fn test_sync_lockfile_only_changes_bumped_package() {
+        let dir = TempDir::new().unwrap();
+        let helper = dir.path().join("helper");
+        fs::create_dir_all(helper.join("src")).unwrap();
+        fs::write(helper.join("Cargo.toml"), "[package]\nname = \"helper\"\nversion = \"0.3.0\"\n").unwrap();
+        fs::write(helper.join("src/lib.rs"), "").unwrap();
+
+        let manifest = dir.path().join("Cargo.toml");
+        fs::write(
+            &manifest,
+            "[package]\nname = \"app\"\nversion = \"1.0.0\"\n\n[dependencies]\nhelper = { path = \"helper\" }\n\n\
+             [workspace]\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        let status = std::process::Command::new("cargo")
+            .args(["generate-lockfile", "--offline"])
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let before = fs::read_to_string(dir.path().join("Cargo.lock")).unwrap();
+
+        check_lockfile_sync(dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("Cargo.lock")).unwrap(), before, "MUST not write in a check");
+
+        write_version(&manifest, "1.1.0").unwrap();
+        sync_lockfile(dir.path()).unwrap();
+
+        let after = fs::read_to_string(dir.path().join("Cargo.lock")).unwrap();
+        let changed: Vec<(&str, &str)> = before.lines().zip(after.lines()).filter(|(b, a)| b != a).collect();
+        assert_eq!(changed, vec![("version = \"1.0.0\"", "version = \"1.1.0\"")]);
+        assert_eq!(before.lines().count(), after.lines().count());
+    }
+
+    #[test]
+    fn test_check_lockfile_sync_reports_broken_manifest() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"1.0.0\"\n\n[dependencies]\nmissing = { path = \"missing\" }\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "version = 4\n").unwrap();
+
+        let err = check_lockfile_sync(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("cargo update -p app --dry-run failed"), "{}", err);
+    }
+
+    #[test]
+    fn test_shared_version_members_skips_unpublished_and_independent() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(
+            dir.path(),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.2.0\"\n",
+        );
+        let members = [
+            ("core", "version.workspace = true\n"),
+            ("cli", "version = { workspace = true }\npublish = true\n"),
+            ("xtask", "version.workspace = true\npublish = false\n"),
+            ("internal", "version.workspace = true\npublish = []\n"),
+            ("plugin", "version = \"0.3.0\"\n"),
+        ];
+        for (name, fields) in members {
+            let manifest = format!("[package]\nname = \"{}\"\n{}", name, fields);
+            create_member_cargo_toml(dir.path(), &format!("crates/{}", name), &manifest);
+        }
+
+        assert_eq!(shared_version_members(dir.path(), false).unwrap(), vec!["cli", "core"]);
+        assert_eq!(shared_version_members(dir.path(), true).unwrap(), vec!["cli", "core", "internal", "xtask"]);
+    }
+
+    #[test]
+    fn test_read_version_at_nested_table() {
+        let dir = TempDir::new().unwrap();
+        let path = create_cargo_toml(
+            dir.path(),
+            r#"[package]
+name = "app"
+version = "0.0.0"
+
+[package.metadata.release]
+version = "3.1.4"
+"#,
+        );
+
+        assert_eq!(read_version_at(&path, "package.metadata.release.version").unwrap().as_deref(), Some("3.1.4"));
+        assert_eq!(read_version_at(&path, "package.metadata.other.version").unwrap(), None);
+        assert!(read_version_at(&path, "package.metadata").is_err());
+    }
+
+    #[test]
+    fn test_write_version_at_nested_and_dotted_tables() {
+        let dir = TempDir::new().unwrap();
+        let path = create_cargo_toml(
+            dir.path(),
+            r#"[package]
+name = "app"
+version = "0.0.0"
+metadata.release.version = "3.1.4"
+"#,
+        );
+
+        write_version_at(&path, "package.metadata.release.version", "3.2.0").unwrap();
+        assert_eq!(read_version_at(&path, "package.metadata.release.version").unwrap().as_deref(), Some("3.2.0"));
+        assert_eq!(read_version(&path).unwrap().as_deref(), Some("0.0.0"));
+
+        // Missing tables are created
+        write_version_at(&path, "package.metadata.deploy.version", "1.0.0").unwrap();
+        assert_eq!(read_version_at(&path, "package.metadata.deploy.version").unwrap().as_deref(), Some("1.0.0"));
+
+        assert!(write_version_at(&path, "package.name.version", "1.0.0").is_err());
+    }
+
+    #[test]
+    fn test_is_network_failure() {
+        assert!(is_network_failure(
+            "error: failed to get `serde` as a dependency\n\nCaused by:\n  [6] Couldn't resolve host name"
+        ));
+        assert!(is_network_failure("warning: spurious network error (2 tries remaining): [28] Timeout was reached"));
+        assert!(!is_network_failure("error: failed to parse manifest at `/tmp/app/Cargo.toml`"));
+        assert!(!is_network_failure("error: package ID specification `nope` did not match any packages"));
+    }
+
+    #[test]
+    fn test_find_lockfile_alongside_manifest() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "").unwrap();
+        assert_eq!(find_lockfile(dir.path()), Some(dir.path().join("Cargo.lock")));
+    }
+
+    #[test]
+    fn test_find_lockfile_in_repo_root() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "").unwrap();
+        let app = dir.path().join("app");
+        fs::create_dir_all(&app).unwrap();
+
+        assert_eq!(find_lockfile(&app), Some(dir.path().join("Cargo.lock")));
+    }
+
+    #[test]
+    fn test_find_lockfile_stops_at_repo_root() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("Cargo.lock"), "").unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+
+        assert_eq!(find_lockfile(&repo), None);
+    }
+
     // Tests for check_workspace_independent_versions
 
     fn create_member_cargo_toml(dir: &Path, member_path: &str, content: &str) {
@@ -656,6 +1421,8 @@ version = "2.0.0"
 
         let result = check_workspace_independent_versions(dir.path()).unwrap();
         assert_eq!(result.len(), 2, "Should detect both members with independent versions");
+        assert!(!versions_consistent(&result));
+        assert!(versions_consistent(&result[..1]));
     }
 
     #[test]
@@ -939,4 +1706,157 @@ version.workspace = false
         let version = doc.get("package").unwrap().get("version").unwrap();
         assert!(!is_workspace_version(version), "workspace = false should NOT be detected as workspace version");
     }
+
+    #[test]
+    fn test_find_member_manifest() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(
+            dir.path(),
+            r#"
+[workspace]
+members = ["crate-a", "crates/*"]
+
+[workspace.package]
+version = "1.0.0"
+"#,
+        );
+        for (path, name, version) in [
+            ("crate-a", "crate-a", "version.workspace = true"),
+            ("crates/crate-b", "crate-b", "version = \"2.0.0\""),
+        ] {
+            fs::create_dir_all(dir.path().join(path)).unwrap();
+            create_cargo_toml(&dir.path().join(path), &format!("[package]\nname = \"{}\"\n{}\n", name, version));
+        }
+
+        let manifest = find_member_manifest(dir.path(), "crate-b").unwrap();
+        assert_eq!(manifest, dir.path().join("crates/crate-b/Cargo.toml"));
+
+        // Inherited versions are bumped through the workspace, not per member
+        assert!(find_member_manifest(dir.path(), "crate-a").is_err());
+        assert!(find_member_manifest(dir.path(), "crate-c").is_err());
+    }
+
+    #[test]
+    fn test_workspace_member_paths_globs_and_excludes() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(
+            dir.path(),
+            "[workspace]\nmembers = [\"crates/*\", \"tools/{gen,lint}\", \"ext/c?\"]\n\
+             exclude = [\"crates/legacy\"]\n",
+        );
+        for path in ["crates/core", "crates/cli", "crates/legacy", "tools/gen", "ext/c1", "ext/cxx"] {
+            fs::create_dir_all(dir.path().join(path)).unwrap();
+        }
+        fs::write(dir.path().join("crates/NOTES.md"), "not a crate").unwrap();
+
+        assert_eq!(
+            workspace_member_paths(dir.path()).unwrap(),
+            vec!["crates/cli", "crates/core", "tools/gen", "tools/lint", "ext/c1"]
+        );
+        assert_eq!(expand_braces("a/{b,c}/{d,e}"), vec!["a/b/d", "a/b/e", "a/c/d", "a/c/e"]);
+    }
+
+    #[test]
+    fn test_check_independent_versions_globbed_members() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(
+            dir.path(),
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/old\"]\n\n\
+             [workspace.package]\nversion = \"1.0.0\"\n",
+        );
+        let crates = [
+            ("core", "version = \"2.0.0\""),
+            ("cli", "version.workspace = true"),
+            ("old", "version = \"0.1.0\""),
+            ("tool", "version = \"0.2.0\"\npublish = false"),
+        ];
+        for (name, version) in crates {
+            let path = dir.path().join("crates").join(name);
+            fs::create_dir_all(&path).unwrap();
+            create_cargo_toml(&path, &format!("[package]\nname = \"{}\"\n{}\n", name, version));
+        }
+
+        let members = check_workspace_independent_versions(dir.path()).unwrap();
+        assert_eq!(members.len(), 2, "{:?}", members);
+        assert_eq!(members[0].name, "core");
+        assert_eq!(members[0].path, "crates/core");
+        assert!(members[0].publish);
+        assert_eq!(members[1].name, "tool");
+        assert!(!members[1].publish);
+    }
+
+    #[test]
+    fn test_updated_requirement_keeps_precision() {
+        assert_eq!(updated_requirement("1.2", "1.3.0").as_deref(), Some("1.3"));
+        assert_eq!(updated_requirement("^1.2.0", "1.3.0").as_deref(), Some("^1.3.0"));
+        assert_eq!(updated_requirement("=1", "2.0.0").as_deref(), Some("=2"));
+        assert_eq!(updated_requirement(">= 1.2", "1.3.0").as_deref(), Some(">= 1.3"));
+        assert_eq!(updated_requirement("1.2", "1.3.0-rc.1").as_deref(), Some("1.3.0-rc.1"));
+    }
+
+    #[test]
+    fn test_updated_requirement_leaves_ranges_alone() {
+        assert_eq!(updated_requirement(">=1.0, <2.0", "1.3.0"), None);
+        assert_eq!(updated_requirement("<2.0", "1.3.0"), None);
+        assert_eq!(updated_requirement("1.*", "1.3.0"), None);
+        assert_eq!(updated_requirement("not a requirement", "1.3.0"), None);
+    }
+
+    #[test]
+    fn test_update_internal_dependents() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(dir.path(), "[workspace]\nmembers = [\"core\", \"app\", \"other\"]\n");
+        let manifests = [
+            ("core", "[package]\nname = \"crate-core\"\nversion = \"1.3.0\"\n"),
+            (
+                "app",
+                "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\n\
+                 crate-core = { path = \"../core\", version = \"1.2\", features = [\"x\"] }\n",
+            ),
+            // Same crate name from the registry (no path) must be left alone
+            (
+                "other",
+                "[package]\nname = \"other\"\nversion = \"0.1.0\"\n\n[dependencies]\n\
+                 crate-core = { version = \"1.2\" }\n",
+            ),
+        ];
+        for (path, content) in manifests {
+            fs::create_dir_all(dir.path().join(path)).unwrap();
+            create_cargo_toml(&dir.path().join(path), content);
+        }
+
+        let changed = update_internal_dependents(dir.path(), "crate-core", "1.3.0").unwrap();
+        assert_eq!(changed, vec![dir.path().join("app/Cargo.toml")]);
+
+        let app = fs::read_to_string(dir.path().join("app/Cargo.toml")).unwrap();
+        assert!(app.contains(r#"crate-core = { path = "../core", version = "1.3", features = ["x"] }"#), "{}", app);
+        let other = fs::read_to_string(dir.path().join("other/Cargo.toml")).unwrap();
+        assert!(other.contains(r#"version = "1.2""#));
+    }
+
+    #[test]
+    fn test_update_internal_dependents_workspace_dependencies() {
+        let dir = TempDir::new().unwrap();
+        create_cargo_toml(
+            dir.path(),
+            "[workspace]\nmembers = [\"core\", \"app\"]\n\n[workspace.dependencies]\n\
+             crate-core = { path = \"core\", version = \"1.2\" }\n",
+        );
+        fs::create_dir_all(dir.path().join("core")).unwrap();
+        create_cargo_toml(&dir.path().join("core"), "[package]\nname = \"crate-core\"\nversion = \"1.3.0\"\n");
+        fs::create_dir_all(dir.path().join("app")).unwrap();
+        create_cargo_toml(
+            &dir.path().join("app"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\n\
+             crate-core = { path = \"../core\", version = \">=1.0, <2.0\" }\n",
+        );
+
+        let changed = update_internal_dependents(dir.path(), "crate-core", "1.3.0").unwrap();
+        assert_eq!(changed, vec![dir.path().join("Cargo.toml")]);
+
+        let root = fs::read_to_string(dir.path().join("Cargo.toml")).unwrap();
+        assert!(root.contains(r#"crate-core = { path = "core", version = "1.3" }"#), "{}", root);
+        let app = fs::read_to_string(dir.path().join("app/Cargo.toml")).unwrap();
+        assert!(app.contains(r#"version = ">=1.0, <2.0""#), "MUST leave a compound range alone: {}", app);
+    }
 }
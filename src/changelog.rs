@@ -0,0 +1,106 @@
+use eyre::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The changelog file updated by --changelog
+const CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Section headings, keyed by Conventional Commits type; anything else lands in "Other"
+const GROUPS: &[(&str, &str)] = &[("feat", "Features"), ("fix", "Fixes")];
+
+/// Get the path to CHANGELOG.md in the given directory
+pub fn changelog_path(dir: &Path) -> PathBuf {
+    dir.join(CHANGELOG_FILE)
+}
+
+/// Group commit subjects under headings by their Conventional Commits type
+fn group_commits(subjects: &[String]) -> Vec<(&'static str, Vec<&str>)> {
+    let mut groups: Vec<(&'static str, Vec<&str>)> =
+        GROUPS.iter().map(|(_, heading)| (*heading, Vec::new())).collect();
+    groups.push(("Other", Vec::new()));
+
+    for subject in subjects {
+        let kind = subject
+            .split_once(':')
+            .map(|(header, _)| header.split(['(', '!']).next().unwrap_or(header).trim())
+            .unwrap_or("");
+        let index = GROUPS.iter().position(|(k, _)| *k == kind).unwrap_or(GROUPS.len());
+        groups[index].1.push(subject);
+    }
+
+    groups.retain(|(_, entries)| !entries.is_empty());
+    groups
+}
+
+/// Render a `## <tag> (<date>)` section listing the grouped commits
+fn render_section(tag: &str, date: &str, subjects: &[String]) -> String {
+    let mut section = format!("## {} ({})\n", tag, date);
+    for (heading, entries) in group_commits(subjects) {
+        section.push_str(&format!("\n### {}\n\n", heading));
+        for entry in entries {
+            section.push_str(&format!("- {}\n", entry));
+        }
+    }
+    section
+}
+
+/// Prepend a section for this release to CHANGELOG.md, keeping a leading `# ` title at the top
+pub fn prepend_section(dir: &Path, tag: &str, date: &str, subjects: &[String]) -> Result<()> {
+    let path = changelog_path(dir);
+    let existing = if path.exists() {
+        fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+
+    let section = render_section(tag, date, subjects);
+    let content = match existing.strip_prefix("# ").and_then(|rest| rest.split_once('\n')) {
+        Some((title, rest)) => format!("# {}\n\n{}\n{}", title, section, rest.trim_start_matches('\n')),
+        None if existing.is_empty() => section,
+        None => format!("{}\n{}", section, existing),
+    };
+
+    fs::write(&path, content).context(format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_group_commits() {
+        let subjects: Vec<String> = ["feat(cli): add flag", "fix: crash", "docs: readme", "feat!: breaking"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let groups = group_commits(&subjects);
+        assert_eq!(
+            groups,
+            vec![
+                ("Features", vec!["feat(cli): add flag", "feat!: breaking"]),
+                ("Fixes", vec!["fix: crash"]),
+                ("Other", vec!["docs: readme"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prepend_section_keeps_title_and_history() {
+        let dir = TempDir::new().unwrap();
+        let path = changelog_path(dir.path());
+        fs::write(&path, "# Changelog\n\n## v1.0.0 (2024-01-01)\n\n### Other\n\n- Initial\n").unwrap();
+
+        prepend_section(dir.path(), "v1.1.0", "2024-02-01", &["feat: new".to_string()]).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# Changelog\n\n## v1.1.0 (2024-02-01)\n\n### Features\n\n- feat: new\n\n\
+             ## v1.0.0 (2024-01-01)\n\n### Other\n\n- Initial\n"
+        );
+    }
+}
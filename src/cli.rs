@@ -1,16 +1,29 @@
-use clap::Parser;
+use clap::parser::ValueSource;
+use clap::{ArgGroup, Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use eyre::{Result, bail};
+use std::ffi::OsString;
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::LazyLock;
 
+use crate::cargo;
+use crate::git;
+use crate::output::ColorChoice;
+use crate::readme;
+use crate::version::{self, BumpType, PreKind, VersionScheme};
+
 static HELP_TEXT: LazyLock<String> = LazyLock::new(get_tool_validation_help);
 
-#[derive(Parser)]
+#[derive(Parser, Clone)]
 #[command(
     name = "bump",
     about = "bump semantic versions in Cargo.toml, commit, and tag",
     version = env!("GIT_DESCRIBE"),
-    after_help = HELP_TEXT.as_str()
+    after_help = HELP_TEXT.as_str(),
+    group(ArgGroup::new("tag_existing").args(["tag_only", "git_ref"]).multiple(true)),
+    group(ArgGroup::new("per_member").args(["all_members", "tag_per_member"])),
+    group(ArgGroup::new("version_source").args(["version_file", "version_key"]))
 )]
 pub struct Cli {
     /// Bump major version (X.0.0)
@@ -21,35 +34,624 @@ pub struct Cli {
     #[arg(short = 'm', long, conflicts_with = "major")]
     pub minor: bool,
 
+    /// Bump patch version (x.y.Z); the default, spelled out for scripts (-p is --package)
+    #[arg(
+        short = 'P',
+        long,
+        conflicts_with_all = ["major", "minor", "pre", "revision", "set", "release", "infer_from", "auto", "calver"]
+    )]
+    pub patch: bool,
+
+    /// Set an explicit target version instead of bumping
+    #[arg(long, value_name = "VERSION", conflicts_with_all = ["major", "minor", "pre", "infer_from"])]
+    pub set: Option<String>,
+
+    /// Allow a new version at or below the latest tag (bumps from Cargo.toml when it disagrees with the tags)
+    #[arg(long)]
+    pub allow_downgrade: bool,
+
+    /// Bump from the latest git tag even when Cargo.toml has a (different) version, then write it back
+    #[arg(long, conflicts_with_all = ["set", "tag_only"])]
+    pub from_tag: bool,
+
+    /// Version to start at when there is no version anywhere (default: 0.1.0)
+    #[arg(long, value_name = "VERSION")]
+    pub initial: Option<String>,
+
+    /// Advance the bumped segment by N instead of one (e.g. --by 5: 1.2.3 → 1.2.8)
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["pre", "revision", "set", "release", "calver"]
+    )]
+    pub by: u64,
+
+    /// Bump to a pre-release (x.y.Z-rc.1, or advance rc.1 → rc.2)
+    #[arg(long, value_name = "KIND", conflicts_with_all = ["major", "minor"])]
+    pub pre: Option<PreKind>,
+
+    /// Bump the fourth segment of a MAJOR.MINOR.PATCH.REVISION version (1.2.3.4 → 1.2.3.5, 1.2.3 → 1.2.3.1)
+    /// Cargo rejects four segments, so this needs --version-key or --version-file
+    #[arg(
+        long,
+        requires = "version_source",
+        conflicts_with_all = ["major", "minor", "pre", "set", "release", "infer_from", "auto", "calver"]
+    )]
+    pub revision: bool,
+
+    /// Attach build metadata to the new version and tag (e.g. ci.42 → v1.2.3+ci.42)
+    #[arg(long, value_name = "META", conflicts_with_all = ["revision", "tag_only"])]
+    pub build: Option<String>,
+
+    /// Promote a pre-release to its stable version (2.0.0-rc.3 → 2.0.0)
+    #[arg(long, conflicts_with_all = ["major", "minor", "pre", "set"])]
+    pub release: bool,
+
+    /// Infer the bump type from a conventional-commit subject (e.g. a PR title)
+    #[arg(long, value_name = "STRING", conflicts_with_all = ["major", "minor", "pre", "release"])]
+    pub infer_from: Option<String>,
+
+    /// Pick the bump type from the Conventional Commits since the last tag
+    #[arg(long, conflicts_with_all = ["major", "minor", "pre", "set", "release", "infer_from"])]
+    pub auto: bool,
+
+    /// Calendar versioning (YYYY.M.N): bump to this month, counting releases within it
+    #[arg(long, conflicts_with_all = ["major", "minor", "pre", "infer_from", "auto"])]
+    pub calver: bool,
+
+    /// Print the next version and exit without touching files or git
+    #[arg(long, conflicts_with_all = ["tag_only", "probe"])]
+    pub print_version: bool,
+
+    /// With --print-version, print the full tag (e.g. v1.2.3) instead of the version
+    #[arg(long, requires = "print_version")]
+    pub tag: bool,
+
+    /// Check prerequisites without changing anything; exit 0 if bump can run
+    #[arg(long)]
+    pub probe: bool,
+
     /// Preview changes without applying
     #[arg(short = 'n', long)]
     pub dry_run: bool,
 
     /// Commit message to use
-    #[arg(long, conflicts_with = "automatic")]
+    #[arg(long, conflicts_with_all = ["automatic", "message_file"])]
     pub message: Option<String>,
 
+    /// Read the commit message from a file
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["message", "automatic"])]
+    pub message_file: Option<PathBuf>,
+
+    /// Add this body (e.g. release notes) to the commit after the subject line
+    #[arg(long, value_name = "STR", conflicts_with_all = ["commit_body_file", "no_commit", "tag_only", "git_ref"])]
+    pub commit_body: Option<String>,
+
+    /// Read the commit body from a file
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["commit_body", "no_commit", "tag_only", "git_ref"])]
+    pub commit_body_file: Option<PathBuf>,
+
+    /// Never prompt: use the default commit message and patch bump instead of asking
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+
     /// Generate automatic commit message
-    #[arg(short = 'a', long, conflicts_with = "message")]
+    #[arg(short = 'a', long, conflicts_with_all = ["message", "message_file"])]
     pub automatic: bool,
 
-    /// Paths to git repository roots
+    /// Only bump on this branch (default: the remote's default branch)
+    #[arg(long, value_name = "NAME", num_args = 0..=1)]
+    pub branch: Option<Option<String>>,
+
+    /// Don't sync Cargo.lock after writing the new version (cargo update may need the network)
+    #[arg(long)]
+    pub no_lock: bool,
+
+    /// Retry cargo update this many times, with backoff, when the registry can't be reached
+    #[arg(long, value_name = "N", default_value_t = cargo::DEFAULT_LOCK_RETRIES, conflicts_with = "no_lock")]
+    pub lock_retries: u32,
+
+    /// Fetch tags from the remote first, so tags teammates pushed count as existing
+    #[arg(long)]
+    pub fetch_tags: bool,
+
+    /// In a shallow clone, fetch the full history and tags first (instead of only warning)
+    #[arg(long)]
+    pub unshallow: bool,
+
+    /// Fetch and compare HEAD with its upstream first: strict (the default) refuses when behind, warn only warns
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "strict"
+    )]
+    pub check_remote: Option<RemoteCheck>,
+
+    /// Author of the release commit, as "Name <email>" (default: the repo's configured identity)
+    #[arg(long, value_name = "AUTHOR")]
+    pub author: Option<String>,
+
+    /// Skip git hooks (pre-commit, commit-msg, pre-push) when committing and pushing
+    #[arg(long)]
+    pub no_verify: bool,
+
+    /// Fold the release into HEAD (git commit --amend) instead of a new commit; HEAD must be untagged and unpushed
+    #[arg(long, conflicts_with_all = ["tag_only", "no_commit", "package"])]
+    pub amend: bool,
+
+    /// Update and stage the version files without committing or tagging
+    #[arg(long, conflicts_with_all = ["tag_only", "no_tag", "push", "verify_clean_after"])]
+    pub no_commit: bool,
+
+    /// Bump and commit without creating a tag (e.g. when CI tags after merge)
+    #[arg(long, conflicts_with = "tag_only")]
+    pub no_tag: bool,
+
+    /// If tagging fails, undo the release commit and restore the version files
+    #[arg(long)]
+    pub rollback_on_failure: bool,
+
+    /// Create a GPG-signed tag (git tag -s)
+    #[arg(short = 's', long)]
+    pub sign: bool,
+
+    /// Don't sign the tag, even if the config sets sign = true
+    #[arg(long, conflicts_with_all = ["sign", "local_user"])]
+    pub no_sign: bool,
+
+    /// Sign the tag with this key (git tag -u <KEYID>)
+    #[arg(long, value_name = "KEYID")]
+    pub local_user: Option<String>,
+
+    /// Create a lightweight tag (no annotation); the message is only used for the commit
+    #[arg(long, conflicts_with_all = ["sign", "local_user", "tag_template", "stamp"])]
+    pub lightweight: bool,
+
+    /// Prepend the commits since the last tag to CHANGELOG.md as part of the release commit
+    #[arg(long, conflicts_with = "tag_only")]
+    pub changelog: bool,
+
+    /// Replace the old version with the new one in README.md (badges, dependency snippets) in the release commit
+    #[arg(long, conflicts_with_all = ["tag_only", "package"])]
+    pub update_readme: bool,
+
+    /// Set the version in package.json beside Cargo.toml to match, if there is one
+    #[arg(long, conflicts_with_all = ["tag_only", "package", "all_members"])]
+    pub sync_npm: bool,
+
+    /// Set the version in pyproject.toml beside Cargo.toml ([project] or [tool.poetry]) to match, if there is one
+    #[arg(long, conflicts_with_all = ["tag_only", "package", "all_members"])]
+    pub sync_pyproject: bool,
+
+    /// Where the version appears in README.md, e.g. 'mycrate@{version}' ({name} is the package); repeatable
+    #[arg(long, value_name = "TEMPLATE", requires = "update_readme")]
+    pub readme_template: Vec<String>,
+
+    /// What to stage for the release commit: all changes, only tracked files, or only Cargo.toml/Cargo.lock
+    #[arg(long, value_enum, value_name = "WHAT", default_value_t = git::StageMode::All)]
+    pub stage: git::StageMode,
+
+    /// Push the commit and this release's tags after tagging
+    #[arg(long)]
+    pub push: bool,
+
+    /// Don't push, even if the config sets push = true
+    #[arg(long, conflicts_with_all = ["push", "push_tags"])]
+    pub no_push: bool,
+
+    /// Push only this release's tags after tagging, leaving the commit for CI to push (works with --tag-only)
+    #[arg(long, conflicts_with_all = ["push", "no_tag", "no_commit"])]
+    pub push_tags: bool,
+
+    /// Don't print the "Run: git push" reminders (never printed with --push)
+    #[arg(long)]
+    pub no_reminder: bool,
+
+    /// Remote used by --push/--push-tags and for detecting the default branch
+    #[arg(long, value_name = "NAME", default_value = "origin")]
+    pub remote: String,
+
+    /// Include unrelated uncommitted changes in the release commit instead of refusing
+    #[arg(long)]
+    pub allow_dirty: bool,
+
+    /// Only changes matching this glob (e.g. 'src/**') make the tree dirty; repeatable (others stay uncommitted)
+    #[arg(long, value_name = "GLOB", conflicts_with = "allow_dirty")]
+    pub dirty_files: Vec<String>,
+
+    /// Fail if anything is left uncommitted after tagging
+    #[arg(long)]
+    pub verify_clean_after: bool,
+
+    /// Path to the Cargo.toml to bump (relative to each directory, like cargo's); git runs at its repo root
+    #[arg(long, value_name = "PATH")]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Bump only these workspace members, each with its own version and tag (e.g. crate-b-v2.0.1)
+    #[arg(short = 'p', long = "package", value_name = "NAME", conflicts_with_all = ["tag_only", "auto_namespace_tags"])]
+    pub package: Vec<String>,
+
+    /// Bump every workspace member with its own version, each tagged separately (inheriting members are skipped)
+    #[arg(
+        long,
+        conflicts_with_all = ["package", "tag_only", "auto_namespace_tags", "amend", "version_key", "update_readme"]
+    )]
+    pub all_members: bool,
+
+    /// Also tag each published member sharing the workspace version, named after it (e.g. core-v1.2.0)
+    #[arg(long, conflicts_with_all = ["package", "all_members", "no_tag", "tag_only"])]
+    pub tag_per_member: bool,
+
+    /// With --all-members or --tag-per-member, also release members marked `publish = false`
+    #[arg(long, requires = "per_member")]
+    pub include_unpublished: bool,
+
+    /// Refuse a workspace bump when members with their own version don't all share one
+    #[arg(long, conflicts_with_all = ["package", "all_members"])]
+    pub require_consistent: bool,
+
+    /// Use a plain VERSION file (e.g. 1.2.3) when there is no Cargo.toml
+    #[arg(long, conflicts_with_all = ["tag_only", "package"])]
+    pub version_file: bool,
+
+    /// Read and write the version at this dotted key in Cargo.toml (e.g. package.metadata.release.version)
+    #[arg(long, value_name = "KEY", conflicts_with_all = ["tag_only", "package", "version_file"])]
+    pub version_key: Option<String>,
+
+    /// Prefix for version tags (default: v; e.g. release- for release-1.2.3; empty for bare 1.2.3)
+    #[arg(long, value_name = "STR")]
+    pub tag_prefix: Option<String>,
+
+    /// Prefix tags with the package name (e.g. mycrate-v1.2.3)
+    #[arg(long)]
+    pub auto_namespace_tags: bool,
+
+    /// Tag HEAD at the current Cargo.toml version without editing or committing
+    #[arg(long)]
+    pub tag_only: bool,
+
+    /// Tag this commit instead of HEAD, at the version in its Cargo.toml (implies --tag-only)
+    #[arg(
+        long = "ref",
+        value_name = "COMMITISH",
+        conflicts_with_all = ["set", "no_tag", "amend", "package", "all_members", "tag_per_member"]
+    )]
+    pub git_ref: Option<String>,
+
+    /// With --tag-only or --ref, move an existing tag (only if the tagged commit's Cargo.toml matches)
+    #[arg(long, requires = "tag_existing")]
+    pub force: bool,
+
+    /// With --force, skip the check that HEAD's Cargo.toml matches the tag
+    #[arg(long, requires = "force")]
+    pub force_unsafe: bool,
+
+    /// Delete this tag instead of releasing (also from the remote, only if --remote is given)
+    #[arg(
+        long,
+        value_name = "TAG",
+        conflicts_with_all = ["tag_only", "git_ref", "no_tag", "print_version", "probe", "push", "push_tags"]
+    )]
+    pub delete_tag: Option<String>,
+
+    /// Print a JSON array summarizing each directory's result on stdout, instead of the tags
+    #[arg(long)]
+    pub json: bool,
+
+    /// Append a JSON line describing each release to this file
+    #[arg(long, value_name = "FILE")]
+    pub ledger: Option<PathBuf>,
+
+    /// Prompt for the bump type (set at startup when no bump flag is given on a TTY)
+    #[arg(skip)]
+    pub interactive: bool,
+
+    /// --remote was given on the command line (--delete-tag only touches a remote named explicitly)
+    #[arg(skip)]
+    pub remote_given: bool,
+
+    /// Oldest git this repository allows (from config; default MIN_GIT_VERSION)
+    #[arg(skip)]
+    pub min_git_version: Option<String>,
+
+    /// Ask before committing (set at startup on a TTY unless --yes or --dry-run is given)
+    #[arg(skip)]
+    pub confirm: bool,
+
+    /// Template for generated commit messages: {version}, {tag}, {previous} and {date} are substituted
+    #[arg(long, value_name = "STR")]
+    pub commit_template: Option<String>,
+
+    /// Template for the tag annotation, with the same placeholders (default: reuse the commit message)
+    #[arg(long, value_name = "STR")]
+    pub tag_template: Option<String>,
+
+    /// End the tag annotation with the date and releasing user (the commit message is left as is)
+    #[arg(long)]
+    pub stamp: bool,
+
+    /// Shell command to run before any files are changed; a non-zero exit aborts the bump
+    #[arg(long, value_name = "CMD")]
+    pub pre_hook: Option<String>,
+
+    /// Shell command to run after the release is tagged
+    #[arg(long, value_name = "CMD")]
+    pub post_hook: Option<String>,
+
+    /// Treat the directories as roots and bump every git repo with a Cargo.toml below them
+    #[arg(short = 'r', long)]
+    pub recursive: bool,
+
+    /// With --recursive, how many directory levels to search below each root
+    #[arg(long, value_name = "N", requires = "recursive")]
+    pub max_depth: Option<usize>,
+
+    /// Process up to N directories at once (output is grouped per directory; prompts are disabled)
+    #[arg(short = 'j', long, value_name = "N", default_value_t = 1)]
+    pub jobs: usize,
+
+    /// Echo the detailed log to stderr as well as the log file
+    #[arg(short = 'v', long, conflicts_with = "quiet")]
+    pub verbose: bool,
+
+    /// Only print errors and the result on stdout (no status lines)
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+
+    /// Write the log file as plain text or as one JSON object per line
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+
+    /// Write the log here instead of ~/.local/share/bump/logs/bump.log
+    #[arg(long, value_name = "PATH", conflicts_with = "no_log")]
+    pub log_file: Option<PathBuf>,
+
+    /// Don't write a log file (--verbose still echoes the log to stderr)
+    #[arg(long)]
+    pub no_log: bool,
+
+    /// Color status lines: auto (on a terminal, unless NO_COLOR is set), always or never
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Run tagging, staging and committing through the git binary or in-process with libgit2
+    #[arg(long, value_enum, value_name = "BACKEND", default_value_t = git::Backend::Git)]
+    pub backend: git::Backend,
+
+    /// Print a completion script for the shell to stdout and exit
+    #[arg(long, value_name = "SHELL")]
+    pub completions: Option<Shell>,
+
+    /// Paths to git repository roots (write ./status or -- status for a directory named like a subcommand)
+    #[arg(value_name = "DIRECTORIES")]
+    pub directories: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<BumpCommand>,
+}
+
+/// How log entries are written (--log-format)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// How --check-remote reacts when HEAD is behind its upstream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RemoteCheck {
+    Warn,
+    Strict,
+}
+
+/// Subcommands; with none, bump releases the given directories
+#[derive(Subcommand, Debug, Clone)]
+pub enum BumpCommand {
+    /// Tag the current Cargo.toml version as the first release, with no bump and no commit
+    Init(InitArgs),
+    /// Revert the last release: delete its tag, drop the release commit, restore the version
+    Undo(UndoArgs),
+    /// Show the version, latest tag, commits since it and working tree state (changes nothing)
+    Status(StatusArgs),
+    /// Write a version to Cargo.toml (and sync Cargo.lock) with no git at all: no stage, commit or tag
+    SetVersion(SetVersionArgs),
+}
+
+/// Arguments for `bump init`
+#[derive(Args, Debug, Clone)]
+pub struct InitArgs {
+    /// Prefix for the tag (default: the configured prefix, or "v")
+    #[arg(long, value_name = "STR")]
+    pub tag_prefix: Option<String>,
+
+    /// Tag message (default: "Release <tag>")
+    #[arg(short = 'm', long, value_name = "MSG")]
+    pub message: Option<String>,
+
+    /// Show the tag that would be created without creating it
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// Path to the git repository root (default: current directory)
+    #[arg(value_name = "DIRECTORY")]
+    pub directory: Option<PathBuf>,
+}
+
+/// Arguments for `bump undo`
+#[derive(Args, Debug, Clone)]
+pub struct UndoArgs {
+    /// Undo even if the tag has already been pushed, or the remote can't be checked
+    #[arg(long)]
+    pub force: bool,
+
+    /// Prefix of the release tags to look for
+    #[arg(long, value_name = "STR", default_value = version::DEFAULT_TAG_PREFIX)]
+    pub tag_prefix: String,
+
+    /// Remote checked for an already-pushed tag
+    #[arg(long, value_name = "NAME", default_value = "origin")]
+    pub remote: String,
+
+    /// Path to the git repository root (default: current directory)
+    #[arg(value_name = "DIRECTORY")]
+    pub directory: Option<PathBuf>,
+}
+
+/// Arguments for `bump status`
+#[derive(Args, Debug, Clone)]
+pub struct StatusArgs {
+    /// Prefix of the release tags to look for
+    #[arg(long, value_name = "STR", default_value = version::DEFAULT_TAG_PREFIX)]
+    pub tag_prefix: String,
+
+    /// Paths to git repository roots (default: current directory)
     #[arg(value_name = "DIRECTORIES")]
     pub directories: Vec<PathBuf>,
 }
 
+/// Arguments for `bump set-version`
+#[derive(Args, Debug, Clone)]
+pub struct SetVersionArgs {
+    /// The version to write (e.g. 1.4.0)
+    #[arg(value_name = "VERSION")]
+    pub version: String,
+
+    /// Set only these workspace members, and update sibling path-dependency requirements on them
+    #[arg(short = 'p', long = "package", value_name = "NAME")]
+    pub package: Vec<String>,
+
+    /// Don't sync Cargo.lock after writing the version
+    #[arg(long)]
+    pub no_lock: bool,
+
+    /// Directory holding the Cargo.toml, or the workspace root with --package (default: current directory)
+    #[arg(value_name = "DIRECTORY")]
+    pub directory: Option<PathBuf>,
+}
+
+impl Cli {
+    /// The README templates to update (--readme-template, or the defaults)
+    pub fn readme_templates(&self) -> Vec<String> {
+        if self.readme_template.is_empty() {
+            readme::DEFAULT_TEMPLATES.iter().map(|t| t.to_string()).collect()
+        } else {
+            self.readme_template.clone()
+        }
+    }
+
+    /// The tag prefix to use (--tag-prefix, config, or the default `v`)
+    pub fn tag_prefix(&self) -> &str {
+        self.tag_prefix.as_deref().unwrap_or(version::DEFAULT_TAG_PREFIX)
+    }
+
+    /// The oldest git allowed (config min-git-version, or MIN_GIT_VERSION)
+    pub fn min_git_version(&self) -> &str {
+        self.min_git_version.as_deref().unwrap_or(MIN_GIT_VERSION)
+    }
+
+    /// Whether to remind the user to push afterwards: not when bump pushes, or with --no-reminder
+    pub fn push_reminder(&self) -> bool {
+        !self.push && !self.no_reminder
+    }
+
+    /// The bump type selected by the flags (patch when none is given)
+    pub fn bump_type(&self) -> BumpType {
+        match self.infer_from {
+            Some(ref subject) => version::infer_bump_type_from_subject(subject),
+            None => BumpType::from_cli(self.major, self.minor, self.patch, self.pre, self.revision),
+        }
+    }
+
+    /// The versioning scheme (--calver, otherwise SemVer)
+    pub fn version_scheme(&self) -> VersionScheme {
+        if self.calver { VersionScheme::CalVer } else { VersionScheme::SemVer }
+    }
+
+    /// Whether any flag chose how the version moves (otherwise bump may ask interactively)
+    pub fn has_bump_selection(&self) -> bool {
+        self.major
+            || self.minor
+            || self.patch
+            || self.pre.is_some()
+            || self.revision
+            || self.set.is_some()
+            || self.release
+            || self.infer_from.is_some()
+            || self.auto
+            || self.calver
+    }
+}
+
+/// Parse the command line, noting which flags were given explicitly rather than defaulted
+pub fn parse_from<I, T>(args: I) -> Result<Cli, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = Cli::command().try_get_matches_from(args)?;
+    let mut cli = Cli::from_arg_matches(&matches)?;
+    cli.remote_given = matches.value_source("remote") == Some(ValueSource::CommandLine);
+    Ok(cli)
+}
+
+/// Oldest git bump supports; a config's min-git-version can raise it
+pub const MIN_GIT_VERSION: &str = "2.20.0";
+
+/// Tools bump shells out to, with the minimum version each must meet
+const REQUIRED_TOOLS: &[(&str, &str)] = &[("git", MIN_GIT_VERSION), ("cargo", "1.70.0")];
+
 /// Generate tool validation help text (called once via LazyLock)
 fn get_tool_validation_help() -> String {
-    let git_status = check_tool_version("git", "--version", "2.20.0");
+    let tool_lines: Vec<String> = REQUIRED_TOOLS
+        .iter()
+        .map(|(tool, min_version)| {
+            let status = check_tool_version(tool, "--version", min_version);
+            format!("  {} {:<10} {}", status.status_icon, tool, status.version)
+        })
+        .collect();
     format!(
-        "REQUIRED TOOLS:\n  {} {:<10} {}\n\nLogs are written to: ~/.local/share/bump/logs/bump.log",
-        git_status.status_icon, "git", git_status.version
+        "REQUIRED TOOLS:\n{}\n\nLogs are written to: ~/.local/share/bump/logs/bump.log",
+        tool_lines.join("\n")
     )
 }
 
+/// Check that every required tool is installed and new enough
+pub fn required_tools_ok() -> bool {
+    REQUIRED_TOOLS
+        .iter()
+        .all(|(tool, min_version)| check_tool_version(tool, "--version", min_version).meets_requirement)
+}
+
+/// The git version to require: a config can raise MIN_GIT_VERSION but never lower it
+fn required_git_version(min_version: &str) -> &str {
+    if version_compare(min_version, MIN_GIT_VERSION) { min_version } else { MIN_GIT_VERSION }
+}
+
+/// Fail fast when git is missing or older than the required version
+pub fn ensure_git_version(min_version: &str) -> Result<()> {
+    let is_version = |v: &str| v.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if !is_version(min_version) {
+        bail!("Invalid min-git-version '{}': expected a version like {}", min_version, MIN_GIT_VERSION);
+    }
+
+    let min_version = required_git_version(min_version);
+    let status = check_tool_version("git", "--version", min_version);
+    if !status.meets_requirement {
+        bail!("bump needs git {} or newer, but git is {}", min_version, status.version);
+    }
+    Ok(())
+}
+
 struct ToolStatus {
     version: String,
     status_icon: String,
+    meets_requirement: bool,
 }
 
 /// Check if a tool is installed and meets minimum version requirements
@@ -68,45 +670,88 @@ fn check_tool_version(tool: &str, version_arg: &str, min_version: &str) -> ToolS
             ToolStatus {
                 version: if version.is_empty() { "unknown".to_string() } else { version },
                 status_icon: if meets_requirement { "✅" } else { "⚠️" }.to_string(),
+                meets_requirement,
             }
         }
         _ => ToolStatus {
             version: "not found".to_string(),
             status_icon: "❌".to_string(),
+            meets_requirement: false,
         },
     }
 }
 
+/// Maximum number of output lines to scan for a tool's version
+/// Most tools print it on the first line; gh prints a multi-line banner
+fn max_version_lines(tool: &str) -> usize {
+    match tool {
+        "gh" => 2,
+        _ => 1,
+    }
+}
+
 /// Extract version number from tool output
+/// git version 2.34.1
+/// cargo 1.75.0 (1d8b05cdd 2023-11-20)
+/// gh version 2.40.1 (2023-12-13)
 fn extract_version_from_output(tool: &str, output: &str) -> String {
-    if tool == "git" {
-        // git version 2.34.1
-        if let Some(line) = output.lines().next()
-            && let Some(version_part) = line.split_whitespace().nth(2)
-        {
-            return version_part.to_string();
-        }
+    output
+        .lines()
+        .take(max_version_lines(tool))
+        .flat_map(|line| line.split_whitespace())
+        .find_map(parse_version_token)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Pull a leading X.Y.Z out of a token like `2.43.0`, `v2.40.1`, or `2.43.0.windows.1`
+fn parse_version_token(token: &str) -> Option<String> {
+    let token = token.strip_prefix('v').unwrap_or(token);
+    let mut parts = token.splitn(4, '.');
+    let major = parts.next()?;
+    let minor = parts.next()?;
+    let patch = parts.next()?;
+    // Allow suffixes on the patch segment, e.g. `0-rc1`
+    let patch_end = patch.find(|c: char| !c.is_ascii_digit()).unwrap_or(patch.len());
+    let patch = &patch[..patch_end];
+
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if is_number(major) && is_number(minor) && is_number(patch) {
+        Some(format!("{}.{}.{}", major, minor, patch))
+    } else {
+        None
     }
-    "unknown".to_string()
 }
 
-/// Simple version comparison (assumes semantic versioning)
+/// Simple version comparison (assumes semantic versioning); missing segments count as 0
 fn version_compare(version: &str, min_version: &str) -> bool {
-    let parse_version = |v: &str| -> Vec<u32> { v.split('.').map(|part| part.parse().unwrap_or(0)).collect() };
+    // Numeric segments up to the first one that isn't a plain number, so trailing
+    // metadata like `-rc1`, `.windows.1` or ` (Apple Git-146)` is ignored
+    let parse_version = |v: &str| -> Vec<u32> {
+        let v = v.split_whitespace().next().unwrap_or_default();
+        let mut segments = Vec::new();
+        for part in v.split('.') {
+            let digits = &part[..part.find(|c: char| !c.is_ascii_digit()).unwrap_or(part.len())];
+            let Ok(segment) = digits.parse() else {
+                break;
+            };
+            segments.push(segment);
+            if digits.len() != part.len() {
+                break;
+            }
+        }
+        segments
+    };
 
     let v1 = parse_version(version);
     let v2 = parse_version(min_version);
 
-    for (a, b) in v1.iter().zip(v2.iter()) {
-        if a > b {
-            return true;
-        }
-        if a < b {
-            return false;
+    for i in 0..v1.len().max(v2.len()) {
+        let (a, b) = (v1.get(i).unwrap_or(&0), v2.get(i).unwrap_or(&0));
+        if a != b {
+            return a > b;
         }
     }
-
-    v1.len() >= v2.len()
+    true
 }
 
 #[cfg(test)]
@@ -120,6 +765,32 @@ mod tests {
         assert!(!version_compare("2.19.0", "2.20.0"));
         assert!(version_compare("3.0.0", "2.20.0"));
         assert!(!version_compare("1.0.0", "2.20.0"));
+        assert!(version_compare("2.20", "2.20.0"));
+    }
+
+    #[test]
+    fn test_version_compare_ignores_vendor_suffixes() {
+        assert!(version_compare("2.39.3 (Apple Git-146)", "2.20.0"));
+        assert!(!version_compare("2.19.1 (Apple Git-101)", "2.20.0"));
+        assert!(version_compare("2.45.1.windows.1", "2.45.1"));
+        assert!(!version_compare("2.19.0.windows.1", "2.20.0"));
+        assert!(version_compare("2.44.0.msysgit.0", "2.20.0"));
+        assert!(version_compare("2.20.0-rc1", "2.20.0"));
+        assert!(!version_compare("2.x.9", "2.20.0"));
+    }
+
+    #[test]
+    fn test_ensure_git_version() {
+        assert!(ensure_git_version(MIN_GIT_VERSION).is_ok());
+        assert!(ensure_git_version("2").is_ok());
+
+        let err = ensure_git_version("999.0.0").unwrap_err().to_string();
+        assert!(err.contains("bump needs git 999.0.0 or newer"), "{}", err);
+        assert!(ensure_git_version("2.x").is_err());
+        assert!(ensure_git_version("").is_err());
+
+        assert_eq!(required_git_version("2.30.0"), "2.30.0");
+        assert_eq!(required_git_version("1.8"), MIN_GIT_VERSION, "MUST not go below the built-in minimum");
     }
 
     #[test]
@@ -128,6 +799,46 @@ mod tests {
         assert_eq!(extract_version_from_output("git", output), "2.43.0");
     }
 
+    #[test]
+    fn test_extract_git_version_platform_suffix() {
+        let output = "git version 2.39.3 (Apple Git-146)";
+        assert_eq!(extract_version_from_output("git", output), "2.39.3");
+
+        let output = "git version 2.43.0.windows.1";
+        assert_eq!(extract_version_from_output("git", output), "2.43.0");
+
+        let output = "git version 2.44.0.msysgit.0";
+        assert_eq!(extract_version_from_output("git", output), "2.44.0");
+    }
+
+    #[test]
+    fn test_extract_cargo_version() {
+        let output = "cargo 1.75.0 (1d8b05cdd 2023-11-20)";
+        assert_eq!(extract_version_from_output("cargo", output), "1.75.0");
+
+        let output = "cargo 1.86.0-nightly (2928e3273 2025-02-07)";
+        assert_eq!(extract_version_from_output("cargo", output), "1.86.0");
+    }
+
+    #[test]
+    fn test_extract_gh_version() {
+        let output = "gh version 2.40.1 (2023-12-13)\nhttps://github.com/cli/cli/releases/tag/v2.40.1\n";
+        assert_eq!(extract_version_from_output("gh", output), "2.40.1");
+    }
+
+    #[test]
+    fn test_extract_version_respects_max_lines() {
+        // The version only appears past the scan limit for git
+        let output = "some banner\ngit version 2.43.0";
+        assert_eq!(extract_version_from_output("git", output), "unknown");
+    }
+
+    #[test]
+    fn test_extract_version_unknown() {
+        assert_eq!(extract_version_from_output("git", ""), "unknown");
+        assert_eq!(extract_version_from_output("cargo", "cargo nightly"), "unknown");
+    }
+
     #[test]
     fn test_cli_parsing() {
         let cli = Cli::try_parse_from(["bump"]).unwrap();
@@ -151,6 +862,15 @@ mod tests {
         assert!(cli.minor);
     }
 
+    #[test]
+    fn test_cli_patch_flag() {
+        let cli = Cli::try_parse_from(["bump", "-P"]).unwrap();
+        assert!(cli.patch);
+        assert!(cli.has_bump_selection());
+        assert_eq!(cli.bump_type(), BumpType::Patch);
+        assert_eq!(Cli::try_parse_from(["bump", "--patch"]).unwrap().bump_type(), BumpType::Patch);
+    }
+
     #[test]
     fn test_cli_dry_run() {
         let cli = Cli::try_parse_from(["bump", "-n"]).unwrap();
@@ -169,6 +889,22 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_cli_by() {
+        assert_eq!(Cli::try_parse_from(["bump"]).unwrap().by, 1);
+        assert_eq!(Cli::try_parse_from(["bump", "--minor", "--by", "3"]).unwrap().by, 3);
+        assert!(Cli::try_parse_from(["bump", "--by", "0"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--by", "2", "--pre", "rc"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_patch_conflicts() {
+        assert!(Cli::try_parse_from(["bump", "--patch", "--major"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "-P", "-m"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--patch", "--pre", "rc"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--patch", "-p", "crate-a"]).unwrap().patch);
+    }
+
     #[test]
     fn test_cli_message_flag() {
         let cli = Cli::try_parse_from(["bump", "--message", "my commit message"]).unwrap();
@@ -194,4 +930,286 @@ mod tests {
         let result = Cli::try_parse_from(["bump", "--message", "test", "--automatic"]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_cli_message_file_flag() {
+        let cli = Cli::try_parse_from(["bump", "--message-file", "msg.txt"]).unwrap();
+        assert_eq!(cli.message_file, Some(PathBuf::from("msg.txt")));
+        assert!(cli.message.is_none());
+        assert!(!cli.automatic);
+    }
+
+    #[test]
+    fn test_cli_message_file_conflicts() {
+        let result = Cli::try_parse_from(["bump", "--message-file", "msg.txt", "--message", "test"]);
+        assert!(result.is_err());
+
+        let result = Cli::try_parse_from(["bump", "--message-file", "msg.txt", "--automatic"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_verbose_and_quiet_conflict() {
+        assert!(Cli::try_parse_from(["bump", "-v"]).unwrap().verbose);
+        assert!(Cli::try_parse_from(["bump", "--quiet"]).unwrap().quiet);
+        assert!(Cli::try_parse_from(["bump", "-v", "-q"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_lightweight_conflicts_with_signing() {
+        assert!(Cli::try_parse_from(["bump", "--lightweight"]).unwrap().lightweight);
+        assert!(Cli::try_parse_from(["bump", "--lightweight", "--sign"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--lightweight", "--local-user", "ABCD"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_completions_flag() {
+        let cli = Cli::try_parse_from(["bump", "--completions", "zsh"]).unwrap();
+        assert_eq!(cli.completions, Some(Shell::Zsh));
+        assert!(Cli::try_parse_from(["bump", "--completions", "tcsh"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_calver_flag() {
+        let cli = Cli::try_parse_from(["bump", "--calver"]).unwrap();
+        assert_eq!(cli.version_scheme(), VersionScheme::CalVer);
+        assert!(cli.has_bump_selection());
+        assert_eq!(Cli::try_parse_from(["bump"]).unwrap().version_scheme(), VersionScheme::SemVer);
+        assert!(Cli::try_parse_from(["bump", "--calver", "--minor"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_branch_flag() {
+        let cli = Cli::try_parse_from(["bump"]).unwrap();
+        assert_eq!(cli.branch, None);
+
+        let cli = Cli::try_parse_from(["bump", "--branch"]).unwrap();
+        assert_eq!(cli.branch, Some(None));
+
+        let cli = Cli::try_parse_from(["bump", "--branch", "trunk"]).unwrap();
+        assert_eq!(cli.branch, Some(Some("trunk".to_string())));
+    }
+
+    #[test]
+    fn test_cli_manifest_path() {
+        let cli = Cli::try_parse_from(["bump", "--manifest-path", "app/Cargo.toml"]).unwrap();
+        assert_eq!(cli.manifest_path, Some(PathBuf::from("app/Cargo.toml")));
+    }
+
+    #[test]
+    fn test_cli_tag_only_force() {
+        let cli = Cli::try_parse_from(["bump", "--tag-only", "--force"]).unwrap();
+        assert!(cli.tag_only);
+        assert!(cli.force);
+        assert!(!cli.force_unsafe);
+    }
+
+    #[test]
+    fn test_cli_ref() {
+        let cli = Cli::try_parse_from(["bump", "--ref", "v1.0.0~2", "--force"]).unwrap();
+        assert_eq!(cli.git_ref.as_deref(), Some("v1.0.0~2"));
+        assert!(cli.force);
+        assert!(Cli::try_parse_from(["bump", "--ref", "HEAD~1", "--no-tag"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_force_requires_tag_only() {
+        assert!(Cli::try_parse_from(["bump", "--force"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--tag-only", "--force-unsafe"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_infer_from() {
+        let cli = Cli::try_parse_from(["bump", "--infer-from", "feat: add streaming API"]).unwrap();
+        assert_eq!(cli.infer_from, Some("feat: add streaming API".to_string()));
+
+        let result = Cli::try_parse_from(["bump", "--infer-from", "feat: x", "--major"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_pre_flag() {
+        let cli = Cli::try_parse_from(["bump", "--pre", "rc"]).unwrap();
+        assert_eq!(cli.pre, Some(PreKind::Rc));
+
+        assert!(Cli::try_parse_from(["bump", "--pre", "gamma"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--pre", "beta", "--minor"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_set_flag() {
+        let cli = Cli::try_parse_from(["bump", "--set", "2.0.0"]).unwrap();
+        assert_eq!(cli.set, Some("2.0.0".to_string()));
+
+        assert!(Cli::try_parse_from(["bump", "--set", "2.0.0", "--minor"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_release_flag() {
+        let cli = Cli::try_parse_from(["bump", "--release"]).unwrap();
+        assert!(cli.release);
+
+        assert!(Cli::try_parse_from(["bump", "--release", "--pre", "rc"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_push_and_remote() {
+        let cli = Cli::try_parse_from(["bump"]).unwrap();
+        assert!(!cli.push);
+        assert_eq!(cli.remote, "origin");
+
+        let cli = Cli::try_parse_from(["bump", "--push", "--remote", "upstream"]).unwrap();
+        assert!(cli.push);
+        assert_eq!(cli.remote, "upstream");
+    }
+
+    #[test]
+    fn test_cli_include_unpublished_needs_member_release() {
+        assert!(Cli::try_parse_from(["bump", "--all-members", "--include-unpublished"]).is_ok());
+        assert!(Cli::try_parse_from(["bump", "--tag-per-member", "--include-unpublished"]).is_ok());
+        assert!(Cli::try_parse_from(["bump", "--include-unpublished"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_delete_tag_remote_given() {
+        let cli = parse_from(["bump", "--delete-tag", "v1.2.0"]).unwrap();
+        assert_eq!(cli.delete_tag.as_deref(), Some("v1.2.0"));
+        assert!(!cli.remote_given);
+
+        let cli = parse_from(["bump", "--delete-tag", "v1.2.0", "--remote", "origin"]).unwrap();
+        assert!(cli.remote_given);
+        assert!(parse_from(["bump", "--delete-tag", "v1.2.0", "--tag-only"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_sign_flags() {
+        let cli = Cli::try_parse_from(["bump", "-s"]).unwrap();
+        assert!(cli.sign);
+        assert_eq!(cli.local_user, None);
+
+        let cli = Cli::try_parse_from(["bump", "--local-user", "ABCD1234"]).unwrap();
+        assert_eq!(cli.local_user.as_deref(), Some("ABCD1234"));
+    }
+
+    #[test]
+    fn test_cli_tag_prefix() {
+        let cli = Cli::try_parse_from(["bump"]).unwrap();
+        assert_eq!(cli.tag_prefix(), "v");
+
+        let cli = Cli::try_parse_from(["bump", "--tag-prefix", "release-"]).unwrap();
+        assert_eq!(cli.tag_prefix(), "release-");
+
+        let cli = Cli::try_parse_from(["bump", "--tag-prefix", ""]).unwrap();
+        assert_eq!(cli.tag_prefix(), "");
+    }
+
+    #[test]
+    fn test_cli_no_tag_flag() {
+        let cli = Cli::try_parse_from(["bump", "--no-tag"]).unwrap();
+        assert!(cli.no_tag);
+
+        assert!(Cli::try_parse_from(["bump", "--no-tag", "--tag-only"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_no_commit_flag() {
+        let cli = Cli::try_parse_from(["bump", "--no-commit"]).unwrap();
+        assert!(cli.no_commit);
+
+        assert!(Cli::try_parse_from(["bump", "--no-commit", "--push"]).is_err());
+        assert!(Cli::try_parse_from(["bump", "--no-commit", "--tag-only"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_print_version() {
+        let cli = Cli::try_parse_from(["bump", "--print-version", "--tag", "--major"]).unwrap();
+        assert!(cli.print_version);
+        assert!(cli.tag);
+
+        assert!(Cli::try_parse_from(["bump", "--tag"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_package() {
+        let cli = Cli::try_parse_from(["bump", "-p", "crate-a", "--package", "crate-b"]).unwrap();
+        assert_eq!(cli.package, vec!["crate-a".to_string(), "crate-b".to_string()]);
+    }
+
+    #[test]
+    fn test_cli_auto_flag() {
+        let cli = Cli::try_parse_from(["bump", "--auto"]).unwrap();
+        assert!(cli.auto);
+        assert!(!cli.automatic);
+
+        assert!(Cli::try_parse_from(["bump", "--auto", "--minor"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_has_bump_selection() {
+        assert!(!Cli::try_parse_from(["bump"]).unwrap().has_bump_selection());
+        assert!(Cli::try_parse_from(["bump", "--minor"]).unwrap().has_bump_selection());
+        assert!(Cli::try_parse_from(["bump", "--auto"]).unwrap().has_bump_selection());
+        assert!(Cli::try_parse_from(["bump", "--revision", "--version-file"]).unwrap().has_bump_selection());
+    }
+
+    #[test]
+    fn test_cli_revision_needs_version_source() {
+        assert!(Cli::try_parse_from(["bump", "--revision"]).is_err(), "Cargo rejects four-segment versions");
+        assert!(Cli::try_parse_from(["bump", "--revision", "--version-key", "package.metadata.version"]).is_ok());
+    }
+
+    #[test]
+    fn test_cli_jobs() {
+        assert_eq!(Cli::try_parse_from(["bump"]).unwrap().jobs, 1);
+        assert_eq!(Cli::try_parse_from(["bump", "-j", "8", "a", "b"]).unwrap().jobs, 8);
+    }
+
+    #[test]
+    fn test_cli_recursive() {
+        let cli = Cli::try_parse_from(["bump", "--recursive", "--max-depth", "2", "src"]).unwrap();
+        assert!(cli.recursive);
+        assert_eq!(cli.max_depth, Some(2));
+
+        assert!(Cli::try_parse_from(["bump", "--max-depth", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_undo_subcommand() {
+        let cli = Cli::try_parse_from(["bump", "undo", "--force", "repo"]).unwrap();
+        let Some(BumpCommand::Undo(args)) = cli.command else {
+            panic!("expected undo subcommand");
+        };
+        assert!(args.force);
+        assert_eq!(args.tag_prefix, "v");
+        assert_eq!(args.directory, Some(PathBuf::from("repo")));
+
+        let cli = Cli::try_parse_from(["bump", "init", "--tag-prefix", "app-v", "-n"]).unwrap();
+        let Some(BumpCommand::Init(args)) = cli.command else {
+            panic!("expected init subcommand");
+        };
+        assert_eq!(args.tag_prefix.as_deref(), Some("app-v"));
+        assert!(args.dry_run);
+        assert_eq!(args.directory, None);
+
+        // Without a subcommand, positionals are still directories
+        let cli = Cli::try_parse_from(["bump", "a", "b"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.directories.len(), 2);
+    }
+
+    #[test]
+    fn test_cli_directory_named_like_subcommand() {
+        // First, the name is the subcommand; ./ or -- makes it a directory
+        let cli = Cli::try_parse_from(["bump", "status"]).unwrap();
+        assert!(cli.command.is_some());
+
+        let cli = Cli::try_parse_from(["bump", "--", "status"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.directories, vec![PathBuf::from("status")]);
+
+        // After a directory, later names are directories too
+        let cli = Cli::try_parse_from(["bump", "./status", "init"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.directories, vec![PathBuf::from("./status"), PathBuf::from("init")]);
+    }
 }
@@ -0,0 +1,234 @@
+use eyre::{Context, Result, eyre};
+use serde::Deserialize;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::Cli;
+
+/// Per-repository config file, looked up in each target directory
+const CONFIG_FILE: &str = ".bump.toml";
+
+/// Default bump type when no bump flag is given
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultBump {
+    Major,
+    Minor,
+    Patch,
+}
+
+/// Defaults read from `.bump.toml` (or the user config); command-line flags win
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Tag prefix (--tag-prefix)
+    pub tag_prefix: Option<String>,
+    /// Sign tags (--sign)
+    pub sign: bool,
+    /// Push after tagging (--push)
+    pub push: bool,
+    /// Template for generated commit messages (--commit-template)
+    pub commit_template: Option<String>,
+    /// Template for tag annotations (--tag-template)
+    pub tag_template: Option<String>,
+    /// Command run before any files are changed (--pre-hook)
+    pub pre_hook: Option<String>,
+    /// Command run after tagging (--post-hook)
+    pub post_hook: Option<String>,
+    /// Only bump on this branch (--branch)
+    pub branch: Option<String>,
+    /// Bump type used when no --major/--minor/... flag is given
+    pub bump: Option<DefaultBump>,
+    /// Oldest git allowed; checked before anything runs
+    pub min_git_version: Option<String>,
+}
+
+/// The user-wide config, `$XDG_CONFIG_HOME/bump/config.toml` (`~/.config/bump/config.toml` by default)
+fn user_config_path() -> Option<PathBuf> {
+    config_home(env::var_os("XDG_CONFIG_HOME"), dirs::home_dir()).map(|dir| dir.join("bump").join("config.toml"))
+}
+
+/// Resolve the config directory the XDG way on every platform: `$XDG_CONFIG_HOME` when it is set to an
+/// absolute path, otherwise `~/.config`
+fn config_home(xdg_config_home: Option<OsString>, home: Option<PathBuf>) -> Option<PathBuf> {
+    xdg_config_home
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")))
+}
+
+/// Parse a config file, naming the file in any error
+fn parse(path: &Path) -> Result<Config> {
+    let content = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+    toml_edit::de::from_str(&content).map_err(|e| eyre!("Invalid config {}: {}", path.display(), e))
+}
+
+impl Config {
+    /// Load `.bump.toml` from the directory, falling back to the user config
+    /// Returns the default (empty) config if neither exists
+    pub fn load(dir: &Path) -> Result<Self> {
+        let local = dir.join(CONFIG_FILE);
+        if local.is_file() {
+            return parse(&local);
+        }
+        match user_config_path() {
+            Some(ref path) if path.is_file() => parse(path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    /// Fill in the defaults this config provides for anything not given on the command line
+    pub fn apply(&self, cli: &Cli) -> Cli {
+        let mut cli = cli.clone();
+
+        if cli.tag_prefix.is_none() {
+            cli.tag_prefix = self.tag_prefix.clone();
+        }
+        // A lightweight tag has no tag object to sign
+        cli.sign |= self.sign && !cli.lightweight && !cli.no_sign;
+        // --no-commit has nothing to push, and --push-tags leaves the commit alone
+        cli.push |= self.push && !cli.no_commit && !cli.no_push && !cli.push_tags;
+        if cli.commit_template.is_none() {
+            cli.commit_template = self.commit_template.clone();
+        }
+        if cli.tag_template.is_none() {
+            cli.tag_template = self.tag_template.clone();
+        }
+        if cli.pre_hook.is_none() {
+            cli.pre_hook = self.pre_hook.clone();
+        }
+        if cli.post_hook.is_none() {
+            cli.post_hook = self.post_hook.clone();
+        }
+        if cli.branch.is_none() {
+            cli.branch = self.branch.clone().map(Some);
+        }
+        cli.min_git_version = self.min_git_version.clone();
+
+        // A configured default bump type replaces the patch default (and the interactive prompt)
+        if !cli.has_bump_selection()
+            && let Some(bump) = self.bump
+        {
+            match bump {
+                DefaultBump::Major => cli.major = true,
+                DefaultBump::Minor => cli.minor = true,
+                DefaultBump::Patch => {}
+            }
+            cli.interactive = false;
+        }
+
+        cli
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_local_config() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            "tag-prefix = \"release-\"\nsign = true\npush = true\ncommit-template = \"chore: release {tag}\"\n\
+             tag-template = \"Release {version}\"\npre-hook = \"cargo test\"\nbranch = \"main\"\nbump = \"minor\"\n\
+             min-git-version = \"2.30.0\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                tag_prefix: Some("release-".to_string()),
+                sign: true,
+                push: true,
+                commit_template: Some("chore: release {tag}".to_string()),
+                tag_template: Some("Release {version}".to_string()),
+                pre_hook: Some("cargo test".to_string()),
+                post_hook: None,
+                branch: Some("main".to_string()),
+                bump: Some(DefaultBump::Minor),
+                min_git_version: Some("2.30.0".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_reports_invalid_config() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(CONFIG_FILE), "tag-prefx = \"v\"\n").unwrap();
+
+        let err = Config::load(dir.path()).unwrap_err().to_string();
+        assert!(err.contains(".bump.toml"), "{}", err);
+        assert!(err.contains("tag-prefx"), "{}", err);
+    }
+
+    #[test]
+    fn test_apply_cli_flags_win() {
+        let config = Config {
+            tag_prefix: Some("release-".to_string()),
+            branch: Some("main".to_string()),
+            bump: Some(DefaultBump::Minor),
+            ..Config::default()
+        };
+
+        let cli = config.apply(&Cli::try_parse_from(["bump"]).unwrap());
+        assert_eq!(cli.tag_prefix(), "release-");
+        assert!(cli.minor);
+        assert_eq!(cli.branch, Some(Some("main".to_string())));
+
+        let args = ["bump", "--tag-prefix", "v", "--major", "--branch", "trunk"];
+        let cli = config.apply(&Cli::try_parse_from(args).unwrap());
+        assert_eq!(cli.tag_prefix(), "v");
+        assert_eq!(cli.branch, Some(Some("trunk".to_string())));
+        assert!(cli.major);
+        assert!(!cli.minor);
+    }
+
+    #[test]
+    fn test_apply_sign_skipped_for_lightweight_tags() {
+        let config = Config {
+            sign: true,
+            ..Config::default()
+        };
+        assert!(config.apply(&Cli::try_parse_from(["bump"]).unwrap()).sign);
+        assert!(!config.apply(&Cli::try_parse_from(["bump", "--lightweight"]).unwrap()).sign);
+    }
+
+    #[test]
+    fn test_apply_no_sign_and_no_push_override_config() {
+        let config = Config {
+            sign: true,
+            push: true,
+            ..Config::default()
+        };
+        let cli = config.apply(&Cli::try_parse_from(["bump"]).unwrap());
+        assert!(cli.sign && cli.push);
+
+        let cli = config.apply(&Cli::try_parse_from(["bump", "--no-sign", "--no-push"]).unwrap());
+        assert!(!cli.sign);
+        assert!(!cli.push);
+        assert!(!config.apply(&Cli::try_parse_from(["bump", "--push-tags"]).unwrap()).push);
+    }
+
+    #[test]
+    fn test_config_home_prefers_xdg() {
+        let home = Some(PathBuf::from("/home/me"));
+        assert_eq!(
+            config_home(Some(OsString::from("/xdg")), home.clone()),
+            Some(PathBuf::from("/xdg"))
+        );
+        assert_eq!(config_home(None, home.clone()), Some(PathBuf::from("/home/me/.config")));
+        assert_eq!(
+            config_home(Some(OsString::from("relative")), home),
+            Some(PathBuf::from("/home/me/.config")),
+            "MUST ignore a relative XDG_CONFIG_HOME, as the spec says"
+        );
+        assert_eq!(config_home(None, None), None);
+    }
+}
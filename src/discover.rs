@@ -0,0 +1,72 @@
+use eyre::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories that never contain repos worth bumping
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+/// Find git repositories with a Cargo.toml at or below `root` (--recursive)
+/// Hidden directories and build output are skipped, and the walk doesn't descend into a
+/// repo once found (so nested checkouts and submodules aren't bumped separately)
+pub fn discover_repos(root: &Path, max_depth: Option<usize>) -> Result<Vec<PathBuf>> {
+    let mut repos = Vec::new();
+    walk(root, 0, max_depth, &mut repos)?;
+    repos.sort();
+    Ok(repos)
+}
+
+fn walk(dir: &Path, depth: usize, max_depth: Option<usize>, repos: &mut Vec<PathBuf>) -> Result<()> {
+    if dir.join(".git").exists() {
+        if dir.join("Cargo.toml").exists() {
+            repos.push(dir.to_path_buf());
+        }
+        return Ok(());
+    }
+
+    if max_depth.is_some_and(|max| depth >= max) {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(dir).context(format!("Failed to read directory {}", dir.display()))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        // Don't follow symlinks, so the walk can't loop
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        if !is_dir || name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        walk(&path, depth + 1, max_depth, repos)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn make_repo(dir: &Path, with_cargo: bool) {
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        if with_cargo {
+            fs::write(dir.join("Cargo.toml"), "[package]\nname = \"x\"\n").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_discover_repos() {
+        let root = TempDir::new().unwrap();
+        make_repo(&root.path().join("a"), true);
+        make_repo(&root.path().join("group/b"), true);
+        make_repo(&root.path().join("docs"), false);
+        make_repo(&root.path().join("a/vendor/nested"), true);
+        make_repo(&root.path().join("c/target/d"), true);
+
+        let repos = discover_repos(root.path(), None).unwrap();
+        assert_eq!(repos, vec![root.path().join("a"), root.path().join("group/b")]);
+
+        let repos = discover_repos(root.path(), Some(1)).unwrap();
+        assert_eq!(repos, vec![root.path().join("a")]);
+    }
+}
@@ -1,6 +1,42 @@
+use clap::ValueEnum;
 use eyre::{Context, Result, bail};
-use std::path::Path;
+use log::info;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::version;
+
+/// In-process versions of the hot operations; everything else still runs the git binary
+#[cfg(feature = "libgit2")]
+mod libgit2;
+
+/// How git operations are carried out (--backend)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// Run the git binary
+    #[default]
+    Git,
+    /// Use libgit2 in-process for tagging, staging and committing (needs the `libgit2` build feature)
+    Libgit2,
+}
+
+/// Set once at startup from --backend
+static LIBGIT2: AtomicBool = AtomicBool::new(false);
+
+/// Choose the backend for the rest of the run; libgit2 must have been compiled in
+pub fn set_backend(backend: Backend) -> Result<()> {
+    if backend == Backend::Libgit2 && !cfg!(feature = "libgit2") {
+        bail!("This bump was built without libgit2 support; rebuild with `--features libgit2` or use --backend git");
+    }
+    LIBGIT2.store(backend == Backend::Libgit2, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(feature = "libgit2")]
+fn use_libgit2() -> bool {
+    LIBGIT2.load(Ordering::Relaxed)
+}
 
 /// Check if the given path is inside a git repository
 pub fn is_git_repo(path: &Path) -> bool {
@@ -11,10 +47,97 @@ pub fn is_git_repo(path: &Path) -> bool {
         .is_ok_and(|output| output.status.success())
 }
 
-/// Get the latest semver tag (tags starting with 'v')
-pub fn get_latest_tag(path: &Path) -> Result<Option<String>> {
+/// Get the top-level directory of the repository containing the given path
+pub fn repo_root(path: &Path) -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        bail!("git rev-parse failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Get the name of the currently checked-out branch
+pub fn current_branch(path: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        bail!("git rev-parse failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Check if HEAD is detached (a commit is checked out rather than a branch)
+pub fn is_detached_head(path: &Path) -> Result<bool> {
     let output = Command::new("git")
-        .args(["tag", "-l", "v*", "--sort=-v:refname"])
+        .args(["symbolic-ref", "-q", "HEAD"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git symbolic-ref")?;
+
+    // Exit code 1 means HEAD is not a symbolic ref; anything else non-zero is a real failure
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => bail!("git symbolic-ref failed: {}", String::from_utf8_lossy(&output.stderr)),
+    }
+}
+
+/// Detect the default branch of a remote (main, master, trunk, ...) from its HEAD
+/// Returns None if the remote HEAD isn't set and can't be queried
+pub fn default_branch(path: &Path, remote: &str) -> Result<Option<String>> {
+    // Fast path: the locally cached remote HEAD (set by clone or `git remote set-head`)
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", &format!("refs/remotes/{}/HEAD", remote)])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git symbolic-ref")?;
+
+    if output.status.success() {
+        let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let prefix = format!("{}/", remote);
+        return Ok(Some(head.strip_prefix(&prefix).unwrap_or(&head).to_string()));
+    }
+
+    // Slow path: ask the remote directly
+    let output = Command::new("git")
+        .args(["remote", "show", remote])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git remote show")?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let details = String::from_utf8_lossy(&output.stdout);
+    Ok(details
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("HEAD branch:"))
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty() && branch != "(unknown)"))
+}
+
+/// Get the latest semver tag (tags starting with the given prefix, usually 'v')
+/// Tags are ordered by semver precedence, so `v1.3.0` wins over `v1.3.0-rc.2`
+pub fn get_latest_tag(path: &Path, prefix: &str, revisions: bool) -> Result<Option<String>> {
+    #[cfg(feature = "libgit2")]
+    if use_libgit2() {
+        return libgit2::get_latest_tag(path, prefix, revisions);
+    }
+
+    let output = Command::new("git")
+        .args(["tag", "-l", &format!("{}*", prefix)])
         .current_dir(path)
         .output()
         .context("Failed to run git tag")?;
@@ -24,11 +147,63 @@ pub fn get_latest_tag(path: &Path) -> Result<Option<String>> {
     }
 
     let tags = String::from_utf8_lossy(&output.stdout);
-    Ok(tags.lines().next().map(|s| s.to_string()))
+    Ok(highest_tag(tags.lines(), prefix, revisions))
+}
+
+/// Pick the tag with the highest version among those carrying the prefix
+/// Skips tags that merely share the prefix (e.g. `mycrate-vendor-v1.0.0` for `mycrate-v`),
+/// and four-segment tags unless `revisions` is set
+fn highest_tag<'a>(tags: impl Iterator<Item = &'a str>, prefix: &str, revisions: bool) -> Option<String> {
+    tags.filter_map(|tag| {
+        let version = version::parse_tag(tag, prefix, revisions).ok()?;
+        Some((version, tag))
+    })
+    .max_by(|(a, _), (b, _)| a.cmp(b))
+    .map(|(_, tag)| tag.to_string())
+}
+
+/// Get the subjects of commits since a tag (newest first), or of all commits if there is no tag
+pub fn commits_since(path: &Path, tag: Option<&str>) -> Result<Vec<String>> {
+    Ok(log_since(path, tag, "%s")?.lines().map(|line| line.to_string()).collect())
+}
+
+/// Get the full messages (subject, body and footers) of commits since a tag, newest first
+/// Records are split on an ASCII record separator, since a message may contain blank lines
+pub fn commit_messages_since(path: &Path, tag: Option<&str>) -> Result<Vec<String>> {
+    Ok(log_since(path, tag, "%B%x1e")?
+        .split('\x1e')
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+        .collect())
+}
+
+/// Run `git log` since a tag (or over all history) with the given --pretty format
+fn log_since(path: &Path, tag: Option<&str>, format: &str) -> Result<String> {
+    let range = tag.map(|t| format!("{}..HEAD", t));
+    let pretty = format!("--pretty={}", format);
+    let mut args = vec!["log", pretty.as_str()];
+    args.extend(range.as_deref());
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(path)
+        .output()
+        .context("Failed to run git log")?;
+
+    if !output.status.success() {
+        bail!("git log failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 /// Check if a specific tag exists
 pub fn tag_exists(path: &Path, tag: &str) -> Result<bool> {
+    #[cfg(feature = "libgit2")]
+    if use_libgit2() {
+        return libgit2::tag_exists(path, tag);
+    }
+
     let output = Command::new("git")
         .args(["tag", "-l", tag])
         .current_dir(path)
@@ -43,10 +218,45 @@ pub fn tag_exists(path: &Path, tag: &str) -> Result<bool> {
     Ok(!result.trim().is_empty())
 }
 
-/// Stage all changes (git add -A)
-pub fn stage_all(path: &Path) -> Result<()> {
+/// What goes into the release commit (--stage)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum StageMode {
+    /// Every change, including untracked files (git add -A)
+    #[default]
+    All,
+    /// Changes to files git already tracks (git add -u)
+    Tracked,
+    /// Only Cargo.toml and Cargo.lock files
+    CargoOnly,
+}
+
+/// The manifest files staged by `StageMode::CargoOnly`, as pathspecs covering every directory
+const CARGO_PATHSPECS: &[&str] = &[":(glob)**/Cargo.toml", ":(glob)**/Cargo.lock"];
+
+/// Stage changes for the release commit (git add -A, git add -u, or just the Cargo files)
+pub fn stage_all(path: &Path, mode: StageMode) -> Result<()> {
+    #[cfg(feature = "libgit2")]
+    if use_libgit2() {
+        return libgit2::stage_all(path, mode);
+    }
+
+    let mut args = vec!["add".to_string()];
+    match mode {
+        StageMode::All => args.push("-A".to_string()),
+        StageMode::Tracked => args.push("-u".to_string()),
+        StageMode::CargoOnly => {
+            // git add fails on a pathspec that matches nothing (e.g. no Cargo.lock), so list the changes first
+            let changed = cargo_files_changed(path)?;
+            if changed.is_empty() {
+                return Ok(());
+            }
+            args.push("--".to_string());
+            args.extend(changed);
+        }
+    }
+
     let output = Command::new("git")
-        .args(["add", "-A"])
+        .args(&args)
         .current_dir(path)
         .output()
         .context("Failed to run git add")?;
@@ -58,6 +268,48 @@ pub fn stage_all(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Stage exactly these files (paths relative to the repository root), and nothing else
+pub fn stage_files(path: &Path, files: &[&str]) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(feature = "libgit2")]
+    if use_libgit2() {
+        return libgit2::stage_files(path, files);
+    }
+
+    let output = Command::new("git")
+        .args(["add", "-A", "--"])
+        .args(files.iter().map(|file| format!(":(top,literal){}", file)))
+        .current_dir(path)
+        .output()
+        .context("Failed to run git add")?;
+
+    if !output.status.success() {
+        bail!("git add failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(())
+}
+
+/// Changed or new Cargo.toml/Cargo.lock files, relative to the path
+fn cargo_files_changed(path: &Path) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["ls-files", "--modified", "--others", "--exclude-standard", "--"])
+        .args(CARGO_PATHSPECS)
+        .current_dir(path)
+        .output()
+        .context("Failed to run git ls-files")?;
+
+    if !output.status.success() {
+        bail!("git ls-files failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect();
+    files.dedup();
+    Ok(files)
+}
+
 /// Get list of staged files
 pub fn get_staged_files(path: &Path) -> Result<Vec<String>> {
     let output = Command::new("git")
@@ -74,36 +326,409 @@ pub fn get_staged_files(path: &Path) -> Result<Vec<String>> {
     Ok(files.lines().map(|s| s.to_string()).collect())
 }
 
+/// Options controlling how a commit is created
+/// The committer comes from git's config, or GIT_COMMITTER_NAME/GIT_COMMITTER_EMAIL in the environment
+#[derive(Debug, Default, Clone)]
+pub struct CommitOptions {
+    /// Attribute the commit to this author instead of the configured identity (git commit --author)
+    pub author: Option<String>,
+    /// Skip the pre-commit and commit-msg hooks (git commit --no-verify)
+    pub no_verify: bool,
+    /// Body paragraph after the subject line (a second git commit -m)
+    pub body: Option<String>,
+}
+
+/// Check an author string has the `Name <email>` form git expects
+pub fn validate_author(author: &str) -> Result<()> {
+    let valid = author
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .is_some_and(|(name, email)| !name.trim().is_empty() && email.contains('@') && !email.contains(['<', '>']));
+    if !valid {
+        bail!("Invalid author '{}': expected \"Name <email>\"", author);
+    }
+    Ok(())
+}
+
+/// The -m arguments for a message: the subject, then the body if there is one
+fn message_args<'a>(message: &'a str, options: &'a CommitOptions) -> Vec<&'a str> {
+    let mut args = vec!["-m", message];
+    if let Some(ref body) = options.body {
+        args.extend(["-m", body.as_str()]);
+    }
+    args
+}
+
+/// Run git commit with the given arguments plus any options
+fn run_commit(path: &Path, args: &[&str], options: &CommitOptions) -> Result<()> {
+    let mut command = Command::new("git");
+    command.arg("commit").args(args);
+    if let Some(ref author) = options.author {
+        command.arg(format!("--author={}", author));
+    }
+    if options.no_verify {
+        command.arg("--no-verify");
+    }
+
+    let output = command.current_dir(path).output().context("Failed to run git commit")?;
+
+    if !output.status.success() {
+        bail!("git commit failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(())
+}
+
 /// Create a commit with the given message
-pub fn commit(path: &Path, message: &str) -> Result<()> {
+pub fn commit(path: &Path, message: &str, options: &CommitOptions) -> Result<()> {
+    #[cfg(feature = "libgit2")]
+    if use_libgit2() {
+        return libgit2::commit(path, message, options);
+    }
+
+    run_commit(path, &message_args(message, options), options)
+}
+
+/// Options controlling how a tag is created
+#[derive(Debug, Default, Clone)]
+pub struct TagOptions {
+    /// Replace an existing tag of the same name (git tag -f)
+    pub force: bool,
+    /// Create a GPG-signed tag (git tag -s)
+    pub sign: bool,
+    /// Sign with this key instead of the default one (git tag -u <keyid>)
+    pub local_user: Option<String>,
+    /// Create a lightweight tag with no tag object or message (git tag <name>)
+    pub lightweight: bool,
+    /// Tag this commit instead of HEAD (git tag <name> <commit>)
+    pub target: Option<String>,
+}
+
+/// Create an annotated (optionally signed) tag with the given message
+/// Lightweight tags ignore the message
+pub fn create_tag(path: &Path, tag: &str, message: &str, options: &TagOptions) -> Result<()> {
+    #[cfg(feature = "libgit2")]
+    if use_libgit2() {
+        return libgit2::create_tag(path, tag, message, options);
+    }
+
+    let mut args = vec!["tag"];
+    if options.lightweight {
+        args.push(tag);
+    } else {
+        match options.local_user {
+            Some(ref keyid) => args.extend(["-u", keyid.as_str()]),
+            None if options.sign => args.push("-s"),
+            None => args.push("-a"),
+        }
+        args.extend([tag, "-m", message]);
+    }
+    if let Some(ref target) = options.target {
+        args.push(target);
+    }
+    if options.force {
+        args.push("-f");
+    }
+
     let output = Command::new("git")
-        .args(["commit", "-m", message])
+        .args(&args)
         .current_dir(path)
         .output()
-        .context("Failed to run git commit")?;
+        .context("Failed to run git tag")?;
 
     if !output.status.success() {
-        bail!("git commit failed: {}", String::from_utf8_lossy(&output.stderr));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // The raw gpg chatter stays in the log (shown with --verbose); the error says what to do
+        if let Some(hint) = signing_failure_hint(&stderr) {
+            info!("git tag stderr: {}", stderr.trim());
+            bail!("git tag failed: {}", hint);
+        }
+        bail!("git tag failed: {}", stderr);
     }
 
     Ok(())
 }
 
-/// Create an annotated tag with the given message
-pub fn create_tag(path: &Path, tag: &str, message: &str) -> Result<()> {
+/// Translate the usual GPG signing failures in git's stderr into what to do about them
+fn signing_failure_hint(stderr: &str) -> Option<&'static str> {
+    let stderr = stderr.to_lowercase();
+    if stderr.contains("cannot run gpg") {
+        Some("gpg could not be run; install it, set gpg.program, or run without --sign")
+    } else if stderr.contains("no secret key") || stderr.contains("secret key not available") {
+        Some(
+            "no GPG secret key for the signing identity; configure user.signingkey (or pass --local-user) \
+             or run without --sign",
+        )
+    } else if stderr.contains("gpg failed to sign") {
+        Some("gpg failed to sign the tag; check gpg-agent (e.g. export GPG_TTY=$(tty)), or run without --sign")
+    } else {
+        None
+    }
+}
+
+/// Push HEAD and the given tags to a remote, naming each ref so no other local tags go along
+pub fn push(path: &Path, remote: &str, tags: &[&str], no_verify: bool) -> Result<()> {
+    let refspecs: Vec<String> = std::iter::once("HEAD".to_string())
+        .chain(tags.iter().map(|tag| format!("refs/tags/{}", tag)))
+        .collect();
+    run_push(path, remote, &refspecs, no_verify)
+}
+
+/// Run `git push <remote> <refspecs...>`
+fn run_push(path: &Path, remote: &str, refspecs: &[String], no_verify: bool) -> Result<()> {
+    let mut args = vec!["push", remote];
+    args.extend(refspecs.iter().map(String::as_str));
+    if no_verify {
+        args.push("--no-verify");
+    }
+
     let output = Command::new("git")
-        .args(["tag", "-a", tag, "-m", message])
+        .args(&args)
         .current_dir(path)
         .output()
-        .context("Failed to run git tag")?;
+        .context("Failed to run git push")?;
 
     if !output.status.success() {
-        bail!("git tag failed: {}", String::from_utf8_lossy(&output.stderr));
+        bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
     }
+    Ok(())
+}
+
+/// Check that a remote is configured, naming it in the error
+pub fn ensure_remote(path: &Path, remote: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", remote])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git remote get-url")?;
 
+    if !output.status.success() {
+        bail!(
+            "No remote named '{}' in {}; add it with `git remote add {} <url>` or pick another with --remote",
+            remote,
+            path.display(),
+            remote
+        );
+    }
     Ok(())
 }
 
+/// Push the given tags, and nothing else, to a remote (commits are left for CI to push)
+/// With `force`, a tag that already exists there is moved (after --tag-only --force)
+pub fn push_tags(path: &Path, remote: &str, tags: &[&str], force: bool, no_verify: bool) -> Result<()> {
+    ensure_remote(path, remote)?;
+
+    let refspecs: Vec<String> = tags
+        .iter()
+        .map(|tag| format!("{}refs/tags/{}", if force { "+" } else { "" }, tag))
+        .collect();
+    run_push(path, remote, &refspecs, no_verify)
+}
+
+/// Delete a local tag, and with `remote` also delete it there (git push <remote> :refs/tags/<tag>)
+pub fn delete_tag(path: &Path, tag: &str, remote: Option<&str>) -> Result<()> {
+    if let Some(remote) = remote {
+        ensure_remote(path, remote)?;
+    }
+
+    let output = Command::new("git")
+        .args(["tag", "-d", tag])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git tag -d")?;
+
+    if !output.status.success() {
+        bail!("git tag -d failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    if let Some(remote) = remote {
+        let refspec = format!(":refs/tags/{}", tag);
+        let output = Command::new("git")
+            .args(["push", remote, &refspec])
+            .current_dir(path)
+            .output()
+            .context("Failed to run git push")?;
+
+        if !output.status.success() {
+            bail!("git push {} {} failed: {}", remote, refspec, String::from_utf8_lossy(&output.stderr).trim());
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch the remote's tags so existence checks see tags teammates already pushed
+pub fn fetch_tags(path: &Path, remote: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["fetch", "--tags", "--quiet", remote])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git fetch")?;
+
+    if !output.status.success() {
+        bail!("git fetch --tags {} failed: {}", remote, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(())
+}
+
+/// Check if the repository is a shallow clone, with history cut off at some depth
+pub fn is_shallow(path: &Path) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--is-shallow-repository"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        bail!("git rev-parse --is-shallow-repository failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim() == "true")
+}
+
+/// Fetch the rest of a shallow clone's history and all tags (git fetch --unshallow --tags)
+pub fn unshallow(path: &Path, remote: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["fetch", "--unshallow", "--tags", "--quiet", remote])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git fetch")?;
+
+    if !output.status.success() {
+        bail!("git fetch --unshallow {} failed: {}", remote, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(())
+}
+
+/// Fetch the current branch's upstream (git fetch)
+pub fn fetch(path: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["fetch", "--quiet"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git fetch")?;
+
+    if !output.status.success() {
+        bail!("git fetch failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(())
+}
+
+/// Count commits HEAD has that its upstream lacks, and the reverse: (ahead, behind)
+pub fn ahead_behind(path: &Path) -> Result<(usize, usize)> {
+    let output = Command::new("git")
+        .args(["rev-list", "--left-right", "--count", "HEAD...@{u}"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git rev-list")?;
+
+    if !output.status.success() {
+        bail!("No upstream to compare with: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let counts = String::from_utf8_lossy(&output.stdout);
+    let parsed: Vec<usize> = counts.split_whitespace().filter_map(|n| n.parse().ok()).collect();
+    match parsed[..] {
+        [ahead, behind] => Ok((ahead, behind)),
+        _ => bail!("Unexpected git rev-list output: {}", counts.trim()),
+    }
+}
+
+/// Check whether a remote has a tag (git ls-remote)
+/// Errors when the remote can't be asked (unknown, unreachable, or refusing access)
+pub fn remote_has_tag(path: &Path, remote: &str, tag: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["ls-remote", "--tags", remote, &format!("refs/tags/{}", tag)])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git ls-remote")?;
+
+    if !output.status.success() {
+        bail!("git ls-remote {} failed: {}", remote, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(!String::from_utf8_lossy(&output.stdout).trim().is_empty())
+}
+
+/// The abbreviated SHA of HEAD (git rev-parse --short HEAD)
+pub fn head_sha(path: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        bail!("git rev-parse --short HEAD failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Resolve a revision (branch, tag, SHA, ...) to the full SHA of the commit it points at
+pub fn resolve_commit(path: &Path, rev: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        bail!("Cannot resolve '{}' to a commit: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The files a commit changed relative to its parent, relative to the repository root
+pub fn commit_files(path: &Path, rev: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["diff-tree", "--no-commit-id", "--name-only", "-r", "-z", rev])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git diff-tree")?;
+
+    if !output.status.success() {
+        bail!("git diff-tree failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let files = String::from_utf8_lossy(&output.stdout);
+    Ok(files.split('\0').filter(|f| !f.is_empty()).map(|f| f.to_string()).collect())
+}
+
+/// One field of a commit in git's --pretty format (e.g. `%s` for the subject, `%cs` for the date)
+pub fn commit_field(path: &Path, rev: &str, format: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["show", "-s", &format!("--pretty={}", format), rev])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git show")?;
+
+    if !output.status.success() {
+        bail!("git show failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Read a file's contents as committed at the given revision
+/// The file path is relative to `path` (e.g. `./Cargo.toml`)
+pub fn show_file(path: &Path, rev: &str, file: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["show", &format!("{}:{}", rev, file)])
+        .current_dir(path)
+        .output()
+        .context("Failed to run git show")?;
+
+    if !output.status.success() {
+        bail!("git show failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 /// Check if HEAD has an annotated tag pointing directly at it
 pub fn head_has_tag(path: &Path) -> Result<bool> {
     let output = Command::new("git")
@@ -142,24 +767,32 @@ pub fn is_head_pushed(path: &Path) -> Result<bool> {
     Ok(merge_base.status.success())
 }
 
-/// Amend the previous commit without changing the message
-pub fn amend_commit_no_edit(path: &Path) -> Result<()> {
+/// Move HEAD back to a commit, keeping the index and working tree (git reset --soft)
+pub fn reset_soft(path: &Path, rev: &str) -> Result<()> {
     let output = Command::new("git")
-        .args(["commit", "--amend", "--no-edit"])
+        .args(["reset", "--soft", rev])
         .current_dir(path)
         .output()
-        .context("Failed to run git commit --amend")?;
+        .context("Failed to run git reset")?;
 
     if !output.status.success() {
-        bail!(
-            "git commit --amend failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
+        bail!("git reset --soft failed: {}", String::from_utf8_lossy(&output.stderr));
     }
 
     Ok(())
 }
 
+/// Amend the previous commit, replacing its message
+pub fn amend_commit(path: &Path, message: &str, options: &CommitOptions) -> Result<()> {
+    let args: Vec<&str> = std::iter::once("--amend").chain(message_args(message, options)).collect();
+    run_commit(path, &args, options)
+}
+
+/// Amend the previous commit without changing the message
+pub fn amend_commit_no_edit(path: &Path, options: &CommitOptions) -> Result<()> {
+    run_commit(path, &["--amend", "--no-edit"], options)
+}
+
 /// Check if there are any uncommitted changes (staged or unstaged)
 pub fn has_uncommitted_changes(path: &Path) -> Result<bool> {
     let output = Command::new("git")
@@ -176,10 +809,78 @@ pub fn has_uncommitted_changes(path: &Path) -> Result<bool> {
     Ok(!status.trim().is_empty())
 }
 
+/// Check if the working tree has no staged, unstaged, or untracked changes
+pub fn is_working_tree_clean(path: &Path) -> Result<bool> {
+    Ok(!has_uncommitted_changes(path)?)
+}
+
+/// Get list of files with uncommitted changes (staged, unstaged, or untracked)
+pub fn get_uncommitted_files(path: &Path) -> Result<Vec<String>> {
+    get_uncommitted_files_matching(path, &[])
+}
+
+/// Get the uncommitted files matching any of the globs (e.g. `src/**`); no globs means every file
+pub fn get_uncommitted_files_matching(path: &Path, globs: &[String]) -> Result<Vec<String>> {
+    let pathspecs = globs.iter().map(|glob| format!(":(glob){}", glob));
+    let output = Command::new("git")
+        .args(["status", "--porcelain", "-z", "--"])
+        .args(pathspecs)
+        .current_dir(path)
+        .output()
+        .context("Failed to run git status")?;
+
+    if !output.status.success() {
+        bail!("git status failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    // -z records are "XY <path>" with unquoted paths; a rename or copy is followed by a
+    // record holding its original path, which is skipped
+    let status = String::from_utf8_lossy(&output.stdout);
+    let mut records = status.split('\0').filter(|record| !record.is_empty());
+    let mut files = Vec::new();
+    while let Some(record) = records.next() {
+        let Some(file) = record.get(3..) else { continue };
+        if record.starts_with(['R', 'C']) {
+            records.next();
+        }
+        files.push(file.to_string());
+    }
+    Ok(files)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git").args(args).current_dir(dir).output().unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_get_uncommitted_files_matching() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::create_dir_all(tmp.path().join("src/bin")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("generated")).unwrap();
+        std::fs::write(tmp.path().join("src/bin/tool.rs"), "fn main() {}").unwrap();
+        std::fs::write(tmp.path().join("generated/schema.json"), "{}").unwrap();
+
+        let globs = vec!["src/**".to_string(), "*.md".to_string()];
+        assert_eq!(get_uncommitted_files_matching(tmp.path(), &globs).unwrap(), vec!["src/"]);
+        assert_eq!(get_uncommitted_files(tmp.path()).unwrap(), vec!["generated/", "src/"]);
+    }
+
+    fn setup_repo_with_commit(dir: &Path) {
+        git(dir, &["init"]);
+        git(dir, &["config", "user.email", "test@test.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("README.md"), "# Test").unwrap();
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-m", "Initial commit"]);
+    }
 
     #[test]
     fn test_is_git_repo_current_dir() {
@@ -198,7 +899,7 @@ mod tests {
     fn test_get_latest_tag() {
         // Just verify it doesn't error on the current repo
         let cwd = env::current_dir().unwrap();
-        let result = get_latest_tag(&cwd);
+        let result = get_latest_tag(&cwd, "v", false);
         assert!(result.is_ok());
     }
 
@@ -235,4 +936,486 @@ mod tests {
         assert!(result.is_ok());
         // The actual value depends on working tree state
     }
+
+    #[test]
+    fn test_current_branch() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["checkout", "-b", "feature"]);
+
+        assert_eq!(current_branch(tmp.path()).unwrap(), "feature");
+    }
+
+    #[test]
+    fn test_is_detached_head() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        assert!(!is_detached_head(tmp.path()).unwrap());
+
+        git(tmp.path(), &["checkout", "--detach"]);
+        assert!(is_detached_head(tmp.path()).unwrap());
+    }
+
+    #[test]
+    fn test_default_branch_from_remote_head() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["update-ref", "refs/remotes/origin/trunk", "HEAD"]);
+        git(
+            tmp.path(),
+            &["symbolic-ref", "refs/remotes/origin/HEAD", "refs/remotes/origin/trunk"],
+        );
+
+        assert_eq!(default_branch(tmp.path(), "origin").unwrap(), Some("trunk".to_string()));
+    }
+
+    #[test]
+    fn test_default_branch_remote_head_not_set() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+
+        assert_eq!(default_branch(tmp.path(), "origin").unwrap(), None);
+    }
+
+    #[test]
+    fn test_commit_files_and_field() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "notes").unwrap();
+        git(tmp.path(), &["add", "-A"]);
+        git(tmp.path(), &["commit", "-m", "Bump version to v1.0.0"]);
+
+        assert_eq!(commit_files(tmp.path(), "HEAD").unwrap(), vec!["Cargo.toml", "notes.txt"]);
+        assert_eq!(commit_field(tmp.path(), "HEAD", "%s").unwrap(), "Bump version to v1.0.0");
+    }
+
+    #[test]
+    fn test_get_uncommitted_files() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        assert!(get_uncommitted_files(tmp.path()).unwrap().is_empty());
+
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        std::fs::write(tmp.path().join("new.txt"), "new").unwrap();
+
+        let files = get_uncommitted_files(tmp.path()).unwrap();
+        assert_eq!(files, vec!["README.md".to_string(), "new.txt".to_string()]);
+
+        // Renames report the new path, and unusual names come back unquoted
+        git(tmp.path(), &["mv", "README.md", "docs \"café\".md"]);
+        let files = get_uncommitted_files(tmp.path()).unwrap();
+        assert_eq!(files, vec!["docs \"café\".md".to_string(), "new.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_repo_root_from_subdirectory() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        let sub = tmp.path().join("app");
+        std::fs::create_dir_all(&sub).unwrap();
+
+        let root = repo_root(&sub).unwrap();
+        assert_eq!(root.canonicalize().unwrap(), tmp.path().canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_get_latest_tag_release_beats_prerelease() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["tag", "v1.3.0-rc.2"]);
+        git(tmp.path(), &["tag", "v1.3.0"]);
+        git(tmp.path(), &["tag", "v1.2.9"]);
+
+        assert_eq!(get_latest_tag(tmp.path(), "v", false).unwrap(), Some("v1.3.0".to_string()));
+    }
+
+    #[test]
+    fn test_get_latest_tag_with_prefix() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["tag", "v9.0.0"]);
+        git(tmp.path(), &["tag", "mycrate-v1.2.0"]);
+        git(tmp.path(), &["tag", "mycrate-v1.10.0"]);
+        git(tmp.path(), &["tag", "mycrate-vendor-v5.0.0"]);
+
+        assert_eq!(
+            get_latest_tag(tmp.path(), "mycrate-v", false).unwrap(),
+            Some("mycrate-v1.10.0".to_string())
+        );
+        assert_eq!(get_latest_tag(tmp.path(), "v", false).unwrap(), Some("v9.0.0".to_string()));
+    }
+
+    #[test]
+    fn test_get_latest_tag_four_segments_need_revisions() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["tag", "v1.2.3"]);
+        git(tmp.path(), &["tag", "v1.2.3.4"]);
+
+        assert_eq!(get_latest_tag(tmp.path(), "v", false).unwrap(), Some("v1.2.3".to_string()));
+        assert_eq!(get_latest_tag(tmp.path(), "v", true).unwrap(), Some("v1.2.3.4".to_string()));
+    }
+
+    #[test]
+    fn test_create_tag_force_moves_tag() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        create_tag(tmp.path(), "v1.0.0", "first", &TagOptions::default()).unwrap();
+
+        // Without force, re-creating fails
+        assert!(create_tag(tmp.path(), "v1.0.0", "again", &TagOptions::default()).is_err());
+
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        git(tmp.path(), &["commit", "-am", "Second commit"]);
+        create_tag(
+            tmp.path(),
+            "v1.0.0",
+            "moved",
+            &TagOptions {
+                force: true,
+                ..TagOptions::default()
+            },
+        )
+        .unwrap();
+        assert!(head_has_tag(tmp.path()).unwrap());
+    }
+
+    #[test]
+    fn test_show_file_at_head() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Uncommitted").unwrap();
+
+        assert_eq!(show_file(tmp.path(), "HEAD", "./README.md").unwrap(), "# Test");
+    }
+
+    #[test]
+    fn test_resolve_commit() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        create_tag(tmp.path(), "v1.0.0", "release", &TagOptions::default()).unwrap();
+
+        let head = resolve_commit(tmp.path(), "HEAD").unwrap();
+        assert_eq!(head.len(), 40);
+        // Annotated tags resolve to the commit, not the tag object
+        assert_eq!(resolve_commit(tmp.path(), "v1.0.0").unwrap(), head);
+        assert!(resolve_commit(tmp.path(), "does-not-exist").is_err());
+
+        let short = head_sha(tmp.path()).unwrap();
+        assert!(short.len() >= 7 && head.starts_with(&short), "{} is not a prefix of {}", short, head);
+    }
+
+    #[test]
+    fn test_create_tag_signing_failure_is_surfaced() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+
+        let options = TagOptions {
+            local_user: Some("no-such-key@example.invalid".to_string()),
+            ..TagOptions::default()
+        };
+        let err = create_tag(tmp.path(), "v1.0.0", "Release", &options).unwrap_err();
+        assert!(err.to_string().contains("git tag failed"), "unexpected error: {}", err);
+        assert!(!tag_exists(tmp.path(), "v1.0.0").unwrap());
+    }
+
+    #[test]
+    fn test_signing_failure_hint() {
+        let missing_key = "error: gpg failed to sign the data\ngpg: skipped \"abc\": No secret key\n";
+        assert!(signing_failure_hint(missing_key).unwrap().contains("user.signingkey"));
+        assert!(signing_failure_hint("error: cannot run gpg: No such file or directory").unwrap().contains("install"));
+        assert!(signing_failure_hint("error: gpg failed to sign the data").unwrap().contains("GPG_TTY"));
+        assert_eq!(signing_failure_hint("fatal: tag 'v1.0.0' already exists"), None);
+    }
+
+    #[test]
+    fn test_push_sends_branch_and_tags() {
+        let tmp = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["remote", "add", "origin", &remote.path().display().to_string()]);
+        git(tmp.path(), &["push", "-u", "origin", "HEAD"]);
+
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        git(tmp.path(), &["commit", "-am", "Second commit"]);
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        git(tmp.path(), &["tag", "experiment"]);
+
+        push(tmp.path(), "origin", &["v1.0.0"], false).unwrap();
+        assert!(is_head_pushed(tmp.path()).unwrap());
+        assert!(tag_exists(remote.path(), "v1.0.0").unwrap());
+        assert!(!tag_exists(remote.path(), "experiment").unwrap(), "MUST push only the named tags");
+
+        assert!(push(tmp.path(), "nonexistent", &[], false).is_err());
+    }
+
+    #[test]
+    fn test_push_tags_leaves_branch_alone() {
+        let tmp = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["remote", "add", "origin", &remote.path().display().to_string()]);
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        git(tmp.path(), &["tag", "experiment"]);
+
+        let tags = ["v1.0.0"];
+        push_tags(tmp.path(), "origin", &tags, false, false).unwrap();
+        assert!(tag_exists(remote.path(), "v1.0.0").unwrap());
+        assert!(!tag_exists(remote.path(), "experiment").unwrap(), "MUST push only the named tags");
+        assert!(resolve_commit(remote.path(), "HEAD").is_err(), "MUST not push the branch");
+
+        // A tag moved locally (--tag-only --force) only replaces the remote one with force
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        git(tmp.path(), &["commit", "-am", "Second commit"]);
+        git(tmp.path(), &["tag", "-f", "-a", "v1.0.0", "-m", "Release"]);
+        assert!(push_tags(tmp.path(), "origin", &tags, false, false).is_err());
+        push_tags(tmp.path(), "origin", &tags, true, false).unwrap();
+        assert_eq!(
+            resolve_commit(remote.path(), "v1.0.0").unwrap(),
+            resolve_commit(tmp.path(), "HEAD").unwrap()
+        );
+
+        let err = push_tags(tmp.path(), "upstream", &tags, false, false).unwrap_err();
+        assert!(err.to_string().contains("No remote named 'upstream'"), "{}", err);
+    }
+
+    #[test]
+    fn test_delete_tag_locally_and_on_remote() {
+        let tmp = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["remote", "add", "origin", &remote.path().display().to_string()]);
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        create_tag(tmp.path(), "v1.1.0", "Release", &TagOptions::default()).unwrap();
+        push_tags(tmp.path(), "origin", &["v1.0.0", "v1.1.0"], false, false).unwrap();
+
+        delete_tag(tmp.path(), "v1.0.0", None).unwrap();
+        assert!(!tag_exists(tmp.path(), "v1.0.0").unwrap());
+        assert!(tag_exists(remote.path(), "v1.0.0").unwrap(), "MUST leave the remote alone without a remote");
+
+        delete_tag(tmp.path(), "v1.1.0", Some("origin")).unwrap();
+        assert!(!tag_exists(tmp.path(), "v1.1.0").unwrap());
+        assert!(!tag_exists(remote.path(), "v1.1.0").unwrap());
+    }
+
+    #[test]
+    fn test_commits_since() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        git(tmp.path(), &["commit", "-am", "feat: second"]);
+
+        assert_eq!(commits_since(tmp.path(), Some("v1.0.0")).unwrap(), vec!["feat: second"]);
+        assert_eq!(commits_since(tmp.path(), None).unwrap(), vec!["feat: second", "Initial commit"]);
+    }
+
+    #[test]
+    fn test_commit_messages_since_keeps_bodies() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        git(tmp.path(), &["commit", "-am", "fix: second\n\nDetails.\n\nBREAKING CHANGE: config moved"]);
+
+        assert_eq!(
+            commit_messages_since(tmp.path(), Some("v1.0.0")).unwrap(),
+            vec!["fix: second\n\nDetails.\n\nBREAKING CHANGE: config moved"]
+        );
+        assert_eq!(commit_messages_since(tmp.path(), None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_delete_tag_and_remote_has_tag() {
+        let tmp = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["remote", "add", "origin", &remote.path().display().to_string()]);
+
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        assert!(!remote_has_tag(tmp.path(), "origin", "v1.0.0").unwrap());
+        git(tmp.path(), &["push", "origin", "v1.0.0"]);
+        assert!(remote_has_tag(tmp.path(), "origin", "v1.0.0").unwrap());
+        assert!(remote_has_tag(tmp.path(), "nowhere", "v1.0.0").is_err(), "MUST not read an unreachable remote as no");
+
+        delete_tag(tmp.path(), "v1.0.0", None).unwrap();
+        assert!(!tag_exists(tmp.path(), "v1.0.0").unwrap());
+        assert!(delete_tag(tmp.path(), "v1.0.0", None).is_err());
+    }
+
+    #[test]
+    fn test_fetch_tags() {
+        let tmp = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo_with_commit(tmp.path());
+        git(tmp.path(), &["remote", "add", "origin", &remote.path().display().to_string()]);
+        create_tag(tmp.path(), "v1.0.0", "Release", &TagOptions::default()).unwrap();
+        git(tmp.path(), &["push", "origin", "HEAD", "v1.0.0"]);
+        delete_tag(tmp.path(), "v1.0.0", None).unwrap();
+
+        fetch_tags(tmp.path(), "origin").unwrap();
+        assert!(tag_exists(tmp.path(), "v1.0.0").unwrap());
+
+        assert!(fetch_tags(tmp.path(), "nonexistent").is_err());
+    }
+
+    #[test]
+    fn test_create_lightweight_tag() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        let options = TagOptions {
+            lightweight: true,
+            ..TagOptions::default()
+        };
+
+        create_tag(tmp.path(), "v1.0.0", "Release v1.0.0", &options).unwrap();
+        let output = Command::new("git")
+            .args(["cat-file", "-t", "v1.0.0"])
+            .current_dir(tmp.path())
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "commit");
+    }
+
+    #[test]
+    fn test_amend_commit_replaces_message() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        stage_all(tmp.path(), StageMode::All).unwrap();
+
+        amend_commit(tmp.path(), "Release v1.0.0", &CommitOptions::default()).unwrap();
+        assert_eq!(commits_since(tmp.path(), None).unwrap(), vec!["Release v1.0.0"]);
+        assert!(!has_uncommitted_changes(tmp.path()).unwrap());
+    }
+
+    #[test]
+    fn test_stage_files_only_stages_listed_files() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        std::fs::create_dir(tmp.path().join("generated")).unwrap();
+        std::fs::write(tmp.path().join("generated/schema.json"), "{}").unwrap();
+
+        stage_files(&tmp.path().join("generated"), &["README.md"]).unwrap();
+        assert_eq!(get_staged_files(tmp.path()).unwrap(), vec!["README.md"]);
+    }
+
+    #[test]
+    fn test_stage_modes() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        std::fs::write(tmp.path().join("generated.txt"), "noise").unwrap();
+        std::fs::create_dir(tmp.path().join("member")).unwrap();
+        std::fs::write(tmp.path().join("member/Cargo.toml"), "[package]").unwrap();
+
+        stage_all(tmp.path(), StageMode::CargoOnly).unwrap();
+        assert_eq!(get_staged_files(tmp.path()).unwrap(), vec!["member/Cargo.toml"]);
+
+        stage_all(tmp.path(), StageMode::Tracked).unwrap();
+        assert_eq!(get_staged_files(tmp.path()).unwrap(), vec!["README.md", "member/Cargo.toml"]);
+
+        stage_all(tmp.path(), StageMode::All).unwrap();
+        assert_eq!(get_staged_files(tmp.path()).unwrap(), vec!["README.md", "generated.txt", "member/Cargo.toml"]);
+    }
+
+    #[test]
+    fn test_validate_author() {
+        assert!(validate_author("Release Bot <bot@example.com>").is_ok());
+        assert!(validate_author("bot@example.com").is_err());
+        assert!(validate_author("Release Bot").is_err());
+        assert!(validate_author(" <bot@example.com>").is_err());
+        assert!(validate_author("Release Bot <not-an-email>").is_err());
+    }
+
+    #[test]
+    fn test_commit_with_author() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        stage_all(tmp.path(), StageMode::All).unwrap();
+
+        let options = CommitOptions {
+            author: Some("Release Bot <bot@example.com>".to_string()),
+            ..CommitOptions::default()
+        };
+        commit(tmp.path(), "Release v1.0.0", &options).unwrap();
+
+        let output = Command::new("git")
+            .args(["log", "-1", "--pretty=%an <%ae>"])
+            .current_dir(tmp.path())
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Release Bot <bot@example.com>");
+    }
+
+    #[test]
+    fn test_commit_with_body() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        stage_all(tmp.path(), StageMode::All).unwrap();
+
+        let options = CommitOptions {
+            body: Some("- Faster startup\n- Fixed --dry-run output".to_string()),
+            ..CommitOptions::default()
+        };
+        commit(tmp.path(), "Release v1.0.0", &options).unwrap();
+
+        let output = Command::new("git")
+            .args(["log", "-1", "--pretty=%B"])
+            .current_dir(tmp.path())
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "Release v1.0.0\n\n- Faster startup\n- Fixed --dry-run output"
+        );
+    }
+
+    #[test]
+    fn test_commit_no_verify_skips_hooks() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo_with_commit(tmp.path());
+        let hook = tmp.path().join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        stage_all(tmp.path(), StageMode::All).unwrap();
+        assert!(commit(tmp.path(), "Blocked", &CommitOptions::default()).is_err());
+
+        let options = CommitOptions {
+            no_verify: true,
+            ..CommitOptions::default()
+        };
+        commit(tmp.path(), "Release v1.0.0", &options).unwrap();
+    }
+
+    #[test]
+    fn test_ahead_behind() {
+        let tmp = TempDir::new().unwrap();
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        setup_repo_with_commit(tmp.path());
+        assert!(ahead_behind(tmp.path()).is_err(), "no upstream yet");
+
+        git(tmp.path(), &["remote", "add", "origin", &remote.path().display().to_string()]);
+        git(tmp.path(), &["push", "-u", "origin", "HEAD"]);
+        assert_eq!(ahead_behind(tmp.path()).unwrap(), (0, 0));
+
+        std::fs::write(tmp.path().join("README.md"), "# Changed").unwrap();
+        git(tmp.path(), &["commit", "-am", "Second commit"]);
+        git(tmp.path(), &["push", "origin", "HEAD"]);
+        git(tmp.path(), &["reset", "--hard", "HEAD~1"]);
+        fetch(tmp.path()).unwrap();
+        assert_eq!(ahead_behind(tmp.path()).unwrap(), (0, 1));
+    }
 }
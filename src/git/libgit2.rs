@@ -0,0 +1,328 @@
+use eyre::{Context, Result, bail};
+use chrono::DateTime;
+use git2::{IndexAddOption, Repository, Signature, Time};
+use std::path::Path;
+
+use super::{CommitOptions, StageMode, TagOptions};
+
+/// Open the repository containing the path
+fn open(path: &Path) -> Result<Repository> {
+    Repository::discover(path).context(format!("Failed to open git repository at {}", path.display()))
+}
+
+/// Clean up a message the way `git commit -m` does (trailing whitespace, final newline)
+fn prettify(message: &str) -> Result<String> {
+    git2::message_prettify(message, None).context("Failed to clean up message")
+}
+
+/// Check if a tag exists
+pub fn tag_exists(path: &Path, tag: &str) -> Result<bool> {
+    let repo = open(path)?;
+    Ok(repo.find_reference(&format!("refs/tags/{}", tag)).is_ok())
+}
+
+/// Get the latest semver tag with the given prefix
+pub fn get_latest_tag(path: &Path, prefix: &str, revisions: bool) -> Result<Option<String>> {
+    let repo = open(path)?;
+    let tags = repo
+        .tag_names(Some(&format!("{}*", prefix)))
+        .context("Failed to list tags")?;
+    // Tag names that aren't valid UTF-8 can't carry a version, so skip them
+    Ok(super::highest_tag(tags.iter().filter_map(|name| name.ok().flatten()), prefix, revisions))
+}
+
+/// Stage changes for the release commit, including deletions
+pub fn stage_all(path: &Path, mode: StageMode) -> Result<()> {
+    let repo = open(path)?;
+    let mut index = repo.index().context("Failed to read the index")?;
+    let patterns: &[&str] = match mode {
+        StageMode::All | StageMode::Tracked => &["*"],
+        StageMode::CargoOnly => &["Cargo.toml", "Cargo.lock", "*/Cargo.toml", "*/Cargo.lock"],
+    };
+    if mode != StageMode::Tracked {
+        index
+            .add_all(patterns, IndexAddOption::DEFAULT, None)
+            .context("Failed to stage changes")?;
+    }
+    index.update_all(patterns, None).context("Failed to stage changes")?;
+    index.write().context("Failed to write the index")?;
+    Ok(())
+}
+
+/// Stage exactly these files (paths relative to the repository root)
+pub fn stage_files(path: &Path, files: &[&str]) -> Result<()> {
+    let repo = open(path)?;
+    let mut index = repo.index().context("Failed to read the index")?;
+    index
+        .add_all(files, IndexAddOption::DISABLE_PATHSPEC_MATCH, None)
+        .context("Failed to stage changes")?;
+    index.write().context("Failed to write the index")?;
+    Ok(())
+}
+
+/// Refuse what libgit2 can't do for a commit: run the pre-commit/commit-msg hooks
+/// (unless --no-verify skips them anyway) or sign it (commit.gpgsign)
+fn ensure_plain_commit(repo: &Repository, options: &CommitOptions) -> Result<()> {
+    let config = repo.config().context("Failed to read git config")?;
+    if config.get_bool("commit.gpgsign").unwrap_or(false) {
+        bail!("commit.gpgsign is set but libgit2 can't sign commits; run without --backend libgit2");
+    }
+    if options.no_verify {
+        return Ok(());
+    }
+    let hooks = match config.get_path("core.hooksPath") {
+        Ok(dir) if dir.is_absolute() => dir,
+        Ok(dir) => repo.workdir().unwrap_or(repo.path()).join(dir),
+        Err(_) => repo.path().join("hooks"),
+    };
+    for hook in ["pre-commit", "commit-msg"] {
+        if hooks.join(hook).exists() {
+            bail!(
+                "libgit2 doesn't run the {} hook; run without --backend libgit2, or pass --no-verify to skip it",
+                hook
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Create a commit of the index on HEAD
+pub fn commit(path: &Path, message: &str, options: &CommitOptions) -> Result<()> {
+    let repo = open(path)?;
+    ensure_plain_commit(&repo, options)?;
+    let committer = identity(&repo, "COMMITTER", env_var)?;
+    let author = match options.author {
+        Some(ref author) => parse_author(author)?,
+        None => identity(&repo, "AUTHOR", env_var)?,
+    };
+
+    let mut index = repo.index().context("Failed to read the index")?;
+    let tree_id = index.write_tree().context("Failed to write the tree")?;
+    let tree = repo.find_tree(tree_id).context("Failed to find the tree")?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit().context("HEAD is not a commit")?),
+        Err(_) => None,
+    };
+    // Like a second git commit -m: the body is its own paragraph
+    let message = match options.body {
+        Some(ref body) => format!("{}\n\n{}", message, body),
+        None => message.to_string(),
+    };
+
+    repo.commit(
+        Some("HEAD"),
+        &author,
+        &committer,
+        &prettify(&message)?,
+        &tree,
+        &parent.iter().collect::<Vec<_>>(),
+    )
+    .context("Failed to create commit")?;
+    Ok(())
+}
+
+/// Create an annotated (or lightweight) tag at HEAD
+pub fn create_tag(path: &Path, tag: &str, message: &str, options: &TagOptions) -> Result<()> {
+    if options.sign || options.local_user.is_some() {
+        bail!("Signed tags need the git backend; run without --backend libgit2");
+    }
+
+    let repo = open(path)?;
+    let target = options.target.as_deref().unwrap_or("HEAD");
+    let head = repo.revparse_single(target).context(format!("Failed to resolve {}", target))?;
+    if options.lightweight {
+        repo.tag_lightweight(tag, &head, options.force)
+            .context(format!("Failed to create tag {}", tag))?;
+    } else {
+        let tagger = identity(&repo, "COMMITTER", env_var)?;
+        repo.tag(tag, &head, &tagger, &prettify(message)?, options.force)
+            .context(format!("Failed to create tag {}", tag))?;
+    }
+    Ok(())
+}
+
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(key).ok()
+}
+
+/// The author or committer signature the way git builds it: GIT_<ROLE>_NAME, GIT_<ROLE>_EMAIL and
+/// GIT_<ROLE>_DATE override the configured identity and the current time (the tagger is the committer)
+fn identity(repo: &Repository, role: &str, env: impl Fn(&str) -> Option<String>) -> Result<Signature<'static>> {
+    let name = env(&format!("GIT_{}_NAME", role));
+    let email = env(&format!("GIT_{}_EMAIL", role));
+    let date = env(&format!("GIT_{}_DATE", role));
+    if name.is_none() && email.is_none() && date.is_none() {
+        return repo.signature().context("No git identity configured (user.name/user.email)");
+    }
+
+    let config = repo.config().context("Failed to read git config")?;
+    let name = name.or_else(|| config.get_string("user.name").ok());
+    let email = email.or_else(|| config.get_string("user.email").ok());
+    let (Some(name), Some(email)) = (name, email) else {
+        bail!("No git identity configured (user.name/user.email or GIT_{}_NAME/GIT_{}_EMAIL)", role, role);
+    };
+    let signature = match date {
+        Some(date) => Signature::new(&name, &email, &parse_git_date(&date, role)?),
+        None => Signature::now(&name, &email),
+    };
+    signature.context(format!("Invalid git identity '{} <{}>'", name, email))
+}
+
+/// Parse a GIT_*_DATE the way git does for its common forms: `[@]<seconds> <+hhmm>`, RFC 2822 or ISO 8601
+fn parse_git_date(date: &str, role: &str) -> Result<Time> {
+    let date = date.trim();
+    if let Some((seconds, offset)) = date.trim_start_matches('@').split_once(' ')
+        && let Ok(seconds) = seconds.parse::<i64>()
+        && let Ok(offset) = DateTime::parse_from_str(&format!("0 {}", offset.trim()), "%s %z")
+    {
+        return Ok(Time::new(seconds, offset.offset().local_minus_utc() / 60));
+    }
+    let parsed = DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .or_else(|_| DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S %z"))
+        .with_context(|| {
+            format!(
+                "Unsupported GIT_{}_DATE '{}'; use '@<seconds> <+hhmm>', RFC 2822 or ISO 8601 with a UTC offset",
+                role, date
+            )
+        })?;
+    Ok(Time::new(parsed.timestamp(), parsed.offset().local_minus_utc() / 60))
+}
+
+/// Turn a validated `Name <email>` author into a signature stamped now
+fn parse_author(author: &str) -> Result<Signature<'static>> {
+    super::validate_author(author)?;
+    let (name, email) = author
+        .trim_end_matches('>')
+        .split_once(" <")
+        .expect("validated author has an email");
+    Signature::now(name.trim(), email).context(format!("Invalid author '{}'", author))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git").args(args).current_dir(dir).output().unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn setup_repo(dir: &Path) {
+        git(dir, &["init"]);
+        git(dir, &["config", "user.email", "test@test.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+    }
+
+    #[test]
+    fn test_commit_refuses_hooks_and_gpgsign() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Test").unwrap();
+        std::fs::write(tmp.path().join(".git/hooks/commit-msg"), "#!/bin/sh\nexit 1\n").unwrap();
+        stage_all(tmp.path(), StageMode::All).unwrap();
+
+        let err = commit(tmp.path(), "Initial commit", &CommitOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("commit-msg hook"), "{}", err);
+
+        git(tmp.path(), &["config", "commit.gpgsign", "true"]);
+        let options = CommitOptions {
+            no_verify: true,
+            ..Default::default()
+        };
+        let err = commit(tmp.path(), "Initial commit", &options).unwrap_err();
+        assert!(err.to_string().contains("commit.gpgsign"), "{}", err);
+
+        git(tmp.path(), &["config", "commit.gpgsign", "false"]);
+        commit(tmp.path(), "Initial commit", &options).unwrap();
+        assert_eq!(git(tmp.path(), &["log", "-1", "--pretty=%s"]), "Initial commit");
+    }
+
+    #[test]
+    fn test_stage_commit_and_tag() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "# Test").unwrap();
+
+        stage_all(tmp.path(), StageMode::All).unwrap();
+        commit(tmp.path(), "Initial commit", &CommitOptions::default()).unwrap();
+        assert_eq!(git(tmp.path(), &["log", "-1", "--pretty=%s"]), "Initial commit");
+
+        std::fs::remove_file(tmp.path().join("README.md")).unwrap();
+        stage_all(tmp.path(), StageMode::All).unwrap();
+        let options = CommitOptions {
+            author: Some("Release Bot <bot@example.com>".to_string()),
+            ..CommitOptions::default()
+        };
+        commit(tmp.path(), "Remove readme", &options).unwrap();
+        assert_eq!(git(tmp.path(), &["log", "-1", "--pretty=%an <%ae>"]), "Release Bot <bot@example.com>");
+        assert_eq!(git(tmp.path(), &["status", "--porcelain"]), "");
+
+        assert!(!tag_exists(tmp.path(), "v1.0.0").unwrap());
+        create_tag(tmp.path(), "v1.0.0", "Release v1.0.0", &TagOptions::default()).unwrap();
+        assert!(tag_exists(tmp.path(), "v1.0.0").unwrap());
+        assert_eq!(git(tmp.path(), &["cat-file", "-t", "v1.0.0"]), "tag");
+        assert!(create_tag(tmp.path(), "v1.0.0", "Again", &TagOptions::default()).is_err());
+
+        let lightweight = TagOptions {
+            lightweight: true,
+            ..TagOptions::default()
+        };
+        create_tag(tmp.path(), "v1.1.0-rc.1", "", &lightweight).unwrap();
+        assert_eq!(git(tmp.path(), &["cat-file", "-t", "v1.1.0-rc.1"]), "commit");
+        assert_eq!(get_latest_tag(tmp.path(), "v", false).unwrap(), Some("v1.1.0-rc.1".to_string()));
+    }
+
+    #[test]
+    fn test_stage_cargo_only() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo(tmp.path());
+        std::fs::create_dir(tmp.path().join("member")).unwrap();
+        std::fs::write(tmp.path().join("member/Cargo.toml"), "[package]").unwrap();
+        std::fs::write(tmp.path().join("Cargo.toml"), "[workspace]").unwrap();
+        std::fs::write(tmp.path().join("generated.txt"), "noise").unwrap();
+
+        stage_all(tmp.path(), StageMode::CargoOnly).unwrap();
+        assert_eq!(git(tmp.path(), &["diff", "--cached", "--name-only"]), "Cargo.toml\nmember/Cargo.toml");
+    }
+
+    #[test]
+    fn test_identity_from_environment() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo(tmp.path());
+        let repo = open(tmp.path()).unwrap();
+
+        let configured = identity(&repo, "COMMITTER", |_| None).unwrap();
+        assert_eq!((configured.name().unwrap(), configured.email().unwrap()), ("Test", "test@test.com"));
+
+        let env = |key: &str| match key {
+            "GIT_COMMITTER_NAME" => Some("CI Bot".to_string()),
+            "GIT_COMMITTER_DATE" => Some("@1700000000 +0200".to_string()),
+            _ => None,
+        };
+        let committer = identity(&repo, "COMMITTER", env).unwrap();
+        assert_eq!((committer.name().unwrap(), committer.email().unwrap()), ("CI Bot", "test@test.com"));
+        assert_eq!((committer.when().seconds(), committer.when().offset_minutes()), (1700000000, 120));
+        assert_eq!(identity(&repo, "AUTHOR", env).unwrap().name().unwrap(), "Test");
+
+        let iso = parse_git_date("2023-11-14T22:13:20-05:00", "COMMITTER").unwrap();
+        assert_eq!((iso.seconds(), iso.offset_minutes()), (1700018000, -300));
+        let rfc2822 = parse_git_date("Tue, 14 Nov 2023 22:13:20 +0000", "COMMITTER").unwrap();
+        assert_eq!(rfc2822.seconds(), 1700000000);
+        assert!(parse_git_date("yesterday", "COMMITTER").is_err());
+    }
+
+    #[test]
+    fn test_signed_tags_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        setup_repo(tmp.path());
+        let options = TagOptions {
+            sign: true,
+            ..TagOptions::default()
+        };
+        assert!(create_tag(tmp.path(), "v1.0.0", "Release", &options).is_err());
+    }
+}
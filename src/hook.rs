@@ -0,0 +1,66 @@
+use eyre::{Context, Result, bail};
+use log::info;
+use std::path::Path;
+use std::process::Command;
+
+/// Run a user hook (--pre-hook/--post-hook) through `sh -c` in the repository
+/// The hook sees BUMP_VERSION and BUMP_TAG; its output is logged and included in any error
+pub fn run(name: &str, command: &str, dir: &Path, version: &str, tag: &str) -> Result<()> {
+    info!("Running {}: {}", name, command);
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(dir)
+        .env("BUMP_VERSION", version)
+        .env("BUMP_TAG", tag)
+        .output()
+        .context(format!("Failed to run {} `{}`", name, command))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stdout.lines().chain(stderr.lines()) {
+        info!("{}: {}", name, line);
+    }
+
+    if !output.status.success() {
+        bail!(
+            "{} `{}` failed ({}):\n{}{}",
+            name,
+            command,
+            output.status,
+            stdout,
+            stderr
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_passes_version_and_tag() {
+        let dir = TempDir::new().unwrap();
+        run(
+            "pre-hook",
+            "test \"$BUMP_VERSION\" = 1.2.4 && test \"$BUMP_TAG\" = v1.2.4 && touch ran",
+            dir.path(),
+            "1.2.4",
+            "v1.2.4",
+        )
+        .unwrap();
+        assert!(dir.path().join("ran").exists());
+    }
+
+    #[test]
+    fn test_run_fails_with_hook_output() {
+        let dir = TempDir::new().unwrap();
+        let err = run("pre-hook", "echo tests failed >&2; exit 3", dir.path(), "1.2.4", "v1.2.4")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("pre-hook `echo tests failed >&2; exit 3` failed"), "{}", err);
+        assert!(err.contains("tests failed"), "{}", err);
+    }
+}
@@ -0,0 +1,85 @@
+use eyre::{Context, Result};
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// A single release recorded in the ledger (one JSON object per line)
+#[derive(Debug, Serialize)]
+pub struct LedgerEntry {
+    pub timestamp: String,
+    pub directory: String,
+    pub old_version: Option<String>,
+    pub new_version: String,
+    pub tag: String,
+    pub commit: String,
+    pub user: String,
+}
+
+impl LedgerEntry {
+    /// Build an entry stamped with the current time and user
+    pub fn new(directory: &Path, old_version: Option<String>, new_version: &str, tag: &str, commit: &str) -> Self {
+        LedgerEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            directory: directory.display().to_string(),
+            old_version,
+            new_version: new_version.to_string(),
+            tag: tag.to_string(),
+            commit: commit.to_string(),
+            user: current_user(),
+        }
+    }
+}
+
+/// Get the current user name from the environment
+pub fn current_user() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Append an entry to the ledger file, creating it if needed
+/// The line is written with a single append so concurrent runs don't interleave
+pub fn append(ledger_path: &Path, entry: &LedgerEntry) -> Result<()> {
+    let mut line = serde_json::to_string(entry).context("Failed to serialize ledger entry")?;
+    line.push('\n');
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(ledger_path)
+        .context(format!("Failed to open ledger {}", ledger_path.display()))?;
+
+    file.write_all(line.as_bytes())
+        .context(format!("Failed to write ledger {}", ledger_path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_writes_one_json_object_per_line() {
+        let dir = TempDir::new().unwrap();
+        let ledger = dir.path().join("releases.jsonl");
+
+        let first = LedgerEntry::new(Path::new("/src/app"), None, "0.1.0", "v0.1.0", "abc123");
+        let second = LedgerEntry::new(Path::new("/src/app"), Some("0.1.0".to_string()), "0.1.1", "v0.1.1", "def456");
+        append(&ledger, &first).unwrap();
+        append(&ledger, &second).unwrap();
+
+        let content = fs::read_to_string(&ledger).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["tag"], "v0.1.0");
+        assert!(lines[0]["old_version"].is_null());
+        assert_eq!(lines[1]["old_version"], "0.1.0");
+        assert_eq!(lines[1]["new_version"], "0.1.1");
+        assert_eq!(lines[1]["commit"], "def456");
+        assert_eq!(lines[1]["directory"], "/src/app");
+    }
+}
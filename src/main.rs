@@ -1,45 +1,144 @@
-use clap::Parser;
-use eyre::{Context, Result, bail};
-use log::info;
-use semver::Version;
+use clap::CommandFactory;
+use eyre::{Context, ContextCompat, Result, bail};
+use log::{info, warn};
+use semver::{BuildMetadata, Version};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::env;
 use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use tempfile::NamedTempFile;
 
 mod cargo;
+mod changelog;
 mod cli;
+mod config;
+mod discover;
 mod git;
+mod hook;
+mod ledger;
+mod npm;
+mod output;
+mod pyproject;
+mod readme;
+mod summary;
+mod template;
 mod version;
+mod version_file;
+
+use cli::{Cli, LogFormat, RemoteCheck};
+use output::{dry_run, output, status, success};
+use summary::Outcome;
+use template::MessageContext;
+use version::{BumpType, VersionScheme};
+
+/// Log writer for --verbose: every record goes to the log file and to stderr
+struct TeeToStderr(fs::File);
+
+impl Write for TeeToStderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stderr().write_all(buf)?;
+        self.0.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()?;
+        self.0.flush()
+    }
+}
+
+thread_local! {
+    /// The directory (and, once known, the version) this thread is releasing, for JSON log entries
+    static LOG_CONTEXT: RefCell<(Option<String>, Option<String>)> = const { RefCell::new((None, None)) };
+}
+
+/// Start tagging this thread's log entries with a directory (clears the version)
+fn log_directory(dir: &Path) {
+    LOG_CONTEXT.with(|c| *c.borrow_mut() = (Some(dir.display().to_string()), None));
+}
 
-use cli::Cli;
-use version::BumpType;
+/// Tag this thread's further log entries with the version being released
+fn log_version(version: &str) {
+    LOG_CONTEXT.with(|c| c.borrow_mut().1 = Some(version.to_string()));
+}
+
+/// One log entry as a JSON line (--log-format json)
+fn json_log_line(record: &log::Record) -> String {
+    let (directory, version) = LOG_CONTEXT.with(|c| c.borrow().clone());
+    serde_json::json!({
+        "timestamp": chrono::Local::now().to_rfc3339(),
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+        "directory": directory,
+        "version": version,
+    })
+    .to_string()
+}
 
-fn setup_logging() -> Result<()> {
-    let log_dir = dirs::data_local_dir()
+/// The default log file, ~/.local/share/bump/logs/bump.log
+fn default_log_file() -> PathBuf {
+    dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("bump")
-        .join("logs");
-
-    fs::create_dir_all(&log_dir).context("Failed to create log directory")?;
+        .join("logs")
+        .join("bump.log")
+}
 
-    let log_file = log_dir.join("bump.log");
+/// Open the log file for appending, creating its directory first
+fn open_log_file(log_file: &Path) -> Result<fs::File> {
+    if let Some(log_dir) = log_file.parent() {
+        fs::create_dir_all(log_dir).context(format!("Failed to create log directory {}", log_dir.display()))?;
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_file)
+        .context(format!("Failed to open log file {}", log_file.display()))
+}
 
-    let target = Box::new(
-        fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_file)
-            .context("Failed to open log file")?,
-    );
+/// Log to --log-file (or the default file), or nowhere with --no-log
+/// A log file that can't be opened (e.g. a read-only home) falls back to stderr rather than aborting
+fn setup_logging(cli: &Cli, verbose: bool) {
+    let log_file = cli.log_file.clone().unwrap_or_else(default_log_file);
+    let file = if cli.no_log {
+        None
+    } else {
+        match open_log_file(&log_file) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                status!("Warning: {:#}; logging to stderr instead", e);
+                None
+            }
+        }
+    };
 
-    env_logger::Builder::from_default_env()
-        .target(env_logger::Target::Pipe(target))
-        .init();
+    // --verbose raises the level to info (RUST_LOG still wins) and echoes the log to stderr
+    let mut builder = env_logger::Builder::new();
+    if verbose {
+        builder.filter_level(log::LevelFilter::Info);
+    }
+    let target = match file {
+        Some(file) if verbose => env_logger::Target::Pipe(Box::new(TeeToStderr(file))),
+        Some(file) => env_logger::Target::Pipe(Box::new(file)),
+        None if cli.no_log && !verbose => env_logger::Target::Pipe(Box::new(io::sink())),
+        None => env_logger::Target::Stderr,
+    };
+    builder.target(target);
+    if cli.log_format == LogFormat::Json {
+        builder.format(|buf, record| writeln!(buf, "{}", json_log_line(record)));
+    }
+    builder.parse_default_env().init();
 
-    info!("Logging initialized, writing to: {}", log_file.display());
-    Ok(())
+    if !cli.no_log {
+        info!("Logging initialized, writing to: {}", log_file.display());
+    }
 }
 
 /// Prompt user for commit message using an editor (like git does)
@@ -100,6 +199,154 @@ fn prompt_commit_message_with_editor(staged_files: &[String]) -> Result<String>
     Ok(message)
 }
 
+/// Parse an answer to the bump-type prompt; an empty answer means patch
+fn parse_bump_choice(answer: &str) -> Option<BumpType> {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "1" | "major" => Some(BumpType::Major),
+        "2" | "minor" => Some(BumpType::Minor),
+        "" | "3" | "patch" => Some(BumpType::Patch),
+        _ => None,
+    }
+}
+
+/// Ask which part of the version to bump, showing the version each choice produces (advanced by --by)
+fn prompt_bump_type(current: &Version, by: u64) -> Result<BumpType> {
+    let choices = [BumpType::Major, BumpType::Minor, BumpType::Patch];
+    eprintln!("Current version: {}", version::format_cargo_version(current));
+    for (i, bump_type) in choices.iter().enumerate() {
+        let next = version::format_cargo_version(&version::bump_version(current, *bump_type, by)?);
+        eprintln!("  {}) {:<5} → {}", i + 1, format!("{:?}", bump_type).to_lowercase(), next);
+    }
+
+    let stdin = io::stdin();
+    loop {
+        eprint!("Select bump type [patch]: ");
+        io::stderr().flush().context("Failed to flush prompt")?;
+
+        let mut answer = String::new();
+        if stdin.lock().read_line(&mut answer).context("Failed to read bump type")? == 0 {
+            // EOF: take the default
+            return Ok(BumpType::Patch);
+        }
+        match parse_bump_choice(&answer) {
+            Some(bump_type) => return Ok(bump_type),
+            None => eprintln!("Please answer 1/major, 2/minor or 3/patch"),
+        }
+    }
+}
+
+/// Parse an answer to a yes/no prompt; only y/yes agree
+fn parse_confirm(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Ask a yes/no question on stderr; no answer (Enter or EOF) means no
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N]: ", question);
+    io::stderr().flush().context("Failed to flush prompt")?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).context("Failed to read answer")?;
+    Ok(parse_confirm(&answer))
+}
+
+/// Describe everything a release is about to do in one line, e.g. "update Cargo.toml, then commit and tag v1.2.3"
+fn plan_summary(files: &[String], cli: &Cli, tags: &str) -> String {
+    let mut steps = Vec::new();
+    if !files.is_empty() {
+        steps.push(format!("update {}", files.join(", ")));
+    }
+    if cli.no_commit {
+        steps.push(format!("stage the changes for {}", tags));
+    } else {
+        let commit = if cli.amend { "amend HEAD" } else { "commit" };
+        match cli.no_tag {
+            true => steps.push(format!("{} {} (no tag)", commit, tags)),
+            false => steps.push(format!("{} and tag {}", commit, tags)),
+        }
+        if cli.push {
+            steps.push(format!("push to {}", cli.remote));
+        } else if cli.push_tags {
+            steps.push(format!("push the tags to {}", cli.remote));
+        }
+    }
+    steps.join(", then ")
+}
+
+/// Show the release plan and ask before carrying it out; declining aborts with nothing changed
+fn confirm_action(summary: &str) -> Result<()> {
+    if !confirm(&format!("About to {} — proceed?", summary))? {
+        bail!("Aborted: nothing was changed");
+    }
+    Ok(())
+}
+
+/// What releasing one directory would do, e.g. "1.2.3 -> 1.2.4: update Cargo.toml, then commit and tag v1.2.4"
+/// Read from the local tree and tags only: nothing is fetched, written or checked against the remote
+fn directory_plan(dir: &Path, cli: &Cli) -> Result<String> {
+    let (cargo_path, manifest_dir, repo_dir) = directory_paths(dir, cli)?;
+    if !cli.package.is_empty() {
+        return Ok(format!("release {} one by one", cli.package.join(", ")));
+    }
+    if cli.all_members {
+        return Ok("release each member with its own version".to_string());
+    }
+    let has_cargo_toml = cargo::cargo_toml_exists(&manifest_dir);
+    let tag_prefix = directory_tag_prefix(&cargo_path, has_cargo_toml, cli)?;
+    if let Some(ref rev) = cli.git_ref {
+        return Ok(format!("tag {} with the version committed there", rev));
+    }
+    if cli.tag_only {
+        let current = cargo::read_version(&cargo_path)?.context("No version found in Cargo.toml")?;
+        return Ok(format!("tag HEAD {}", version::format_tag(&version::parse_version(&current)?, &tag_prefix)));
+    }
+
+    let options = version_options(&repo_dir, &cargo_path, &tag_prefix, cli, cli.bump_type())?;
+    let action = determine_version_action(&repo_dir, &cargo_path, &options)?.with_build(build_metadata(cli)?);
+    let new_tag = version::format_tag(&action.target_version, &tag_prefix);
+    let previous = previous_version(&action, &cargo_path, cli)?.unwrap_or_else(|| "none".to_string());
+    let files = planned_files(&action, has_cargo_toml, &manifest_dir, cli);
+    Ok(format!(
+        "{} -> {}: {}",
+        previous,
+        version::format_cargo_version(&action.target_version),
+        plan_summary(&files, cli, &new_tag)
+    ))
+}
+
+/// Show every directory's plan and ask once, before any of them is released
+fn confirm_combined_plan(targets: &[(PathBuf, Cli)]) -> Result<()> {
+    status!("About to release {} directories:", targets.len());
+    for (dir, dir_cli) in targets {
+        let plan = directory_plan(dir, dir_cli).unwrap_or_else(|e| format!("fails: {:#}", e));
+        status!("  {}: {}", dir.display(), plan);
+    }
+    if !confirm("Proceed?")? {
+        bail!("Aborted: nothing was changed");
+    }
+    Ok(())
+}
+
+/// Read a commit message from a file, stripping only its final line ending (blank lines before it stay)
+fn read_message_file(path: &Path) -> Result<String> {
+    if !path.is_file() {
+        bail!("Message file not found: {}", path.display());
+    }
+
+    let content = fs::read_to_string(path).context(format!("Failed to read message file {}", path.display()))?;
+    let message = content
+        .strip_suffix("\r\n")
+        .or_else(|| content.strip_suffix('\n'))
+        .unwrap_or(&content)
+        .to_string();
+
+    if message.trim().is_empty() {
+        bail!("Message file is empty: {}", path.display());
+    }
+
+    Ok(message)
+}
+
 /// Result of determining what version action to take
 #[derive(Debug)]
 struct VersionAction {
@@ -111,433 +358,4123 @@ struct VersionAction {
     is_initial_tag: bool,
 }
 
+impl VersionAction {
+    /// Attach build metadata to the version being released (--build); Cargo.toml then has to match it
+    fn with_build(mut self, build: Option<BuildMetadata>) -> Self {
+        if let Some(build) = build
+            && self.target_version.build != build
+        {
+            self.target_version.build = build;
+            self.needs_cargo_update = true;
+        }
+        self
+    }
+}
+
+/// Exit code: every directory was released
+const EXIT_SUCCESS: i32 = 0;
+/// Exit code: some directories failed and others succeeded
+const EXIT_PARTIAL_FAILURE: i32 = 1;
+/// Exit code: every directory failed
+const EXIT_ALL_FAILED: i32 = 2;
+/// Exit code: a dry run found a release to make, so CI can detect unreleased changes
+const EXIT_DRY_RUN_PENDING: i32 = 3;
+
+/// Pick the exit code for a run from how its directories went
+/// `pending` is whether a dry run found anything to release
+fn exit_code(successes: usize, failures: usize, pending: bool) -> i32 {
+    match (successes, failures) {
+        (0, 0) => EXIT_SUCCESS,
+        (0, _) => EXIT_ALL_FAILED,
+        (_, 0) if pending => EXIT_DRY_RUN_PENDING,
+        (_, 0) => EXIT_SUCCESS,
+        _ => EXIT_PARTIAL_FAILURE,
+    }
+}
+
 /// The default "untouched" version in Cargo.toml
 const DEFAULT_UNTOUCHED_VERSION: Version = Version::new(0, 1, 0);
 
+/// Inputs that steer how the target version is chosen
+#[derive(Debug)]
+struct VersionOptions {
+    /// Which part of the version to bump
+    bump_type: BumpType,
+    /// How many steps a major, minor or patch bump advances (--by)
+    by: u64,
+    /// Prefix for git tags (usually 'v')
+    tag_prefix: String,
+    /// Explicit target version (--set), bypassing the bump rules
+    set: Option<Version>,
+    /// Promote a pre-release to its stable version (--release) instead of bumping
+    release: bool,
+    /// Fall back to a VERSION file when there is no Cargo.toml (--version-file)
+    version_file: bool,
+    /// Dotted key holding the version in Cargo.toml instead of package.version (--version-key)
+    version_key: Option<String>,
+    /// The version lives outside package.version, so four-segment versions and tags count (see allows_revision)
+    revisions: bool,
+    /// Allow a version at or below the latest tag, bumping from Cargo.toml on a mismatch (--allow-downgrade)
+    allow_downgrade: bool,
+    /// Bump from the latest tag even when Cargo.toml has a version (--from-tag)
+    from_tag: bool,
+    /// Ask for the bump type instead of defaulting to patch (no bump flag given, on a TTY)
+    interactive: bool,
+    /// No bump flag was given, so a non-interactive run bumps patch by default
+    default_bump: bool,
+    /// SemVer bumps by type; CalVer moves to the current month (--calver)
+    scheme: VersionScheme,
+    /// Starting version when none exists yet (--initial; default 0.1.0, or this month for CalVer)
+    initial: Option<Version>,
+}
+
+impl VersionOptions {
+    fn new(bump_type: BumpType) -> Self {
+        VersionOptions {
+            bump_type,
+            by: 1,
+            tag_prefix: version::DEFAULT_TAG_PREFIX.to_string(),
+            set: None,
+            release: false,
+            version_file: false,
+            version_key: None,
+            revisions: false,
+            allow_downgrade: false,
+            from_tag: false,
+            interactive: false,
+            default_bump: false,
+            scheme: VersionScheme::SemVer,
+            initial: None,
+        }
+    }
+}
+
+/// Parse --initial, naming the flag in the error
+fn initial_version(cli: &Cli, revisions: bool) -> Result<Option<Version>> {
+    cli.initial
+        .as_deref()
+        .map(|v| parse_version_for(v, revisions).context(format!("Invalid --initial version '{}'", v)))
+        .transpose()
+}
+
+/// Parse --build, naming the flag in the error
+fn build_metadata(cli: &Cli) -> Result<Option<BuildMetadata>> {
+    cli.build
+        .as_deref()
+        .map(|b| version::parse_build(b).context("Invalid --build"))
+        .transpose()
+}
+
+/// Whether the version lives outside Cargo's package.version (--version-key, or --version-file without a
+/// Cargo.toml), the only places a fourth `.REVISION` segment can go since Cargo rejects `1.2.3.4`
+fn allows_revision(cargo_path: &Path, cli: &Cli) -> bool {
+    cli.version_key.is_some() || (cli.version_file && !cargo_path.exists())
+}
+
+/// Parse a version, accepting a fourth `.REVISION` segment only where allows_revision says it can be stored
+fn parse_version_for(version_str: &str, revisions: bool) -> Result<Version> {
+    if revisions {
+        version::parse_revision_version(version_str)
+    } else {
+        version::parse_version(version_str)
+    }
+}
+
+/// Read the current version from Cargo.toml (at --version-key, if given), or from the
+/// VERSION file beside it when there is no manifest and --version-file is set
+fn read_current_version(cargo_path: &Path, version_file: bool, version_key: Option<&str>) -> Result<Option<String>> {
+    if version_file && !cargo_path.exists() {
+        return version_file::read_version_file(cargo_path.parent().unwrap_or(Path::new(".")));
+    }
+    match version_key {
+        Some(key) => cargo::read_version_at(cargo_path, key),
+        None => cargo::read_version(cargo_path),
+    }
+}
+
+/// Write the new version to Cargo.toml (syncing Cargo.lock unless --no-lock), or to the
+/// VERSION file when there is no manifest and --version-file is set; package.json and pyproject.toml
+/// follow with --sync-npm and --sync-pyproject
+fn write_current_version(cargo_path: &Path, manifest_dir: &Path, cli: &Cli, new_version: &str) -> Result<()> {
+    if cli.version_file && !cargo_path.exists() {
+        version_file::write_version_file(manifest_dir, new_version)?;
+        info!("Updated VERSION to {}", new_version);
+    } else {
+        match cli.version_key {
+            Some(ref key) => cargo::write_version_at(cargo_path, key, new_version)?,
+            None => cargo::write_version(cargo_path, new_version)?,
+        }
+        info!("Updated Cargo.toml to version {}", new_version);
+        if !cli.no_lock {
+            cargo::sync_lockfile(manifest_dir)?;
+        }
+    }
+
+    if cli.sync_npm && npm::write_version(manifest_dir, new_version)? {
+        info!("Updated package.json to version {}", new_version);
+    }
+    if cli.sync_pyproject && pyproject::write_version(manifest_dir, new_version)? {
+        info!("Updated pyproject.toml to version {}", new_version);
+    }
+    Ok(())
+}
+
+/// Show what a dry run would write: the Cargo.toml change as a unified diff, and the Cargo.lock entry to sync
+fn preview_manifest_change(cargo_path: &Path, manifest_dir: &Path, cli: &Cli, new_version: &str) -> Result<()> {
+    // An inherited version is written to the parent workspace's manifest, so preview that one
+    let version_path = match cli.version_key {
+        Some(_) => cargo_path.to_path_buf(),
+        None => cargo::version_manifest(cargo_path)?,
+    };
+    let content = fs::read_to_string(&version_path).context(format!("Failed to read {}", version_path.display()))?;
+    let updated = match cli.version_key {
+        Some(ref key) => cargo::set_version_at(&content, key, new_version)?,
+        None => cargo::set_version(&content, new_version)?,
+    };
+    let name = version_path.strip_prefix(manifest_dir).unwrap_or(&version_path).display().to_string();
+    let diff = output::unified_diff(&content, &updated, &name);
+    if !diff.is_empty() {
+        status!("{}", diff.trim_end());
+    }
+
+    if !cli.no_lock && cargo::has_lockfile(manifest_dir) {
+        match cargo::read_package_name(cargo_path)? {
+            Some(package) => dry_run!("Would sync Cargo.lock: {} → {}", package, new_version),
+            None => dry_run!("Would sync Cargo.lock for the workspace members"),
+        }
+        // Catch a manifest or lockfile cargo can't resolve now, not after the version is written
+        // The check runs against the current Cargo.toml; the new version itself isn't written
+        cargo::check_lockfile_sync(manifest_dir).context("The lockfile sync would fail (pass --no-lock to skip it)")?;
+        dry_run!("Cargo.lock resolves with the current Cargo.toml");
+    }
+    Ok(())
+}
+
 /// Determine what version action to take
-fn determine_version_action(dir: &Path, cargo_path: &Path, bump_type: BumpType) -> Result<VersionAction> {
-    // Get version from Cargo.toml (if it exists)
-    let cargo_version = cargo::read_version(cargo_path)?.and_then(|v| version::parse_version(&v).ok());
+fn determine_version_action(dir: &Path, cargo_path: &Path, options: &VersionOptions) -> Result<VersionAction> {
+    let bump_type = options.bump_type;
+    let tag_prefix = options.tag_prefix.as_str();
+
+    // Get version from Cargo.toml, or the VERSION file with --version-file (if it exists)
+    let cargo_version = read_current_version(cargo_path, options.version_file, options.version_key.as_deref())?
+        .and_then(|v| parse_version_for(&v, options.revisions).ok());
 
     // Get latest git tag (if any exist)
-    let latest_tag_version = git::get_latest_tag(dir)?.and_then(|t| version::parse_version(&t).ok());
+    let latest_tag_version = git::get_latest_tag(dir, tag_prefix, options.revisions)?
+        .and_then(|t| version::parse_tag(&t, tag_prefix, options.revisions).ok());
+
+    // An explicit --set target must move forward from both Cargo.toml and the latest tag
+    if let Some(ref target) = options.set {
+        for current in [&cargo_version, &latest_tag_version].into_iter().flatten() {
+            if target <= current {
+                bail!(
+                    "--set {} must be greater than the current version {}",
+                    version::format_cargo_version(target),
+                    version::format_cargo_version(current)
+                );
+            }
+        }
+        info!("Setting version to {}", version::format_cargo_version(target));
+        return Ok(VersionAction {
+            target_version: target.clone(),
+            needs_cargo_update: true,
+            is_initial_tag: false,
+        });
+    }
+
+    // Move forward from a base version: promote it with --release, otherwise bump it
+    let advance = |base: &Version| -> Result<Version> {
+        if options.release {
+            version::promote_release(base)
+        } else if options.scheme == VersionScheme::CalVer {
+            Ok(version::bump_calver(base, chrono::Local::now().date_naive()))
+        } else if options.interactive {
+            version::bump_version(base, prompt_bump_type(base, options.by)?, options.by)
+        } else {
+            version::bump_version(base, bump_type, options.by)
+        }
+    };
+
+    // The tags are the source of truth; Cargo.toml is overwritten with the bumped tag (--from-tag)
+    if options.from_tag {
+        let Some(ref tag) = latest_tag_version else {
+            bail!("--from-tag needs a release tag, but no tag with prefix '{}' was found", tag_prefix);
+        };
+        if let Some(ref cargo) = cargo_version
+            && cargo != tag
+        {
+            let message = format!(
+                "Cargo.toml has {} but the latest tag is {}; bumping from the tag",
+                version::format_cargo_version(cargo),
+                version::format_tag(tag, tag_prefix)
+            );
+            warn!("{}", message);
+            status!("Warning: {}", message);
+        }
+        return Ok(VersionAction {
+            target_version: advance(tag)?,
+            needs_cargo_update: true,
+            is_initial_tag: false,
+        });
+    }
+
+    // The current version is already released and no bump flag was given; maybe one was forgotten
+    if options.default_bump
+        && !options.interactive
+        && let (Some(cargo), Some(tag)) = (&cargo_version, &latest_tag_version)
+        && cargo == tag
+    {
+        let message = format!(
+            "current version {} already tagged; pass --patch/--minor/--major to bump (bumping patch)",
+            version::format_tag(tag, tag_prefix)
+        );
+        warn!("{}", message);
+        status!("Warning: {}", message);
+    }
 
     // Determine the base version to bump from
-    match (&cargo_version, &latest_tag_version) {
+    let action = match (&cargo_version, &latest_tag_version) {
         // Case: Both Cargo.toml and git tags exist
         (Some(cargo), Some(tag)) => {
             if *cargo == DEFAULT_UNTOUCHED_VERSION {
                 // Cargo.toml is at default 0.1.0 (untouched) - defer to git tag
                 info!(
                     "Cargo.toml is at default 0.1.0, using git tag {} as base.",
-                    version::format_tag(tag)
+                    version::format_tag(tag, tag_prefix)
                 );
-                let bumped = version::bump_version(tag, bump_type);
-                Ok(VersionAction {
+                let bumped = advance(tag)?;
+                VersionAction {
                     target_version: bumped,
                     needs_cargo_update: true,
                     is_initial_tag: false,
-                })
+                }
             } else if cargo == tag {
                 // Cargo.toml matches latest tag - bump from it
-                info!("Cargo.toml matches latest tag {}. Bumping.", version::format_tag(cargo));
-                let bumped = version::bump_version(cargo, bump_type);
-                Ok(VersionAction {
+                info!("Cargo.toml matches latest tag {}. Bumping.", version::format_tag(cargo, tag_prefix));
+                let bumped = advance(cargo)?;
+                VersionAction {
                     target_version: bumped,
                     needs_cargo_update: true,
                     is_initial_tag: false,
-                })
+                }
+            } else if options.allow_downgrade {
+                // Trust Cargo.toml over the tags (--allow-downgrade)
+                info!(
+                    "Cargo.toml {} doesn't match latest tag {}; bumping from Cargo.toml.",
+                    version::format_cargo_version(cargo),
+                    version::format_tag(tag, tag_prefix)
+                );
+                VersionAction {
+                    target_version: advance(cargo)?,
+                    needs_cargo_update: true,
+                    is_initial_tag: false,
+                }
             } else {
                 // Cargo.toml is NOT 0.1.0 and doesn't match latest tag - ERROR
                 bail!(
                     "Version mismatch: Cargo.toml has {} but latest git tag is {}. \
                     Please sync them manually before running bump.",
                     version::format_cargo_version(cargo),
-                    version::format_tag(tag)
+                    version::format_tag(tag, tag_prefix)
                 );
             }
         }
 
         // Case: Cargo.toml exists, no git tags
         (Some(cargo), None) => {
-            let cargo_tag = version::format_tag(cargo);
+            if options.release {
+                // First tag, but promoted from the pre-release in Cargo.toml
+                return Ok(VersionAction {
+                    target_version: advance(cargo)?,
+                    needs_cargo_update: true,
+                    is_initial_tag: true,
+                });
+            }
+            let cargo_tag = version::format_tag(cargo, tag_prefix);
             // No tags exist - create initial tag for Cargo.toml version
             info!("No git tags found. Creating initial tag {} from Cargo.toml.", cargo_tag);
-            Ok(VersionAction {
+            VersionAction {
                 target_version: cargo.clone(),
                 needs_cargo_update: false,
                 is_initial_tag: true,
-            })
+            }
         }
 
         // Case: No Cargo.toml version, but git tags exist
         (None, Some(tag)) => {
             info!(
                 "No version in Cargo.toml. Using git tag {} as base.",
-                version::format_tag(tag)
+                version::format_tag(tag, tag_prefix)
             );
-            let bumped = version::bump_version(tag, bump_type);
-            Ok(VersionAction {
+            let bumped = advance(tag)?;
+            VersionAction {
                 target_version: bumped,
                 needs_cargo_update: true,
                 is_initial_tag: false,
-            })
+            }
         }
 
         // Case: No version anywhere
         (None, None) => {
-            info!("No version found anywhere. Starting at 0.1.0");
-            Ok(VersionAction {
-                target_version: Version::new(0, 1, 0),
+            if options.release {
+                bail!("No version found to promote with --release");
+            }
+            let initial = match (&options.initial, options.scheme) {
+                (Some(initial), _) => initial.clone(),
+                (None, VersionScheme::SemVer) => Version::new(0, 1, 0),
+                (None, VersionScheme::CalVer) => version::calver_start(chrono::Local::now().date_naive()),
+            };
+            info!("No version found anywhere. Starting at {}", version::format_cargo_version(&initial));
+            VersionAction {
+                target_version: initial,
                 needs_cargo_update: true,
                 is_initial_tag: true,
-            })
+            }
+        }
+    };
+
+    // The new version must sort above every existing tag (unless --allow-downgrade)
+    if let Some(ref latest) = latest_tag_version
+        && action.target_version <= *latest
+        && !options.allow_downgrade
+    {
+        bail!(
+            "New version {} is not greater than the latest tag {}. \
+             Sync Cargo.toml with the tags, or pass --allow-downgrade.",
+            version::format_cargo_version(&action.target_version),
+            version::format_tag(latest, tag_prefix)
+        );
+    }
+
+    Ok(action)
+}
+
+/// The files this run writes, relative to the repository root (as git reports paths)
+/// Only these may be dirty beforehand, and staged changes limited to them get the generated message
+fn owned_files(repo_dir: &Path, cargo_path: &Path, manifest_dir: &Path, cli: &Cli) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    if cargo_path.exists() {
+        paths.push(match cli.version_key {
+            Some(_) => cargo_path.to_path_buf(),
+            None => cargo::version_manifest(cargo_path)?,
+        });
+        if !cli.no_lock
+            && let Some(lockfile) = cargo::find_lockfile(manifest_dir)
+        {
+            paths.push(lockfile);
+        }
+    } else if cli.version_file {
+        paths.push(version_file::version_file_path(manifest_dir));
+    }
+    if cli.changelog {
+        paths.push(changelog::changelog_path(repo_dir));
+    }
+    if cli.update_readme {
+        paths.push(readme::readme_path(manifest_dir));
+    }
+    if cli.sync_npm {
+        paths.push(npm::package_json_path(manifest_dir));
+    }
+    if cli.sync_pyproject {
+        paths.push(pyproject::pyproject_path(manifest_dir));
+    }
+
+    let root = git::repo_root(repo_dir)?;
+    let root = root.canonicalize().unwrap_or(root);
+    Ok(paths.iter().map(|path| repo_relative(&root, path)).collect())
+}
+
+/// A path relative to the repository root, with `/` separators; the file itself need not exist yet
+fn repo_relative(root: &Path, path: &Path) -> String {
+    let full = match (path.parent().and_then(|p| p.canonicalize().ok()), path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => path.to_path_buf(),
+    };
+    let relative = full.strip_prefix(root).unwrap_or(&full);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// How the release commit message is chosen
+#[derive(Debug, Default)]
+struct MessageOptions {
+    /// Use this message verbatim (--message, or the contents of --message-file)
+    message: Option<String>,
+    /// Always generate `Bump version to <tag>`, whatever is staged (--automatic)
+    automatic: bool,
+    /// Never open the editor; fall back to the generated message (--yes)
+    no_prompt: bool,
+    /// Whether stdin is a terminal, so the editor can be opened at all
+    terminal: bool,
+    /// Replaces the generated message, with placeholders filled in (--commit-template)
+    template: Option<String>,
+    /// Files this run writes; when nothing else is staged the generated message is used
+    owned_files: Vec<String>,
+}
+
+impl MessageOptions {
+    fn from_cli(cli: &Cli) -> Self {
+        MessageOptions {
+            message: cli.message.clone(),
+            automatic: cli.automatic,
+            no_prompt: cli.yes,
+            terminal: io::stdin().is_terminal(),
+            template: cli.commit_template.clone(),
+            owned_files: Vec::new(),
         }
     }
 }
 
 /// Determine the commit message based on CLI flags and context
 fn determine_commit_message(
-    cli: &Cli,
-    new_tag: &str,
+    options: &MessageOptions,
+    context: &MessageContext,
     staged_files: &[String],
     is_initial_tag: bool,
 ) -> Result<String> {
     // Priority 1: User provided --message
-    if let Some(ref msg) = cli.message {
+    if let Some(ref msg) = options.message {
         return Ok(msg.clone());
     }
 
+    // Generated messages follow the configured template, if any
+    let new_tag = context.tag;
+    let generated = |default: String| match options.template {
+        Some(ref template) => template::format_message(template, context),
+        None => default,
+    };
+
     // Priority 2: User requested --automatic
-    if cli.automatic {
-        return Ok(format!("Bump version to {}", new_tag));
+    if options.automatic {
+        return Ok(generated(format!("Bump version to {}", new_tag)));
     }
 
     // Priority 3: Auto-generate for version-only changes
     if staged_files.is_empty() {
-        return Ok(format!("Release {}", new_tag));
+        return Ok(generated(format!("Release {}", new_tag)));
     }
 
-    let only_cargo_files = staged_files.iter().all(|f| f == "Cargo.toml" || f == "Cargo.lock");
-    if only_cargo_files {
+    // --yes never prompts, so other staged changes get the default message too
+    if options.no_prompt || staged_files.iter().all(|f| options.owned_files.contains(f)) {
         if is_initial_tag {
-            return Ok(format!("Release {}", new_tag));
+            return Ok(generated(format!("Release {}", new_tag)));
         } else {
-            return Ok(format!("Bump version to {}", new_tag));
+            return Ok(generated(format!("Bump version to {}", new_tag)));
         }
     }
 
+    // Piped or CI runs have nobody to type a message, so say how to supply one instead
+    if !options.terminal {
+        bail!(
+            "Staged changes need a commit message, but stdin is not a terminal to open an editor on. \
+             Pass --message, --message-file, --automatic or --yes."
+        );
+    }
+
     // Priority 4: Open editor for complex changes
     prompt_commit_message_with_editor(staged_files)
 }
 
-/// Process a single directory
-fn process_directory(dir: &Path, cli: &Cli, bump_type: BumpType) -> Result<()> {
-    let dir_name = dir
-        .file_name()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| dir.display().to_string());
+/// The tag annotation: the --tag-template filled in, or else the commit message
+fn tag_message(cli: &Cli, context: &MessageContext, commit_message: &str) -> String {
+    let message = match cli.tag_template {
+        Some(ref template) => template::format_message(template, context),
+        None => commit_message.to_string(),
+    };
+    with_stamp(cli, message)
+}
 
-    // 1. Validate - is this a git repo?
-    if !git::is_git_repo(dir) {
-        bail!("Not a git repository: {}", dir.display());
+/// With --stamp, end a tag annotation with when and by whom it was made
+fn with_stamp(cli: &Cli, message: String) -> String {
+    if !cli.stamp {
+        return message;
     }
+    let date = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    stamped(&message, &date, &ledger::current_user())
+}
+
+/// A message followed by a Date/Released-by footer, in git trailer form
+fn stamped(message: &str, date: &str, user: &str) -> String {
+    format!("{}\n\nDate: {}\nReleased-by: {}", message.trim_end(), date, user)
+}
 
-    // 2. Validate - does Cargo.toml exist?
-    if !cargo::cargo_toml_exists(dir) {
-        bail!("No Cargo.toml found in: {}", dir.display());
+/// Give the user's checks a chance to stop the release before anything is written (--pre-hook)
+fn run_pre_hook(cli: &Cli, repo_dir: &Path, version: &str, tag: &str) -> Result<()> {
+    match cli.pre_hook {
+        Some(ref command) => hook::run("pre-hook", command, repo_dir, version, tag),
+        None => Ok(()),
     }
+}
 
-    // 3. Validate - check for workspace members with independent versions
-    let independent_members = cargo::check_workspace_independent_versions(dir)?;
-    if !independent_members.is_empty() {
-        let member_list: Vec<String> = independent_members
-            .iter()
-            .map(|m| format!("  - {} ({}): {}", m.name, m.path, m.version))
-            .collect();
-        bail!(
-            "Workspace members have independent versions (not using version.workspace = true):\n{}\n\n\
-             bump only supports workspaces with a unified version in [workspace.package].",
-            member_list.join("\n")
-        );
+/// The release is committed and tagged; a failing post-hook can't undo it, only report it (--post-hook)
+fn run_post_hook(cli: &Cli, repo_dir: &Path, version: &str, tag: &str) -> Result<()> {
+    match cli.post_hook {
+        Some(ref command) => hook::run("post-hook", command, repo_dir, version, tag)
+            .context(format!("{} was released, but the post-hook failed", tag)),
+        None => Ok(()),
     }
+}
 
-    let cargo_path = cargo::cargo_toml_path(dir);
+/// Say what --push or --push-tags would send (dry run)
+fn preview_push(cli: &Cli) {
+    if cli.push {
+        let what = if cli.no_tag { "" } else { " and tags" };
+        dry_run!("Would push the current branch{} to {}", what, cli.remote);
+    } else if cli.push_tags {
+        dry_run!("Would push tags to {}", cli.remote);
+    }
+}
 
-    // 3. Determine version action
-    let action = determine_version_action(dir, &cargo_path, bump_type)?;
-    let new_tag = version::format_tag(&action.target_version);
-    let new_cargo_version = version::format_cargo_version(&action.target_version);
+/// Push the release commit and its tags (--push), only the tags (--push-tags), or remind the user to
+fn push_release(cli: &Cli, repo_dir: &Path, tags: &[&str]) -> Result<()> {
+    let pushed_tags: &[&str] = if cli.no_tag { &[] } else { tags };
+    if cli.push {
+        git::push(repo_dir, &cli.remote, pushed_tags, cli.no_verify)?;
+        success!("Pushed {} to {}", tags.join(", "), cli.remote);
+    } else if cli.push_tags {
+        git::push_tags(repo_dir, &cli.remote, pushed_tags, false, cli.no_verify)?;
+        success!("Pushed {} to {}; push the commit separately", tags.join(", "), cli.remote);
+    } else if cli.push_reminder() {
+        status!("Run: git push{}", if cli.no_tag { "" } else { " && git push --tags" });
+    }
+    Ok(())
+}
 
-    // 4. Display what we're doing
-    if action.is_initial_tag {
-        println!("tag: {}", new_tag);
-    } else {
-        // For bumps, show the transition
-        let current_version = cargo::read_version(&cargo_path)?
-            .and_then(|v| version::parse_version(&v).ok())
-            .map(|v| version::format_cargo_version(&v))
-            .unwrap_or_else(|| "unknown".to_string());
-        println!("bump: {} → {}", current_version, new_cargo_version);
+/// Refuse to sweep unrelated work-in-progress into the release commit (unless --allow-dirty)
+/// Changes to the files this run writes don't count; with --dirty-files, only changes matching those globs do
+fn ensure_clean_tree(cli: &Cli, repo_dir: &Path, owned: &[String]) -> Result<()> {
+    if cli.allow_dirty || git::is_working_tree_clean(repo_dir)? {
+        return Ok(());
     }
 
-    // 5. Verify new tag doesn't exist
-    if git::tag_exists(dir, &new_tag)? {
-        bail!("Tag {} already exists", new_tag);
+    let unrelated: Vec<String> = git::get_uncommitted_files_matching(repo_dir, &cli.dirty_files)?
+        .into_iter()
+        .filter(|f| !owned.contains(f))
+        .collect();
+    if !unrelated.is_empty() {
+        bail!(
+            "Working tree has uncommitted changes:\n{}\n\nCommit or stash them first, or pass --allow-dirty \
+             to include them in the release commit.",
+            unrelated.iter().map(|f| format!("  - {}", f)).collect::<Vec<_>>().join("\n")
+        );
     }
+    Ok(())
+}
 
-    // 6. Check for uncommitted changes to determine workflow
-    let has_changes = git::has_uncommitted_changes(dir)?;
+/// Stage the release: with --dirty-files only the files this run writes, so changes outside the globs
+/// stay out of the release commit; otherwise whatever --stage says
+fn stage_release(cli: &Cli, repo_dir: &Path, owned: &[String]) -> Result<()> {
+    if cli.dirty_files.is_empty() {
+        return git::stage_all(repo_dir, cli.stage);
+    }
+    let root = git::repo_root(repo_dir)?;
+    let written: Vec<&str> = owned
+        .iter()
+        .filter(|file| root.join(file).exists())
+        .map(String::as_str)
+        .collect();
+    git::stage_files(repo_dir, &written)
+}
 
-    // 7. Handle dry-run
-    if cli.dry_run {
-        if action.needs_cargo_update {
-            println!("[dry-run] Would update: Cargo.toml");
-        }
-        if !has_changes && !git::head_has_tag(dir)? {
-            let is_pushed = git::is_head_pushed(dir)?;
-            if is_pushed {
-                println!("[dry-run] Would create new commit and tag: {}", new_tag);
-            } else {
-                println!("[dry-run] Would amend previous commit and tag: {}", new_tag);
-            }
-        } else {
-            println!("[dry-run] Would commit and tag: {}", new_tag);
+/// Fetch and refuse (strict) or warn (warn) when HEAD is behind its upstream (--check-remote)
+fn check_remote(repo_dir: &Path, mode: RemoteCheck) -> Result<()> {
+    let problem = match git::fetch(repo_dir).and_then(|_| git::ahead_behind(repo_dir)) {
+        Ok((_, 0)) => return Ok(()),
+        Ok((_, behind)) => format!("Branch is {} commit(s) behind its upstream; pull first", behind),
+        Err(e) => format!("Could not compare with the upstream: {:#}", e),
+    };
+
+    match mode {
+        RemoteCheck::Strict => bail!("{} (or pass --check-remote=warn)", problem),
+        RemoteCheck::Warn => {
+            warn!("{}", problem);
+            status!("Warning: {}", problem);
+            Ok(())
         }
-        return Ok(());
     }
+}
 
-    // Workflow branches based on whether there are uncommitted changes
-    if has_changes {
-        // ===== STANDARD WORKFLOW: Uncommitted changes exist =====
+/// Pick the bump type from the commits since the last tag (--auto)
+fn auto_bump_type(repo_dir: &Path, tag_prefix: &str, revisions: bool) -> Result<BumpType> {
+    let last_tag = git::get_latest_tag(repo_dir, tag_prefix, revisions)?;
+    let messages = git::commit_messages_since(repo_dir, last_tag.as_deref())?;
+    let bump_type = version::infer_bump_type(&messages);
+    info!("Inferred {:?} from {} commits since {:?}", bump_type, messages.len(), last_tag);
+    Ok(bump_type)
+}
 
-        // 8. Update Cargo.toml if needed
-        if action.needs_cargo_update {
-            cargo::write_version(&cargo_path, &new_cargo_version)?;
-            info!("Updated Cargo.toml to version {}", new_cargo_version);
-            cargo::sync_lockfile(dir)?;
-        }
+/// Append the release to the --ledger file, if one was given
+fn record_in_ledger(
+    cli: &Cli,
+    dir: &Path,
+    repo_dir: &Path,
+    previous_version: Option<String>,
+    new_version: &str,
+    new_tag: &str,
+) -> Result<()> {
+    if let Some(ref ledger_path) = cli.ledger {
+        let commit = git::resolve_commit(repo_dir, new_tag)?;
+        let entry = ledger::LedgerEntry::new(dir, previous_version, new_version, new_tag, &commit);
+        ledger::append(ledger_path, &entry)?;
+        info!("Recorded {} in ledger {}", new_tag, ledger_path.display());
+    }
+    Ok(())
+}
 
-        // 9. Stage all changes
-        git::stage_all(dir)?;
+/// Tag HEAD at the current Cargo.toml version without editing files or committing (--tag-only)
+/// With --ref, tag that commit at the version its own Cargo.toml had instead
+fn tag_head(
+    repo_dir: &Path,
+    manifest_dir: &Path,
+    cargo_path: &Path,
+    cli: &Cli,
+    tag_prefix: &str,
+    tag_options: &git::TagOptions,
+) -> Result<Outcome> {
+    let rev = cli.git_ref.as_deref().unwrap_or("HEAD");
+    let commit = git::resolve_commit(repo_dir, rev)?;
+    let version_path = cargo::version_manifest(cargo_path)?;
+    let version_dir = version_path.parent().unwrap_or(manifest_dir);
+    let committed_version = || -> Result<Option<Version>> {
+        let committed = git::show_file(version_dir, &commit, "./Cargo.toml")?;
+        Ok(cargo::read_version_from_str(&committed)?.and_then(|v| version::parse_version(&v).ok()))
+    };
 
-        // 10. Determine commit message
-        let staged_files = git::get_staged_files(dir)?;
-        let commit_message = determine_commit_message(cli, &new_tag, &staged_files, action.is_initial_tag)?;
+    let target_version = match cli.git_ref {
+        Some(_) => committed_version()?.context(format!("No version found in Cargo.toml at {}", rev))?,
+        None => {
+            let current = cargo::read_version(cargo_path)?.context("No version found in Cargo.toml")?;
+            version::parse_version(&current)?
+        }
+    };
+    let new_tag = version::format_tag(&target_version, tag_prefix);
 
-        // 11. Commit
-        if !staged_files.is_empty() {
-            git::commit(dir, &commit_message)?;
-            info!("Committed with message: {}", commit_message);
+    let exists = git::tag_exists(repo_dir, &new_tag)?;
+    if exists && !cli.force {
+        bail!("Tag {} already exists (use --force to move it)", new_tag);
+    }
+
+    // Never point a release tag at a commit whose manifest says otherwise (e.g. an uncommitted version edit)
+    let verb = if exists { "move" } else { "create" };
+    if !cli.force_unsafe {
+        let committed_version = committed_version()?
+            .map(|v| version::format_cargo_version(&v))
+            .unwrap_or_else(|| "none".to_string());
+        if committed_version != version::format_cargo_version(&target_version) {
+            bail!(
+                "{}'s committed Cargo.toml has version {} but the tag is {}. \
+                 Refusing to {} the tag ({}).",
+                rev,
+                committed_version,
+                new_tag,
+                verb,
+                if exists { "use --force-unsafe to override" } else { "commit the version change first" }
+            );
         }
+    }
 
-        // 12. Create annotated tag
-        git::create_tag(dir, &new_tag, &commit_message)?;
-        info!("Created tag: {}", new_tag);
+    if cli.dry_run {
+        // Moving a tag onto the commit it already marks changes nothing
+        let pending = !exists || git::resolve_commit(repo_dir, &new_tag)? != commit;
+        dry_run!("Would {} tag {} at {}", verb, new_tag, rev);
+        if cli.push_tags {
+            dry_run!("Would push tags to {}", cli.remote);
+        }
+        output!("{}", new_tag);
+        return Ok(Outcome {
+            version: Some(version::format_cargo_version(&target_version)),
+            pending,
+            ..Outcome::default()
+        });
+    }
 
-        println!("Committed and tagged {}", new_tag);
-    } else {
-        // ===== CLEAN TREE WORKFLOW: No uncommitted changes =====
+    let tag_message = with_stamp(cli, cli.message.clone().unwrap_or_else(|| format!("Release {}", new_tag)));
+    let tag_options = git::TagOptions {
+        target: Some(commit),
+        ..tag_options.clone()
+    };
+    git::create_tag(repo_dir, &new_tag, &tag_message, &tag_options)?;
+    info!("Tagged {} ({}): {}", rev, verb, new_tag);
 
-        // Check if HEAD already has a tag
-        if git::head_has_tag(dir)? {
-            bail!("HEAD already has a tag. Make changes first, then run bump.");
+    record_in_ledger(cli, repo_dir, repo_dir, None, &version::format_cargo_version(&target_version), &new_tag)?;
+
+    success!("Tagged {}", new_tag);
+    if cli.push_tags {
+        git::push_tags(repo_dir, &cli.remote, &[new_tag.as_str()], exists, cli.no_verify)?;
+        success!("Pushed {} to {}", new_tag, cli.remote);
+    } else if cli.push_reminder() {
+        status!("Run: git push --tags{}", if exists { " --force" } else { "" });
+    }
+    output!("{}", new_tag);
+
+    Ok(Outcome {
+        version: Some(version::format_cargo_version(&target_version)),
+        tagged: true,
+        ..Outcome::default()
+    })
+}
+
+/// State captured before committing, restored if tagging fails (--rollback-on-failure)
+struct RollbackPoint {
+    /// HEAD before the release commit or amend (None in a repo without commits)
+    head: Option<String>,
+    /// Files bump edits and their original contents (None if the file didn't exist)
+    files: Vec<(PathBuf, Option<String>)>,
+}
+
+/// Undo a release whose tagging failed: reset to the old HEAD and restore the edited files
+fn rollback(repo_dir: &Path, point: &RollbackPoint) -> Result<()> {
+    if let Some(ref head) = point.head {
+        info!("Rollback: git reset --soft {}", head);
+        git::reset_soft(repo_dir, head)?;
+    }
+
+    for (path, content) in &point.files {
+        info!("Rollback: restoring {}", path.display());
+        match content {
+            Some(content) => fs::write(path, content).context(format!("Failed to restore {}", path.display()))?,
+            None if path.exists() => fs::remove_file(path).context(format!("Failed to remove {}", path.display()))?,
+            None => {}
         }
+    }
 
-        // Check if HEAD has been pushed
-        let is_pushed = git::is_head_pushed(dir)?;
+    // Only the restored files; anything else in the tree stays as the user left it
+    info!("Rollback: restaging restored files");
+    let root = git::repo_root(repo_dir)?;
+    let root = root.canonicalize().unwrap_or(root);
+    let files: Vec<String> = point.files.iter().map(|(path, _)| repo_relative(&root, path)).collect();
+    git::stage_files(repo_dir, &files.iter().map(String::as_str).collect::<Vec<_>>())
+}
 
-        // Update Cargo.toml
-        if action.needs_cargo_update {
-            cargo::write_version(&cargo_path, &new_cargo_version)?;
-            info!("Updated Cargo.toml to version {}", new_cargo_version);
-            cargo::sync_lockfile(dir)?;
+/// A workspace member being released with --package
+struct PackageRelease {
+    name: String,
+    manifest: PathBuf,
+    previous_version: Option<String>,
+    new_version: String,
+    new_tag: String,
+}
+
+/// Bump the named workspace members independently, tagging each as <name>-v<version> (--package)
+/// All members are committed together; members not listed are left untouched
+fn bump_packages(
+    workspace_dir: &Path,
+    repo_dir: &Path,
+    packages: &[String],
+    cli: &Cli,
+    bump_type: BumpType,
+    tag_options: &git::TagOptions,
+    commit_options: &git::CommitOptions,
+) -> Result<Outcome> {
+    // Work out every member's next version before changing anything
+    let mut releases = Vec::new();
+    for name in packages {
+        let manifest = cargo::find_member_manifest(workspace_dir, name)?;
+        let tag_prefix = format!("{}-{}", name, cli.tag_prefix());
+        let bump_type = if cli.auto { auto_bump_type(repo_dir, &tag_prefix, false)? } else { bump_type };
+        let options = VersionOptions {
+            tag_prefix: tag_prefix.clone(),
+            set: cli.set.as_deref().map(version::parse_version).transpose()?,
+            initial: initial_version(cli, false)?,
+            release: cli.release,
+            allow_downgrade: cli.allow_downgrade,
+            from_tag: cli.from_tag,
+            by: cli.by,
+            default_bump: !cli.has_bump_selection(),
+            scheme: cli.version_scheme(),
+            ..VersionOptions::new(bump_type)
+        };
+        let action = determine_version_action(repo_dir, &manifest, &options)?.with_build(build_metadata(cli)?);
+        let new_tag = version::format_tag(&action.target_version, &tag_prefix);
+        if !cli.no_tag && git::tag_exists(repo_dir, &new_tag)? {
+            bail!("Tag {} already exists", new_tag);
         }
 
-        // Stage the Cargo.toml changes
-        git::stage_all(dir)?;
-        let staged_files = git::get_staged_files(dir)?;
+        let previous_version = if action.is_initial_tag {
+            None
+        } else {
+            cargo::read_version(&manifest)?
+        };
+        releases.push(PackageRelease {
+            name: name.clone(),
+            manifest,
+            previous_version,
+            new_version: version::format_cargo_version(&action.target_version),
+            new_tag,
+        });
+    }
 
-        if is_pushed {
-            // HEAD is pushed - create a new commit
-            let commit_message = determine_commit_message(cli, &new_tag, &staged_files, action.is_initial_tag)?;
+    // Members are listed by name, since each has its own version
+    let outcome = Outcome {
+        version: Some(
+            releases
+                .iter()
+                .map(|r| format!("{} {}", r.name, r.new_version))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        ..Outcome::default()
+    };
 
-            if !staged_files.is_empty() {
-                git::commit(dir, &commit_message)?;
-                info!("Committed with message: {}", commit_message);
+    if cli.print_version {
+        for release in &releases {
+            output!("{}", if cli.tag { &release.new_tag } else { &release.new_version });
+        }
+        return Ok(outcome);
+    }
+
+    // One aligned row per member: old → new
+    let width = releases.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    for release in &releases {
+        match release.previous_version {
+            Some(ref previous) => {
+                status!("bump: {:<width$} {} → {}", release.name, previous, release.new_version, width = width)
             }
+            None => status!("tag: {}", release.new_tag),
+        }
+    }
+    let tags: Vec<&str> = releases.iter().map(|r| r.new_tag.as_str()).collect();
 
-            git::create_tag(dir, &new_tag, &commit_message)?;
-            info!("Created tag: {}", new_tag);
+    if cli.dry_run {
+        for release in &releases {
+            dry_run!("Would update: {}", release.manifest.display());
+            let manifest_dir = release.manifest.parent().unwrap_or(workspace_dir);
+            preview_manifest_change(&release.manifest, manifest_dir, cli, &release.new_version)?;
+        }
+        dry_run!("Would commit{}: {}", if cli.no_tag { " (no tag)" } else { " and tag" }, tags.join(", "));
+        preview_push(cli);
+        for tag in &tags {
+            output!("{}", tag);
+        }
+        return Ok(Outcome { pending: true, ..outcome });
+    }
 
-            println!("Committed and tagged {}", new_tag);
-        } else {
-            // HEAD is not pushed - amend the previous commit
-            if !staged_files.is_empty() {
-                git::amend_commit_no_edit(dir)?;
-                info!("Amended previous commit with Cargo.toml changes");
-            }
+    let mut owned = Vec::new();
+    for release in &releases {
+        let manifest_dir = release.manifest.parent().unwrap_or(workspace_dir);
+        owned.extend(owned_files(repo_dir, &release.manifest, manifest_dir, cli)?);
+    }
+    ensure_clean_tree(cli, repo_dir, &owned)?;
 
-            // Use automatic message for the tag since we're amending
-            let tag_message = format!("Bump version to {}", new_tag);
-            git::create_tag(dir, &new_tag, &tag_message)?;
-            info!("Created tag: {}", new_tag);
+    if cli.confirm {
+        let files: Vec<String> = releases
+            .iter()
+            .map(|r| r.manifest.strip_prefix(repo_dir).unwrap_or(&r.manifest).display().to_string())
+            .collect();
+        confirm_action(&plan_summary(&files, cli, &tags.join(", ")))?;
+    }
+
+    for release in &releases {
+        run_pre_hook(cli, repo_dir, &release.new_version, &release.new_tag)?;
+    }
+
+    let root = git::repo_root(repo_dir)?;
+    let root = root.canonicalize().unwrap_or(root);
+    for release in &releases {
+        cargo::write_version(&release.manifest, &release.new_version)?;
+        info!("Updated {} to version {}", release.manifest.display(), release.new_version);
 
-            println!("Amended commit and tagged {}", new_tag);
+        // Siblings that path-depend on this crate must require the new version
+        for dependent in cargo::update_internal_dependents(workspace_dir, &release.name, &release.new_version)? {
+            status!("Updated {} requirement in {}", release.name, dependent.display());
+            owned.push(repo_relative(&root, &dependent));
+        }
+    }
+    if !cli.no_lock {
+        for release in &releases {
+            cargo::sync_lockfile(release.manifest.parent().unwrap_or(workspace_dir))?;
         }
     }
+    stage_release(cli, repo_dir, &owned)?;
 
-    println!("Run: git push && git push --tags");
+    if cli.no_commit {
+        status!("Staged version changes for {} (not committed or tagged)", tags.join(", "));
+        return Ok(outcome);
+    }
 
-    if !dir_name.is_empty() && dir != env::current_dir().unwrap_or_default() {
-        println!("[{}] Done", dir_name);
+    // The members share one commit, so its message lists every version and tag
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let versions = releases.iter().map(|r| r.new_version.as_str()).collect::<Vec<_>>().join(", ");
+    let previous = releases
+        .iter()
+        .map(|r| r.previous_version.as_deref().unwrap_or("none"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let all_tags = tags.join(", ");
+    let message_context = MessageContext {
+        version: &versions,
+        tag: &all_tags,
+        previous: &previous,
+        date: &date,
+    };
+    let message_options = MessageOptions {
+        owned_files: owned,
+        ..MessageOptions::from_cli(cli)
+    };
+    let is_initial_tag = releases.iter().all(|r| r.previous_version.is_none());
+    let staged_files = git::get_staged_files(repo_dir)?;
+    let commit_message = determine_commit_message(&message_options, &message_context, &staged_files, is_initial_tag)?;
+    if !staged_files.is_empty() {
+        git::commit(repo_dir, &commit_message, commit_options)?;
+        info!("Committed with message: {}", commit_message);
     }
 
-    Ok(())
-}
+    if !cli.no_tag {
+        for release in &releases {
+            let context = MessageContext {
+                version: &release.new_version,
+                tag: &release.new_tag,
+                previous: release.previous_version.as_deref().unwrap_or("none"),
+                date: &date,
+            };
+            git::create_tag(repo_dir, &release.new_tag, &tag_message(cli, &context, &commit_message), tag_options)?;
+            info!("Created tag: {}", release.new_tag);
+            record_in_ledger(
+                cli,
+                release.manifest.parent().unwrap_or(workspace_dir),
+                repo_dir,
+                release.previous_version.clone(),
+                &release.new_version,
+                &release.new_tag,
+            )?;
+        }
+    }
+
+    for release in &releases {
+        run_post_hook(cli, repo_dir, &release.new_version, &release.new_tag)?;
+    }
+    push_release(cli, repo_dir, &tags)?;
 
-fn main() -> Result<()> {
-    setup_logging().context("Failed to setup logging")?;
+    for tag in &tags {
+        output!("{}", tag);
+    }
 
-    let cli = Cli::parse();
-    let bump_type = BumpType::from_cli(cli.major, cli.minor);
+    Ok(Outcome {
+        committed: true,
+        tagged: !cli.no_tag,
+        ..outcome
+    })
+}
 
-    info!("Starting bump with type: {:?}", bump_type);
+/// A directory's Cargo.toml, the directory holding it, and the repository git runs in
+/// The manifest may live below the directory (--manifest-path); git operations
+/// then run at the enclosing repository root rather than the manifest directory
+fn directory_paths(dir: &Path, cli: &Cli) -> Result<(PathBuf, PathBuf, PathBuf)> {
+    let cargo_path = match cli.manifest_path {
+        Some(ref path) => dir.join(path),
+        None => cargo::cargo_toml_path(dir),
+    };
+    let manifest_dir = cargo_path.parent().unwrap_or(dir).to_path_buf();
 
-    // Determine directories to process
-    let directories: Vec<PathBuf> = if cli.directories.is_empty() {
-        vec![env::current_dir().context("Failed to get current directory")?]
+    // Like cargo, --manifest-path names the Cargo.toml itself, and it has to exist
+    if cli.manifest_path.is_some() {
+        if cargo_path.file_name().is_none_or(|name| name != "Cargo.toml") {
+            bail!("--manifest-path must be a path to a Cargo.toml file: {}", cargo_path.display());
+        }
+        if !cargo_path.is_file() {
+            bail!("Manifest not found: {}", cargo_path.display());
+        }
+    }
+
+    if !git::is_git_repo(&manifest_dir) {
+        bail!("Not a git repository: {}", manifest_dir.display());
+    }
+    let repo_dir = if cli.manifest_path.is_some() {
+        git::repo_root(&manifest_dir)?
     } else {
-        cli.directories.clone()
+        dir.to_path_buf()
     };
+    Ok((cargo_path, manifest_dir, repo_dir))
+}
 
-    let mut successes = 0;
-    let mut failures = 0;
+/// The tag prefix, namespaced by package name with --auto-namespace-tags (workspace-only manifests have no name)
+fn directory_tag_prefix(cargo_path: &Path, has_cargo_toml: bool, cli: &Cli) -> Result<String> {
+    let package_name = if has_cargo_toml { cargo::read_package_name(cargo_path)? } else { None };
+    Ok(match package_name {
+        Some(name) if cli.auto_namespace_tags => format!("{}-{}", name, cli.tag_prefix()),
+        _ => cli.tag_prefix().to_string(),
+    })
+}
 
-    for dir in &directories {
-        let dir = if dir.is_absolute() { dir.clone() } else { env::current_dir()?.join(dir) };
+/// How the command line steers the target version (--auto reads the commits since the last tag)
+fn version_options(
+    repo_dir: &Path,
+    cargo_path: &Path,
+    tag_prefix: &str,
+    cli: &Cli,
+    bump_type: BumpType,
+) -> Result<VersionOptions> {
+    let revisions = allows_revision(cargo_path, cli);
+    let bump_type = if cli.auto { auto_bump_type(repo_dir, tag_prefix, revisions)? } else { bump_type };
+    Ok(VersionOptions {
+        tag_prefix: tag_prefix.to_string(),
+        set: cli.set.as_deref().map(|v| parse_version_for(v, revisions)).transpose()?,
+        initial: initial_version(cli, revisions)?,
+        release: cli.release,
+        version_file: cli.version_file,
+        version_key: cli.version_key.clone(),
+        revisions,
+        allow_downgrade: cli.allow_downgrade,
+        from_tag: cli.from_tag,
+        by: cli.by,
+        interactive: cli.interactive,
+        default_bump: !cli.has_bump_selection(),
+        scheme: cli.version_scheme(),
+        ..VersionOptions::new(bump_type)
+    })
+}
 
-        if directories.len() > 1 {
-            let dir_name = dir
-                .file_name()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_else(|| dir.display().to_string());
-            println!("\n[{}]", dir_name);
-        }
+/// The version being replaced, formatted for Cargo.toml (None for an initial tag)
+fn previous_version(action: &VersionAction, cargo_path: &Path, cli: &Cli) -> Result<Option<String>> {
+    if action.is_initial_tag {
+        return Ok(None);
+    }
+    let revisions = allows_revision(cargo_path, cli);
+    Ok(Some(
+        read_current_version(cargo_path, cli.version_file, cli.version_key.as_deref())?
+            .and_then(|v| parse_version_for(&v, revisions).ok())
+            .map(|v| version::format_cargo_version(&v))
+            .unwrap_or_else(|| "unknown".to_string()),
+    ))
+}
 
-        match process_directory(&dir, &cli, bump_type) {
-            Ok(()) => successes += 1,
-            Err(e) => {
-                eprintln!("Error: {:#}", e);
-                failures += 1;
-            }
+/// The files a release writes, by name, for the plan shown before it
+fn planned_files(action: &VersionAction, has_cargo_toml: bool, manifest_dir: &Path, cli: &Cli) -> Vec<String> {
+    let mut files = Vec::new();
+    if action.needs_cargo_update {
+        files.push(if has_cargo_toml { "Cargo.toml" } else { "VERSION" }.to_string());
+    }
+    if action.needs_cargo_update && has_cargo_toml && !cli.no_lock && cargo::find_lockfile(manifest_dir).is_some() {
+        files.push("Cargo.lock".to_string());
+    }
+    if cli.changelog {
+        files.push("CHANGELOG.md".to_string());
+    }
+    if cli.update_readme {
+        files.push("README.md".to_string());
+    }
+    if action.needs_cargo_update && cli.sync_npm && npm::package_json_path(manifest_dir).exists() {
+        files.push("package.json".to_string());
+    }
+    if action.needs_cargo_update && cli.sync_pyproject && pyproject::pyproject_path(manifest_dir).exists() {
+        files.push("pyproject.toml".to_string());
+    }
+    files
+}
+
+/// Process a single directory
+fn process_directory(dir: &Path, cli: &Cli, bump_type: BumpType) -> Result<Outcome> {
+    log_directory(dir);
+    let dir_name = dir
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.display().to_string());
+
+    // 1. Validate - is this a git repo?
+    let (cargo_path, manifest_dir, repo_dir) = directory_paths(dir, cli)?;
+
+    // Validate - is a branch checked out? Tagging a detached HEAD is usually a mistake
+    if !cli.print_version && git::is_detached_head(&repo_dir)? {
+        bail!("HEAD is detached in {}. Check out a branch before bumping.", repo_dir.display());
+    }
+
+    // Validate - are we on the release branch?
+    if let Some(ref branch) = cli.branch {
+        let expected = match branch {
+            Some(name) => name.clone(),
+            None => git::default_branch(&repo_dir, &cli.remote)?.with_context(|| {
+                format!(
+                    "Could not detect the default branch of remote '{}' (remote HEAD is not set). \
+                     Run `git remote set-head {} --auto` or pass --branch <NAME>.",
+                    cli.remote, cli.remote
+                )
+            })?,
+        };
+        let current = git::current_branch(&repo_dir)?;
+        if current != expected {
+            bail!("Refusing to bump on branch '{}' (expected '{}')", current, expected);
         }
     }
 
-    if directories.len() > 1 {
-        println!();
-        if failures == 0 {
-            println!("All done! Don't forget to push your changes.");
+    // 2. Validate - does Cargo.toml exist? (--version-file lets a plain VERSION file stand in)
+    let has_cargo_toml = cargo::cargo_toml_exists(&manifest_dir);
+    if !has_cargo_toml && !cli.version_file {
+        bail!("No Cargo.toml found in: {}", manifest_dir.display());
+    }
+    if cli.revision && !allows_revision(&cargo_path, cli) {
+        bail!(
+            "--revision writes a four-segment version, which Cargo rejects in package.version; \
+             use it with --version-key, or --version-file without a Cargo.toml"
+        );
+    }
+
+    if let Some(ref author) = cli.author {
+        git::validate_author(author)?;
+    }
+    // A missing remote should stop the release before it is tagged, not after
+    if cli.push_tags {
+        git::ensure_remote(&repo_dir, &cli.remote)?;
+    }
+    if cli.update_readme {
+        readme::check_templates(&cli.readme_templates())?;
+    }
+    // A pyproject.toml bump can't update must stop the release before Cargo.toml is touched
+    if cli.sync_pyproject {
+        pyproject::read_version(&manifest_dir)?;
+    }
+    let writes_other_files =
+        cli.changelog || cli.update_readme || cli.version_file || cli.sync_npm || cli.sync_pyproject;
+    if cli.stage == git::StageMode::CargoOnly && writes_other_files {
+        bail!(
+            "--stage cargo-only only stages Cargo.toml and Cargo.lock; \
+             it can't commit CHANGELOG.md, README.md, VERSION, package.json or pyproject.toml"
+        );
+    }
+    let commit_options = git::CommitOptions {
+        author: cli.author.clone(),
+        no_verify: cli.no_verify,
+        body: cli.commit_body.clone(),
+    };
+
+    let tag_options = git::TagOptions {
+        force: cli.force,
+        sign: cli.sign,
+        local_user: cli.local_user.clone(),
+        lightweight: cli.lightweight,
+        target: None,
+    };
+
+    // A shallow clone (common in CI) can be missing the latest tag and the commits since it
+    if git::is_shallow(&repo_dir)? {
+        if cli.unshallow {
+            git::unshallow(&repo_dir, &cli.remote)?;
+            info!("Fetched full history from {}", cli.remote);
         } else {
-            println!("Completed: {} succeeded, {} failed", successes, failures);
+            let message = "this is a shallow clone, so the latest tag or the commits since it may be missing \
+                           and the inferred version may be wrong (use --unshallow, or fetch full history in CI)";
+            warn!("{}", message);
+            status!("Warning: {}", message);
         }
     }
 
-    if failures > 0 && successes == 0 {
-        std::process::exit(1);
+    // See tags teammates already pushed before computing and checking the new one (--fetch-tags)
+    // Offline is not fatal: the local tags are still checked
+    if cli.fetch_tags
+        && let Err(e) = git::fetch_tags(&repo_dir, &cli.remote)
+    {
+        warn!("{:#}", e);
+        status!("Warning: could not fetch tags from {}; checking local tags only", cli.remote);
     }
 
-    Ok(())
-}
+    // Tagging a branch that is behind its upstream diverges from what teammates pushed (--check-remote)
+    if let Some(mode) = cli.check_remote {
+        check_remote(&repo_dir, mode)?;
+    }
 
-/// =============================================================================
-/// TEST MODULE FOR BUMP VERSION LOGIC
-/// =============================================================================
-///
-/// THE RULES (EXACTLY AS SPECIFIED):
-///
-/// 1. `0.1.0` is the SPECIAL "UNTOUCHED DEFAULT" version.
-///    - If Cargo.toml = 0.1.0 and git tags exist → DEFER TO GIT TAG
-///    - If Cargo.toml = 0.1.0 and no git tags → Create initial tag v0.1.0
-///
-/// 2. ANY OTHER VERSION in Cargo.toml means "ACTIVELY MANAGED"
-///    - If Cargo.toml != 0.1.0 and latest tag MATCHES → Bump from that version
-///    - If Cargo.toml != 0.1.0 and latest tag DOES NOT MATCH → **ERROR**
-///    - If Cargo.toml != 0.1.0 and no tags exist → Create initial tag
-///
-/// 3. If Cargo.toml has NO version field:
-///    - If git tags exist → Bump from latest tag
-///    - If no git tags → Start at 0.1.0
-///
-/// =============================================================================
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::process::Command;
-    use tempfile::TempDir;
+    // Independently versioned members are bumped one by one (--package)
+    if !cli.package.is_empty() {
+        return bump_packages(&manifest_dir, &repo_dir, &cli.package, cli, bump_type, &tag_options, &commit_options);
+    }
 
-    // =========================================================================
-    // TEST HELPERS
-    // =========================================================================
+    // Every member with its own version is bumped and tagged on its own (--all-members)
+    // Members inheriting version.workspace are left to a plain workspace bump, and
+    // unpublished ones are skipped unless --include-unpublished
+    if cli.all_members {
+        let (members, unpublished): (Vec<_>, Vec<_>) = cargo::check_workspace_independent_versions(&manifest_dir)?
+            .into_iter()
+            .partition(|member| member.publish || cli.include_unpublished);
+        if !unpublished.is_empty() {
+            let names: Vec<&str> = unpublished.iter().map(|member| member.name.as_str()).collect();
+            status!("Skipping unpublished members: {} (pass --include-unpublished to bump them)", names.join(", "));
+        }
+        let members: Vec<String> = members.into_iter().map(|member| member.name).collect();
+        if members.is_empty() {
+            bail!("No workspace members have independent versions; bump the workspace without --all-members");
+        }
+        return bump_packages(&manifest_dir, &repo_dir, &members, cli, bump_type, &tag_options, &commit_options);
+    }
 
-    fn setup_git_repo(dir: &Path) {
-        Command::new("git")
-            .args(["init"])
-            .current_dir(dir)
-            .output()
-            .expect("Failed to init git repo");
+    // 3. Validate - check for workspace members with independent versions
+    let independent_members = if has_cargo_toml {
+        cargo::check_workspace_independent_versions(&manifest_dir)?
+    } else {
+        Vec::new()
+    };
+    // Members that already drifted apart are an error rather than a question (--require-consistent)
+    if cli.require_consistent && !cargo::versions_consistent(&independent_members) {
+        let mismatches: Vec<String> = independent_members
+            .iter()
+            .map(|member| format!("  - {} ({}): {}", member.name, member.path, member.version))
+            .collect();
+        bail!(
+            "Workspace members have inconsistent versions:\n{}\n\
+             Inherit version.workspace = true or give them one shared version before releasing.",
+            mismatches.join("\n")
+        );
+    }
 
-        Command::new("git")
-            .args(["config", "user.email", "test@test.com"])
-            .current_dir(dir)
-            .output()
-            .expect("Failed to set git email");
+    // Only the workspace version moves, so these members would drift; ask first (--yes skips the question)
+    if !independent_members.is_empty() {
+        status!("Warning: workspace members have independent versions (not using version.workspace = true):");
+        for member in &independent_members {
+            status!("  - {} ({}): {}", member.name, member.path, member.version);
+        }
+        status!("Only [workspace.package] will be bumped; use --package to bump members individually.");
+        if !cli.dry_run && !cli.print_version && !cli.yes && !confirm("Continue?")? {
+            bail!("Aborted: workspace members have independent versions");
+        }
+    }
 
-        Command::new("git")
-            .args(["config", "user.name", "Test"])
-            .current_dir(dir)
-            .output()
-            .expect("Failed to set git name");
+    let tag_prefix = directory_tag_prefix(&cargo_path, has_cargo_toml, cli)?;
+
+    if cli.tag_only || cli.git_ref.is_some() {
+        return tag_head(&repo_dir, &manifest_dir, &cargo_path, cli, &tag_prefix, &tag_options);
     }
 
-    fn create_initial_commit(dir: &Path) {
-        fs::write(dir.join("README.md"), "# Test").unwrap();
-        Command::new("git")
-            .args(["add", "-A"])
-            .current_dir(dir)
-            .output()
-            .expect("Failed to add files");
+    // 3. Determine version action
+    let options = version_options(&repo_dir, &cargo_path, &tag_prefix, cli, bump_type)?;
+    let revisions = options.revisions;
+    let action = determine_version_action(&repo_dir, &cargo_path, &options)?.with_build(build_metadata(cli)?);
+    let new_tag = version::format_tag(&action.target_version, &tag_prefix);
+    let new_cargo_version = version::format_cargo_version(&action.target_version);
+    log_version(&new_cargo_version);
+
+    // Just report the computed version, machine-clean on stdout (--print-version)
+    if cli.print_version {
+        output!("{}", if cli.tag { &new_tag } else { &new_cargo_version });
+        return Ok(Outcome {
+            version: Some(new_cargo_version),
+            ..Outcome::default()
+        });
+    }
 
-        Command::new("git")
-            .args(["commit", "-m", "Initial commit"])
-            .current_dir(dir)
-            .output()
-            .expect("Failed to commit");
+    // The version being replaced (None for an initial tag)
+    let previous_version = previous_version(&action, &cargo_path, cli)?;
+    let outcome = Outcome {
+        previous: previous_version.clone(),
+        version: Some(new_cargo_version.clone()),
+        ..Outcome::default()
+    };
+
+    // 4. Display what we're doing
+    match previous_version {
+        None => status!("tag: {}", new_tag),
+        Some(ref current_version) if options.set.is_some() => {
+            status!("set: {} → {}", current_version, new_cargo_version)
+        }
+        // For bumps, show the transition
+        Some(ref current_version) => status!("bump: {} → {}", current_version, new_cargo_version),
+    }
+
+    // Leaving 0.x is a promise about compatibility, so say so (and ask on a terminal below)
+    let crosses_stable = previous_version
+        .as_deref()
+        .and_then(|v| parse_version_for(v, revisions).ok())
+        .is_some_and(|previous| version::crosses_stable_boundary(&previous, &action.target_version));
+    if crosses_stable {
+        status!(
+            "Note: {} is the first stable release. From 1.0.0 on, breaking changes need a major bump; \
+             under 0.x a minor bump was enough.",
+            new_cargo_version
+        );
+    }
+
+    // Members sharing the workspace version get their own tag too (--tag-per-member)
+    let member_tags = if cli.tag_per_member {
+        let members = cargo::shared_version_members(&manifest_dir, cli.include_unpublished)?;
+        if members.is_empty() {
+            bail!("--tag-per-member found no published members with version.workspace = true");
+        }
+        let version = &action.target_version;
+        members
+            .iter()
+            .map(|name| version::format_tag(version, &format!("{}-{}", name, cli.tag_prefix())))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // 5. Verify new tag doesn't exist (nothing to collide with under --no-tag)
+    if !cli.no_tag {
+        for tag in std::iter::once(&new_tag).chain(&member_tags) {
+            if git::tag_exists(&repo_dir, tag)? {
+                bail!("Tag {} already exists", tag);
+            }
+        }
+    }
+
+    let owned = owned_files(&repo_dir, &cargo_path, &manifest_dir, cli)?;
+    ensure_clean_tree(cli, &repo_dir, &owned)?;
+
+    // Rewriting HEAD is only safe while it is neither released nor published (--amend)
+    if cli.amend {
+        if git::head_has_tag(&repo_dir)? {
+            bail!("Refusing to --amend: HEAD is already tagged");
+        }
+        if git::is_head_pushed(&repo_dir)? {
+            bail!("Refusing to --amend: HEAD has already been pushed");
+        }
+    }
+
+    // 6. Check for uncommitted changes to determine workflow
+    let has_changes = git::has_uncommitted_changes(&repo_dir)?;
+
+    // A clean tree on a tagged HEAD has nothing to release; refuse before CHANGELOG.md or hooks run
+    if !has_changes && !cli.dry_run && !cli.no_commit && git::head_has_tag(&repo_dir)? {
+        bail!("HEAD already has a tag. Make changes first, then run bump.");
+    }
+
+    // How the commit is finished off, for status messages
+    let and_tag = if cli.no_tag { " (no tag)" } else { " and tag" };
+
+    // 7. Handle dry-run
+    if cli.dry_run {
+        if action.needs_cargo_update {
+            let file = if has_cargo_toml { "Cargo.toml" } else { "VERSION" };
+            dry_run!("Would update: {}", file);
+            if has_cargo_toml {
+                preview_manifest_change(&cargo_path, &manifest_dir, cli, &new_cargo_version)?;
+            }
+        }
+        if cli.changelog {
+            dry_run!("Would update: CHANGELOG.md");
+        }
+        if cli.update_readme {
+            dry_run!("Would update version references in: README.md");
+        }
+        if action.needs_cargo_update && cli.sync_npm && npm::package_json_path(&manifest_dir).exists() {
+            dry_run!("Would update: package.json");
+        }
+        if action.needs_cargo_update && cli.sync_pyproject && pyproject::pyproject_path(&manifest_dir).exists() {
+            dry_run!("Would update: pyproject.toml");
+        }
+        if cli.no_commit {
+            dry_run!("Would stage changes for {} without committing or tagging", new_tag);
+        } else if cli.amend {
+            dry_run!("Would amend HEAD{}: {}", and_tag, new_tag);
+        } else if !has_changes && !git::head_has_tag(&repo_dir)? {
+            let is_pushed = git::is_head_pushed(&repo_dir)?;
+            if !action.needs_cargo_update && !cli.changelog && !cli.no_tag {
+                dry_run!("No changes to commit; would tag existing HEAD {}: {}", git::head_sha(&repo_dir)?, new_tag);
+            } else if is_pushed {
+                dry_run!("Would create new commit{}: {}", and_tag, new_tag);
+            } else {
+                dry_run!("Would amend previous commit{}: {}", and_tag, new_tag);
+            }
+        } else {
+            dry_run!("Would commit{}: {}", and_tag, new_tag);
+        }
+        if !member_tags.is_empty() {
+            dry_run!("Would tag members: {}", member_tags.join(", "));
+        }
+        preview_push(cli);
+        output!("{}", new_tag);
+        return Ok(Outcome { pending: true, ..outcome });
+    }
+
+    // 1.0.0 is a commitment, so it needs an explicit yes (skipped with --yes and off a terminal)
+    if crosses_stable && !cli.yes && io::stdin().is_terminal() && !confirm(&format!("Release {}?", new_tag))? {
+        bail!("Aborted: {} was not released", new_tag);
+    }
+
+    // One last look at everything about to happen (skipped with --yes and off a terminal)
+    if cli.confirm {
+        let files = planned_files(&action, has_cargo_toml, &manifest_dir, cli);
+        confirm_action(&plan_summary(&files, cli, &new_tag))?;
+    }
+
+    run_pre_hook(cli, &repo_dir, &new_cargo_version, &new_tag)?;
+
+    // Where to return to if tagging fails after the commit (--rollback-on-failure)
+    let rollback_point = if cli.rollback_on_failure {
+        let version_path = if has_cargo_toml {
+            cargo::version_manifest(&cargo_path)?
+        } else {
+            version_file::version_file_path(&manifest_dir)
+        };
+        let mut snapshot = vec![(version_path.clone(), fs::read_to_string(&version_path).ok())];
+        // Cargo.lock goes back byte for byte rather than being re-resolved
+        if has_cargo_toml
+            && !cli.no_lock
+            && let Some(lockfile) = cargo::find_lockfile(&manifest_dir)
+        {
+            snapshot.push((lockfile.clone(), fs::read_to_string(&lockfile).ok()));
+        }
+        if cli.changelog {
+            let changelog_path = changelog::changelog_path(&repo_dir);
+            snapshot.push((changelog_path.clone(), fs::read_to_string(&changelog_path).ok()));
+        }
+        if cli.update_readme {
+            let readme_path = readme::readme_path(&manifest_dir);
+            snapshot.push((readme_path.clone(), fs::read_to_string(&readme_path).ok()));
+        }
+        if cli.sync_npm {
+            let package_json_path = npm::package_json_path(&manifest_dir);
+            snapshot.push((package_json_path.clone(), fs::read_to_string(&package_json_path).ok()));
+        }
+        if cli.sync_pyproject {
+            let pyproject_path = pyproject::pyproject_path(&manifest_dir);
+            snapshot.push((pyproject_path.clone(), fs::read_to_string(&pyproject_path).ok()));
+        }
+        Some(RollbackPoint {
+            head: git::resolve_commit(&repo_dir, "HEAD").ok(),
+            files: snapshot,
+        })
+    } else {
+        None
+    };
+
+    // Prepend this release's commits to CHANGELOG.md so it lands in the release commit (--changelog)
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if cli.changelog {
+        let last_tag = git::get_latest_tag(&repo_dir, &tag_prefix, revisions)?;
+        let subjects = git::commits_since(&repo_dir, last_tag.as_deref())?;
+        changelog::prepend_section(&repo_dir, &new_tag, &date, &subjects)?;
+        info!("Added {} ({} commits) to CHANGELOG.md", new_tag, subjects.len());
+    }
+
+    // Point README badges and install snippets at the new version (--update-readme)
+    if cli.update_readme
+        && let Some(ref previous) = previous_version
+    {
+        let name = if has_cargo_toml { cargo::read_package_name(&cargo_path)? } else { None };
+        let replaced =
+            readme::update(&manifest_dir, &cli.readme_templates(), name.as_deref(), previous, &new_cargo_version)?;
+        if replaced == 0 {
+            status!("Warning: no references to {} found in README.md", previous);
+        } else {
+            info!("Updated {} version reference(s) in README.md", replaced);
+        }
+    }
+
+    // Stage the version edits and stop; the user commits them with their other changes (--no-commit)
+    if cli.no_commit {
+        if action.needs_cargo_update {
+            write_current_version(&cargo_path, &manifest_dir, cli, &new_cargo_version)?;
+        }
+        stage_release(cli, &repo_dir, &owned)?;
+        let staged_files = git::get_staged_files(&repo_dir)?;
+
+        status!("Staged {} for {} (not committed or tagged):", staged_files.len(), new_tag);
+        for file in &staged_files {
+            status!("  - {}", file);
+        }
+        output!("{}", new_tag);
+        return Ok(outcome);
+    }
+
+    // Tag the release commit, unless tagging is left to CI (--no-tag)
+    let create_tag = |message: &str| -> Result<()> {
+        if cli.no_tag {
+            return Ok(());
+        }
+        let mut created = Vec::new();
+        let tagged = std::iter::once(&new_tag).chain(&member_tags).try_for_each(|tag| -> Result<()> {
+            git::create_tag(&repo_dir, tag, message, &tag_options)?;
+            created.push(tag);
+            Ok(())
+        });
+        if let Err(e) = tagged {
+            // Don't leave a partial set of release tags behind
+            for tag in created {
+                git::delete_tag(&repo_dir, tag, None)?;
+            }
+            if let Some(ref point) = rollback_point {
+                rollback(&repo_dir, point)
+                    .context("Rollback after failed tagging also failed")?;
+                return Err(e.wrap_err("Tagging failed; rolled back the release commit and version files"));
+            }
+            return Err(e);
+        }
+        info!("Created tag: {}", new_tag);
+        if !member_tags.is_empty() {
+            status!("Tagged members: {}", member_tags.join(", "));
+        }
+        Ok(())
+    };
+    let and_tagged = if cli.no_tag { "" } else { " and tagged" };
+
+    // Commit the release, or fold it into HEAD (--amend)
+    let committed = if cli.amend { "Amended commit" } else { "Committed" };
+    let commit = |message: &str| {
+        if cli.amend {
+            git::amend_commit(&repo_dir, message, &commit_options)
+        } else {
+            git::commit(&repo_dir, message, &commit_options)
+        }
+    };
+    // With nothing staged there is no release commit, so say which existing commit gets the tag
+    let note_tagging_head = || -> Result<()> {
+        if !cli.no_tag {
+            let message = format!("No changes to commit; tagging existing HEAD {}", git::head_sha(&repo_dir)?);
+            info!("{}", message);
+            status!("{}", message);
+        }
+        Ok(())
+    };
+
+    let message_options = MessageOptions {
+        owned_files: owned.clone(),
+        ..MessageOptions::from_cli(cli)
+    };
+    let message_context = MessageContext {
+        version: &new_cargo_version,
+        tag: &new_tag,
+        previous: previous_version.as_deref().unwrap_or("none"),
+        date: &date,
+    };
+
+    // Workflow branches based on whether there are uncommitted changes
+    if has_changes {
+        // ===== STANDARD WORKFLOW: Uncommitted changes exist =====
+
+        // 8. Update Cargo.toml if needed
+        if action.needs_cargo_update {
+            write_current_version(&cargo_path, &manifest_dir, cli, &new_cargo_version)?;
+        }
+
+        // 9. Stage all changes
+        stage_release(cli, &repo_dir, &owned)?;
+
+        // 10. Determine commit message
+        let staged_files = git::get_staged_files(&repo_dir)?;
+        let commit_message =
+            determine_commit_message(&message_options, &message_context, &staged_files, action.is_initial_tag)?;
+
+        // 11. Commit
+        if !staged_files.is_empty() {
+            commit(&commit_message)?;
+            info!("Committed with message: {}", commit_message);
+        } else {
+            note_tagging_head()?;
+        }
+
+        // 12. Create annotated tag
+        create_tag(&tag_message(cli, &message_context, &commit_message))?;
+
+        success!("{}{} {}", committed, and_tagged, new_tag);
+    } else {
+        // ===== CLEAN TREE WORKFLOW: No uncommitted changes =====
+
+        // Check if HEAD has been pushed
+        let is_pushed = git::is_head_pushed(&repo_dir)?;
+
+        // Update Cargo.toml
+        if action.needs_cargo_update {
+            write_current_version(&cargo_path, &manifest_dir, cli, &new_cargo_version)?;
+        }
+
+        // Stage the Cargo.toml changes
+        stage_release(cli, &repo_dir, &owned)?;
+        let staged_files = git::get_staged_files(&repo_dir)?;
+
+        if is_pushed {
+            // HEAD is pushed - create a new commit
+            let commit_message =
+                determine_commit_message(&message_options, &message_context, &staged_files, action.is_initial_tag)?;
+
+            if !staged_files.is_empty() {
+                commit(&commit_message)?;
+                info!("Committed with message: {}", commit_message);
+            } else {
+                note_tagging_head()?;
+            }
+
+            create_tag(&tag_message(cli, &message_context, &commit_message))?;
+
+            success!("{}{} {}", committed, and_tagged, new_tag);
+        } else {
+            // HEAD is not pushed - amend the previous commit
+            if !staged_files.is_empty() {
+                git::amend_commit_no_edit(&repo_dir, &commit_options)?;
+                info!("Amended previous commit with Cargo.toml changes");
+            } else {
+                note_tagging_head()?;
+            }
+
+            // Use automatic message for the tag since we're amending
+            let automatic = format!("Bump version to {}", new_tag);
+            create_tag(&tag_message(cli, &message_context, &automatic))?;
+
+            success!("Amended commit{} {}", and_tagged, new_tag);
+        }
+    }
+
+    // Record the release in the ledger (without a tag there is no release to record yet)
+    if !cli.no_tag {
+        record_in_ledger(cli, dir, &repo_dir, previous_version, &new_cargo_version, &new_tag)?;
+    }
+
+    // Verify the release captured everything
+    if cli.verify_clean_after {
+        let leftover = git::get_uncommitted_files(&repo_dir)?;
+        if !leftover.is_empty() {
+            bail!(
+                "Working tree is not clean after tagging {}. Uncommitted files:\n{}",
+                new_tag,
+                leftover.iter().map(|f| format!("  - {}", f)).collect::<Vec<_>>().join("\n")
+            );
+        }
+    }
+
+    run_post_hook(cli, &repo_dir, &new_cargo_version, &new_tag)?;
+    let release_tags: Vec<&str> = std::iter::once(&new_tag).chain(&member_tags).map(String::as_str).collect();
+    push_release(cli, &repo_dir, &release_tags)?;
+
+    // Status goes to stderr; stdout carries only the resulting tag for piping
+    output!("{}", new_tag);
+
+    if !dir_name.is_empty() && dir != env::current_dir().unwrap_or_default() {
+        status!("[{}] Done", dir_name);
+    }
+
+    Ok(Outcome {
+        committed: true,
+        tagged: !cli.no_tag,
+        ..outcome
+    })
+}
+
+/// The release settings for `bump init`: a --tag-only run of its directory, so it gets the
+/// same config, backend, output and checks as any other release
+/// Like --tag-only it never commits, so the version must already be committed at HEAD
+fn init_cli(cli: &Cli, args: &cli::InitArgs) -> Cli {
+    let mut release = cli.clone();
+    release.command = None;
+    release.tag_only = true;
+    release.directories = args.directory.iter().cloned().collect();
+    release.dry_run |= args.dry_run;
+    if args.tag_prefix.is_some() {
+        release.tag_prefix = args.tag_prefix.clone();
+    }
+    if args.message.is_some() {
+        release.message = args.message.clone();
+    }
+    release
+}
+
+/// Revert the most recent release (bump undo): delete its tag, soft-reset the release
+/// commit if HEAD is still on it, and put the previous version back in Cargo.toml
+fn undo(dir: &Path, args: &cli::UndoArgs) -> Result<()> {
+    if !git::is_git_repo(dir) {
+        bail!("Not a git repository: {}", dir.display());
+    }
+
+    let tag = git::get_latest_tag(dir, &args.tag_prefix, false)?
+        .with_context(|| format!("No release tags matching '{}*' found", args.tag_prefix))?;
+    // Without the remote configured the tag can't have been pushed there; a remote that can't be asked
+    // might have it, so that also needs --force
+    if !args.force && git::ensure_remote(dir, &args.remote).is_ok() {
+        let pushed = git::remote_has_tag(dir, &args.remote, &tag).context(format!(
+            "Couldn't check whether {} was pushed to {}; pass --force to undo anyway",
+            tag, args.remote
+        ))?;
+        if pushed {
+            bail!(
+                "Tag {} has already been pushed to {}. Undoing it would rewrite published history; \
+                 pass --force to undo anyway (and delete the remote tag yourself).",
+                tag,
+                args.remote
+            );
+        }
+    }
+
+    let tag_commit = git::resolve_commit(dir, &tag)?;
+    let head_is_tagged = git::resolve_commit(dir, "HEAD")? == tag_commit;
+    let has_parent = git::resolve_commit(dir, &format!("{}~1", tag_commit)).is_ok();
+
+    // The version before the release, from Cargo.toml in the release commit's parent
+    let cargo_path = cargo::cargo_toml_path(dir);
+    let previous_version = if has_parent && cargo_path.exists() {
+        git::show_file(dir, &format!("{}~1", tag_commit), "./Cargo.toml")
+            .ok()
+            .and_then(|content| cargo::read_version_from_str(&content).ok().flatten())
+    } else {
+        None
+    };
+    let current_version = if cargo_path.exists() { cargo::read_version(&cargo_path)? } else { None };
+
+    // bump init and --tag-only tag the user's own commit; only a commit bump made is dropped
+    let head_is_release = head_is_tagged
+        && has_parent
+        && is_release_commit(dir, &tag_commit, &tag, &args.tag_prefix, previous_version.as_deref())?;
+
+    // Say exactly what will happen before touching anything
+    status!("undo: {}", tag);
+    status!("  - delete tag {}", tag);
+    if head_is_release {
+        status!("  - soft-reset HEAD ({}) to its parent, keeping the changes staged", &tag_commit[..7]);
+    }
+    let restore_version = match (&previous_version, &current_version) {
+        (Some(previous), Some(current)) if head_is_release && previous != current => Some((current, previous)),
+        _ => None,
+    };
+    if let Some((current, previous)) = restore_version {
+        status!("  - rewrite Cargo.toml version {} → {}", current, previous);
+    }
+
+    git::delete_tag(dir, &tag, None)?;
+    info!("Deleted tag {}", tag);
+
+    if head_is_release {
+        git::reset_soft(dir, "HEAD~1")?;
+        info!("Soft-reset release commit {}", tag_commit);
+    }
+
+    if let Some((_, previous)) = restore_version {
+        cargo::write_version(&cargo_path, previous)?;
+        cargo::sync_lockfile(dir)?;
+        info!("Restored Cargo.toml version {}", previous);
+    }
+
+    success!("Undid {}", tag);
+    Ok(())
+}
+
+/// Files a release commit may touch: the version files and everything bump syncs or generates
+const RELEASE_FILES: &[&str] =
+    &["Cargo.toml", "Cargo.lock", "VERSION", "CHANGELOG.md", "README.md", "package.json", "pyproject.toml"];
+
+/// Whether a tagged commit is bump's own release commit rather than the user's work
+/// It is when it only changed files bump writes, or its subject is one bump generates
+/// (the default messages or the configured commit template)
+fn is_release_commit(dir: &Path, commit: &str, tag: &str, prefix: &str, previous: Option<&str>) -> Result<bool> {
+    let only_release_files = git::commit_files(dir, commit)?.iter().all(|file| {
+        let name = Path::new(file).file_name().map(|name| name.to_string_lossy());
+        name.is_some_and(|name| RELEASE_FILES.contains(&name.as_ref()))
+    });
+    if only_release_files {
+        return Ok(true);
+    }
+
+    let subject = git::commit_field(dir, commit, "%s")?;
+    if subject == format!("Bump version to {}", tag) || subject == format!("Release {}", tag) {
+        return Ok(true);
+    }
+    let Some(template) = config::Config::load(dir)?.commit_template else {
+        return Ok(false);
+    };
+    let version = version::parse_tag(tag, prefix, false).map(|v| version::format_cargo_version(&v));
+    let date = git::commit_field(dir, commit, "%cs")?;
+    let context = MessageContext {
+        version: version.as_deref().unwrap_or(tag),
+        tag,
+        previous: previous.unwrap_or("none"),
+        date: &date,
+    };
+    let generated = template::format_message(&template, &context);
+    Ok(generated.lines().next() == Some(subject.as_str()))
+}
+
+/// Delete a tag to clean up a bad release (--delete-tag); the remote is only touched when --remote is given
+fn delete_release_tag(dir: &Path, tag: &str, cli: &Cli) -> Result<()> {
+    if !git::is_git_repo(dir) {
+        bail!("Not a git repository: {}", dir.display());
+    }
+    let repo_dir = git::repo_root(dir)?;
+    if !git::tag_exists(&repo_dir, tag)? {
+        bail!("Tag {} does not exist in {}", tag, repo_dir.display());
+    }
+
+    let remote = cli.remote_given.then_some(cli.remote.as_str());
+    let target = match remote {
+        Some(remote) => format!("{} (locally and on {})", tag, remote),
+        None => tag.to_string(),
+    };
+    if cli.dry_run {
+        dry_run!("Would delete tag {}", target);
+        return Ok(());
+    }
+
+    git::delete_tag(&repo_dir, tag, remote)?;
+    info!("Deleted tag {}", target);
+    success!("Deleted tag {}", target);
+    Ok(())
+}
+
+/// Check prerequisites for each directory without reading tags or changing anything (--probe)
+/// The report says whether git and cargo are usable and each directory is a repo with a Cargo.toml
+/// (printed as-is with --json)
+fn probe(directories: &[PathBuf], cli: &Cli) -> serde_json::Value {
+    let tools = cli::required_tools_ok();
+    let checked: Vec<serde_json::Value> = directories
+        .iter()
+        .map(|dir| {
+            let manifest_dir = match cli.manifest_path {
+                Some(ref path) => dir.join(path).parent().unwrap_or(dir).to_path_buf(),
+                None => dir.clone(),
+            };
+            let ready = git::is_git_repo(&manifest_dir) && cargo::cargo_toml_exists(&manifest_dir);
+            serde_json::json!({ "directory": dir.display().to_string(), "ready": ready })
+        })
+        .collect();
+    let ready = tools && checked.iter().all(|dir| dir["ready"] == true);
+    serde_json::json!({ "ready": ready, "tools": tools, "directories": checked })
+}
+
+/// Summarize a repository's release state for `bump status` (read-only)
+fn status_report(dir: &Path, tag_prefix: &str) -> Result<String> {
+    if !git::is_git_repo(dir) {
+        bail!("Not a git repository: {}", dir.display());
+    }
+
+    let cargo_path = dir.join("Cargo.toml");
+    let current = if cargo_path.exists() { cargo::read_version(&cargo_path)? } else { None };
+    let latest_tag = git::get_latest_tag(dir, tag_prefix, false)?;
+    let commits = git::commits_since(dir, latest_tag.as_deref())?.len();
+    let uncommitted = git::get_uncommitted_files(dir)?;
+
+    let version_line = current.clone().unwrap_or_else(|| "none (no Cargo.toml version)".to_string());
+    let tag_line = match latest_tag {
+        Some(ref tag) => {
+            let tagged = version::parse_tag(tag, tag_prefix, false).ok();
+            let matches = tagged.is_some() && tagged == current.as_deref().and_then(|v| version::parse_version(v).ok());
+            format!("{} ({})", tag, if matches { "matches Cargo.toml" } else { "differs from Cargo.toml" })
+        }
+        None => "none".to_string(),
+    };
+    let commits_line = match latest_tag {
+        Some(ref tag) => format!("{} since {}", commits, tag),
+        None => format!("{} (no release yet)", commits),
+    };
+    let tree_line = if uncommitted.is_empty() {
+        "clean".to_string()
+    } else {
+        format!("dirty ({} uncommitted)", uncommitted.len())
+    };
+
+    Ok(format!(
+        "version: {}\ntag:     {}\ncommits: {}\ntree:    {}",
+        version_line, tag_line, commits_line, tree_line
+    ))
+}
+
+/// `bump status`: print a report for each directory; fails if any directory couldn't be read
+fn status_command(args: &cli::StatusArgs) -> Result<()> {
+    let cwd = env::current_dir().context("Failed to get current directory")?;
+    let directories: Vec<PathBuf> = if args.directories.is_empty() {
+        vec![cwd]
+    } else {
+        args.directories.iter().map(|dir| cwd.join(dir)).collect()
+    };
+
+    let mut failures = 0;
+    for dir in &directories {
+        if directories.len() > 1 {
+            output!("[{}]", dir.file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+        }
+        match status_report(dir, &args.tag_prefix) {
+            Ok(report) => output!("{}", report),
+            Err(e) => {
+                eprintln!("{} {:#}", output::error_label(), e);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `bump set-version`: write the version into the manifest(s) and sync Cargo.lock, touching nothing in git
+/// Works outside a git repository, for CI steps that commit on their own
+fn set_version(dir: &Path, args: &cli::SetVersionArgs) -> Result<()> {
+    let new_version = version::format_cargo_version(
+        &version::parse_version(&args.version).context(format!("Invalid version '{}'", args.version))?,
+    );
+
+    if args.package.is_empty() {
+        let cargo_path = cargo::cargo_toml_path(dir);
+        if !cargo_path.exists() {
+            bail!("No Cargo.toml found in: {}", dir.display());
+        }
+        let previous = cargo::read_version(&cargo_path)?.unwrap_or_else(|| "none".to_string());
+        cargo::write_version(&cargo_path, &new_version)?;
+        status!("set: {} → {}", previous, new_version);
+        if !args.no_lock {
+            cargo::sync_lockfile(dir)?;
+        }
+    } else {
+        for name in &args.package {
+            let manifest = cargo::find_member_manifest(dir, name)?;
+            let previous = cargo::read_version(&manifest)?.unwrap_or_else(|| "none".to_string());
+            cargo::write_version(&manifest, &new_version)?;
+            status!("set: {} {} → {}", name, previous, new_version);
+
+            for dependent in cargo::update_internal_dependents(dir, name, &new_version)? {
+                status!("Updated {} requirement in {}", name, dependent.display());
+            }
+            if !args.no_lock {
+                cargo::sync_lockfile(manifest.parent().unwrap_or(dir))?;
+            }
+        }
+    }
+
+    output!("{}", new_version);
+    Ok(())
+}
+
+/// Resolve the directories to process (default: current directory), made absolute
+fn resolve_directories(cli: &Cli) -> Result<Vec<PathBuf>> {
+    let cwd = env::current_dir().context("Failed to get current directory")?;
+    let directories: Vec<PathBuf> = if cli.directories.is_empty() {
+        vec![cwd]
+    } else {
+        cli.directories
+            .iter()
+            .map(|dir| if dir.is_absolute() { dir.clone() } else { cwd.join(dir) })
+            .collect()
+    };
+    if !cli.recursive {
+        return Ok(directories);
+    }
+
+    // With --recursive the directories are roots to search for repos
+    let mut repos = Vec::new();
+    for root in &directories {
+        repos.extend(discover::discover_repos(root, cli.max_depth)?);
+    }
+    if !cli.probe {
+        status!("Discovered {} repos", repos.len());
+    }
+    Ok(repos)
+}
+
+/// The outcome of one directory along with the output it produced
+type DirectoryResult = (Result<Outcome>, output::Captured);
+
+/// Process directories on up to `jobs` worker threads (--jobs), buffering each directory's
+/// output so it can be printed as one group; results come back in input order
+fn process_in_parallel(targets: &[(PathBuf, Cli)], jobs: usize) -> Vec<DirectoryResult> {
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<DirectoryResult>>> = targets.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(targets.len()) {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some((dir, cli)) = targets.get(index) else {
+                        break;
+                    };
+                    let result = output::capture(|| process_directory(dir, cli, cli.bump_type()));
+                    *results[index].lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .unwrap_or_else(|e| e.into_inner())
+                .unwrap_or_else(|| (Err(eyre::eyre!("Worker thread did not finish")), output::Captured::default()))
+        })
+        .collect()
+}
+
+/// Write the completion script for the shell, covering every flag and subcommand
+fn write_completions(shell: clap_complete::Shell, out: &mut dyn Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "bump", out);
+}
+
+fn main() {
+    // An error outside the per-directory runs (bad config, git too old, a failed undo) means nothing was released
+    if let Err(e) = run() {
+        eprintln!("{} {:#}", output::error_label(), e);
+        std::process::exit(EXIT_ALL_FAILED);
+    }
+}
+
+fn run() -> Result<()> {
+    let mut cli = cli::parse_from(env::args_os()).unwrap_or_else(|e| e.exit());
+
+    // Completions need no repository, so print them before any git work
+    if let Some(shell) = cli.completions {
+        write_completions(shell, &mut io::stdout());
+        return Ok(());
+    }
+
+    // Probe answers with its exit code (and --json) alone, so it runs before logging and before the
+    // git check, which would report a missing git as an error
+    if cli.probe {
+        let report = match resolve_directories(&cli) {
+            Ok(directories) => probe(&directories, &cli),
+            Err(_) => serde_json::json!({ "ready": false }),
+        };
+        if cli.json {
+            println!("{}", report);
+        }
+        std::process::exit(if report["ready"] == true { 0 } else { 1 });
+    }
+
+    // Output, backend and retries apply to the subcommands too
+    output::set_quiet(cli.quiet);
+    output::set_json(cli.json);
+    output::set_color(output::use_color(
+        cli.color,
+        env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+        io::stderr().is_terminal(),
+    ));
+    git::set_backend(cli.backend)?;
+    cargo::set_lock_retries(cli.lock_retries);
+
+    // Every subcommand but set-version runs git, so a missing or too old git fails before any of them
+    if !matches!(cli.command, Some(cli::BumpCommand::SetVersion(_))) {
+        cli::ensure_git_version(cli::MIN_GIT_VERSION)?;
+    }
+
+    match cli.command {
+        Some(cli::BumpCommand::Init(ref args)) => cli = init_cli(&cli, args),
+        Some(cli::BumpCommand::Undo(ref args)) => {
+            setup_logging(&cli, false);
+            let cwd = env::current_dir().context("Failed to get current directory")?;
+            let dir = args.directory.as_ref().map(|d| cwd.join(d)).unwrap_or(cwd);
+            return undo(&dir, args);
+        }
+        Some(cli::BumpCommand::Status(ref args)) => return status_command(args),
+        Some(cli::BumpCommand::SetVersion(ref args)) => {
+            let cwd = env::current_dir().context("Failed to get current directory")?;
+            let dir = args.directory.as_ref().map(|d| cwd.join(d)).unwrap_or(cwd);
+            return set_version(&dir, args);
+        }
+        None => {}
+    }
+
+
+    // Determine directories to process
+    let directories = resolve_directories(&cli)?;
+
+    setup_logging(&cli, cli.verbose);
+
+    // Cleaning up a bad release replaces the normal run
+    if let Some(ref tag) = cli.delete_tag {
+        for dir in &directories {
+            delete_release_tag(dir, tag, &cli)?;
+        }
+        return Ok(());
+    }
+
+    // Resolve --message-file up front so a bad file fails before any changes are made
+    if let Some(ref path) = cli.message_file {
+        cli.message = Some(read_message_file(path)?);
+    }
+    if let Some(ref path) = cli.commit_body_file {
+        cli.commit_body = Some(read_message_file(path).context("Invalid --commit-body-file")?);
+    }
+
+    // Ask before releasing for real on a terminal; decided before --jobs turns on --yes below
+    let confirm_release = !cli.dry_run && !cli.print_version && !cli.yes && io::stdin().is_terminal();
+
+    // Parallel workers can't share stdin, so never prompt when running with --jobs
+    let parallel = cli.jobs > 1 && directories.len() > 1;
+    if parallel {
+        cli.yes = true;
+    }
+
+    // Without a bump flag, ask on a terminal; scripts keep the patch default so they don't hang
+    cli.interactive = !cli.has_bump_selection() && !cli.print_version && !cli.yes && io::stdin().is_terminal();
+
+    // Several directories share one plan and one question up front, unless each still has to
+    // ask for its bump type; then each directory confirms its own plan
+    let combined_plan = confirm_release && directories.len() > 1 && !cli.interactive;
+    cli.confirm = confirm_release && !combined_plan;
+
+    // Apply each directory's .bump.toml up front so a bad config fails before any changes are made
+    let targets = directories
+        .iter()
+        .map(|dir| Ok((dir.clone(), config::Config::load(dir)?.apply(&cli))))
+        .collect::<Result<Vec<(PathBuf, Cli)>>>()?;
+
+    // A config's min-git-version can raise the requirement checked above, before anything is changed
+    let git_requirements: BTreeSet<&str> = targets
+        .iter()
+        .map(|(_, dir_cli)| dir_cli.min_git_version())
+        .filter(|min_version| *min_version != cli::MIN_GIT_VERSION)
+        .collect();
+    for min_version in git_requirements {
+        cli::ensure_git_version(min_version)?;
+    }
+
+    if combined_plan {
+        confirm_combined_plan(&targets)?;
+    }
+
+    info!("Starting bump with type: {:?}", cli.bump_type());
+
+    let mut successes = 0;
+    let mut failures = 0;
+    let mut rows = Vec::new();
+
+    let dir_header = |dir: &Path| {
+        if directories.len() > 1 {
+            status!("\n[{}]", summary::directory_label(dir));
+        }
+    };
+    let mut tally = |dir: &Path, result: Result<Outcome>| {
+        match result {
+            Ok(_) => successes += 1,
+            Err(ref e) => {
+                eprintln!("{} {:#}", output::error_label(), e);
+                failures += 1;
+            }
+        }
+        rows.push(summary::Row::new(dir, result));
+    };
+
+    if parallel {
+        // Print each directory's buffered output as one block, in the order given
+        let results = process_in_parallel(&targets, cli.jobs);
+        for ((dir, _), (result, captured)) in targets.iter().zip(results) {
+            dir_header(dir);
+            eprint!("{}", captured.stderr);
+            if !cli.json {
+                print!("{}", captured.stdout);
+            }
+            tally(dir, result);
+        }
+    } else {
+        for (dir, dir_cli) in &targets {
+            dir_header(dir);
+            tally(dir, process_directory(dir, dir_cli, dir_cli.bump_type()));
+        }
+    }
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&rows).context("Failed to serialize the summary")?);
+    }
+
+    if directories.len() > 1 {
+        status!("");
+        for line in summary::table(&rows) {
+            status!("{}", line);
+        }
+        status!("");
+        if failures == 0 && targets.iter().any(|(_, dir_cli)| dir_cli.push_reminder()) {
+            success!("All done! Don't forget to push your changes.");
+        } else if failures == 0 {
+            success!("All done!");
+        } else {
+            status!("Completed: {} succeeded, {} failed", successes, failures);
+        }
+    }
+
+    let pending = cli.dry_run && rows.iter().any(|row| row.outcome.pending);
+    let code = exit_code(successes, failures, pending);
+    if code != EXIT_SUCCESS {
+        std::process::exit(code);
+    }
+
+    Ok(())
+}
+
+/// =============================================================================
+/// TEST MODULE FOR BUMP VERSION LOGIC
+/// =============================================================================
+///
+/// THE RULES (EXACTLY AS SPECIFIED):
+///
+/// 1. `0.1.0` is the SPECIAL "UNTOUCHED DEFAULT" version.
+///    - If Cargo.toml = 0.1.0 and git tags exist → DEFER TO GIT TAG
+///    - If Cargo.toml = 0.1.0 and no git tags → Create initial tag v0.1.0
+///
+/// 2. ANY OTHER VERSION in Cargo.toml means "ACTIVELY MANAGED"
+///    - If Cargo.toml != 0.1.0 and latest tag MATCHES → Bump from that version
+///    - If Cargo.toml != 0.1.0 and latest tag DOES NOT MATCH → **ERROR**
+///    - If Cargo.toml != 0.1.0 and no tags exist → Create initial tag
+///
+/// 3. If Cargo.toml has NO version field:
+///    - If git tags exist → Bump from latest tag
+///    - If no git tags → Start at 0.1.0
+///
+/// =============================================================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    // =========================================================================
+    // TEST HELPERS
+    // =========================================================================
+
+    fn setup_git_repo(dir: &Path) {
+        Command::new("git")
+            .args(["init"])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to init git repo");
+
+        Command::new("git")
+            .args(["config", "user.email", "test@test.com"])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to set git email");
+
+        Command::new("git")
+            .args(["config", "user.name", "Test"])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to set git name");
+    }
+
+    fn create_initial_commit(dir: &Path) {
+        fs::write(dir.join("README.md"), "# Test").unwrap();
+        Command::new("git")
+            .args(["add", "-A"])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to add files");
+
+        Command::new("git")
+            .args(["commit", "-m", "Initial commit"])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to commit");
+    }
+
+    fn create_git_tag(dir: &Path, tag: &str) {
+        Command::new("git")
+            .args(["tag", "-a", tag, "-m", tag])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to create tag");
+    }
+
+    fn create_cargo_toml(dir: &Path, version: Option<&str>) {
+        let content = match version {
+            Some(v) => format!(
+                r#"[package]
+name = "test-pkg"
+version = "{}"
+"#,
+                v
+            ),
+            None => r#"[package]
+name = "test-pkg"
+"#
+            .to_string(),
+        };
+        fs::write(dir.join("Cargo.toml"), content).unwrap();
+    }
+
+    fn git_output(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    // =========================================================================
+    // MANIFEST IN A SUBDIRECTORY
+    // =========================================================================
+
+    #[test]
+    fn manifest_path_in_subdirectory_tags_at_repo_root() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        let app = dir.join("app");
+        fs::create_dir_all(&app).unwrap();
+        create_cargo_toml(&app, Some("0.2.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.2.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--manifest-path", "app/Cargo.toml"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        let version = cargo::read_version(&app.join("Cargo.toml")).unwrap();
+        assert_eq!(version, Some("0.2.1".to_string()), "MUST update the manifest in app/");
+        assert!(git::tag_exists(dir, "v0.2.1").unwrap(), "MUST tag at the repo root");
+        assert_eq!(git_output(dir, &["status", "--porcelain"]), "", "MUST commit app/Cargo.toml");
+
+        for (path, problem) in [("app", "must be a path to a Cargo.toml file"), ("lib/Cargo.toml", "not found")] {
+            let cli = Cli::try_parse_from(["bump", "-n", "--manifest-path", path]).unwrap();
+            let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+            assert!(err.contains(problem), "{}", err);
+        }
+    }
+
+    /// A member inheriting version.workspace bumps the parent workspace's version, not its own manifest
+    #[test]
+    fn manifest_path_inherited_version_bumps_workspace_root() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\"]\n\n[workspace.package]\nversion = \"0.4.0\"\n",
+        )
+        .unwrap();
+        let app = dir.join("app");
+        fs::create_dir_all(&app).unwrap();
+        fs::write(app.join("Cargo.toml"), "[package]\nname = \"app\"\nversion.workspace = true\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--manifest-path", "app/Cargo.toml"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("0.4.1"));
+        assert!(fs::read_to_string(app.join("Cargo.toml")).unwrap().contains("version.workspace = true"));
+        assert!(git::tag_exists(dir, "v0.4.1").unwrap());
+        assert_eq!(git_output(dir, &["status", "--porcelain"]), "", "MUST commit the root Cargo.toml");
+    }
+
+    // =========================================================================
+    // NAMESPACED TAGS
+    // =========================================================================
+
+    /// Namespaced tags only consider tags for the same crate
+    #[test]
+    fn namespaced_tags_ignore_other_crates() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.3.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v5.0.0"); // Un-namespaced tag from another release stream
+        create_git_tag(dir, "test-pkg-v0.3.0");
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            tag_prefix: "test-pkg-v".to_string(),
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+
+        assert_eq!(action.target_version, Version::new(0, 3, 1));
+        assert!(!action.is_initial_tag);
+
+        let options = VersionOptions {
+            by: 5,
+            ..options
+        };
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+        assert_eq!(action.target_version, Version::new(0, 3, 5));
+    }
+
+    /// Without a bump flag, an already-tagged version gets a hint but still bumps patch
+    #[test]
+    fn default_bump_of_tagged_version_hints() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.0");
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            default_bump: true,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let (action, captured) = output::capture(|| determine_version_action(dir, &cargo_path, &options));
+        assert_eq!(action.unwrap().target_version, Version::new(1, 2, 1));
+        assert!(
+            captured.stderr.contains("current version v1.2.0 already tagged; pass --patch/--minor/--major"),
+            "{}",
+            captured.stderr
+        );
+
+        let options = VersionOptions::new(BumpType::Minor);
+        let (action, captured) = output::capture(|| determine_version_action(dir, &cargo_path, &options));
+        assert_eq!(action.unwrap().target_version, Version::new(1, 3, 0));
+        assert!(!captured.stderr.contains("already tagged"), "{}", captured.stderr);
+    }
+
+    /// --package bumps and tags one independently versioned member, leaving the others alone
+    #[test]
+    fn package_bumps_only_named_member() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"crate-a\", \"crate-b\"]\n").unwrap();
+        for (name, version) in [("crate-a", "1.0.0"), ("crate-b", "2.0.0")] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+            fs::write(
+                dir.join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\nversion = \"{}\"\n", name, version),
+            )
+            .unwrap();
+        }
+        create_initial_commit(dir);
+        create_git_tag(dir, "crate-b-v2.0.0");
+
+        let cli = Cli::try_parse_from(["bump", "-p", "crate-b"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "crate-b-v2.0.1").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("crate-b/Cargo.toml")).unwrap().as_deref(), Some("2.0.1"));
+        assert_eq!(cargo::read_version(&dir.join("crate-a/Cargo.toml")).unwrap().as_deref(), Some("1.0.0"));
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// --package releases get the same commit template and hooks as a plain bump
+    #[test]
+    fn package_uses_commit_template_and_hooks() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let hooks = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"crate-a\"]\n").unwrap();
+        fs::create_dir_all(dir.join("crate-a")).unwrap();
+        fs::write(dir.join("crate-a/Cargo.toml"), "[package]\nname = \"crate-a\"\nversion = \"1.0.0\"\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "crate-a-v1.0.0");
+
+        let log = hooks.path().join("hooks.log");
+        let pre = format!("echo pre $BUMP_TAG >> {}", log.display());
+        let post = format!("echo post $BUMP_TAG >> {}", log.display());
+        let args = ["bump", "-p", "crate-a", "--commit-template", "chore: release {tag}"];
+        let cli = Cli::try_parse_from(args.iter().copied().chain(["--pre-hook", &pre, "--post-hook", &post])).unwrap();
+        process_directory(dir, &cli, BumpType::Minor).unwrap();
+
+        assert_eq!(git_output(dir, &["log", "-1", "--pretty=%s"]), "chore: release crate-a-v1.1.0");
+        assert_eq!(fs::read_to_string(&log).unwrap(), "pre crate-a-v1.1.0\npost crate-a-v1.1.0\n");
+    }
+
+    /// --all-members bumps and tags each independently versioned member, skipping inheriting ones
+    #[test]
+    fn all_members_bumps_independent_members() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"0.5.0\"\n",
+        )
+        .unwrap();
+        let members = [
+            ("crate-a", "version = \"1.0.0\""),
+            ("crate-b", "version = \"2.0.0\""),
+            ("crate-c", "version.workspace = true"),
+            ("crate-d", "version = \"3.0.0\"\npublish = false"),
+        ];
+        for (name, version) in members {
+            fs::create_dir_all(dir.join("crates").join(name)).unwrap();
+            fs::write(
+                dir.join("crates").join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n{}\n", name, version),
+            )
+            .unwrap();
+        }
+        create_initial_commit(dir);
+        create_git_tag(dir, "crate-a-v1.0.0");
+        create_git_tag(dir, "crate-b-v2.0.0");
+
+        let cli = Cli::try_parse_from(["bump", "--all-members"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Minor));
+        result.unwrap();
+
+        assert!(git::tag_exists(dir, "crate-a-v1.1.0").unwrap());
+        assert!(git::tag_exists(dir, "crate-b-v2.1.0").unwrap());
+        assert!(captured.stderr.contains("crate-a 1.0.0 → 1.1.0"), "{}", captured.stderr);
+        assert!(captured.stderr.contains("Skipping unpublished members: crate-d"), "{}", captured.stderr);
+        let manifest = |name: &str| dir.join("crates").join(name).join("Cargo.toml");
+        assert!(fs::read_to_string(manifest("crate-c")).unwrap().contains("version.workspace = true"));
+        assert_eq!(cargo::read_version(&manifest("crate-d")).unwrap().as_deref(), Some("3.0.0"));
+        assert!(!git::tag_exists(dir, "crate-d-v3.1.0").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("0.5.0"));
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// --tag-per-member tags each published version.workspace member alongside the workspace tag
+    #[test]
+    fn tag_per_member_tags_published_members() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        let members = [("core", ""), ("cli", ""), ("xtask", "publish = false\n")];
+        for (name, publish) in members {
+            fs::create_dir_all(dir.join("crates").join(name)).unwrap();
+            fs::write(
+                dir.join("crates").join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\nversion.workspace = true\n{}", name, publish),
+            )
+            .unwrap();
+        }
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.0");
+        git_output(dir, &["commit", "--allow-empty", "-m", "Add feature"]);
+
+        let cli = Cli::try_parse_from(["bump", "--tag-per-member"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Minor));
+        result.unwrap();
+
+        let head = git_output(dir, &["rev-parse", "HEAD"]);
+        for tag in ["v1.3.0", "core-v1.3.0", "cli-v1.3.0"] {
+            assert_eq!(git_output(dir, &["rev-list", "-n", "1", tag]), head, "{}", tag);
+        }
+        assert!(!git::tag_exists(dir, "xtask-v1.3.0").unwrap());
+        assert!(captured.stderr.contains("Tagged members: cli-v1.3.0, core-v1.3.0"), "{}", captured.stderr);
+
+        // A member tag that already exists stops the next release before anything is written
+        create_git_tag(dir, "core-v1.4.0");
+        git_output(dir, &["commit", "--allow-empty", "-m", "Fix bug"]);
+        let err = process_directory(dir, &cli, BumpType::Minor).unwrap_err();
+        assert!(err.to_string().contains("Tag core-v1.4.0 already exists"), "{}", err);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.3.0"));
+    }
+
+    /// Independent member versions are only a warning; --yes bumps the workspace version anyway
+    #[test]
+    fn independent_members_warn_and_continue_with_yes() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crate-a\", \"crate-b\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        for (name, version) in [("crate-a", "version.workspace = true"), ("crate-b", "version = \"2.0.0\"")] {
+            fs::create_dir_all(dir.join(name)).unwrap();
+            fs::write(
+                dir.join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n{}\n", name, version),
+            )
+            .unwrap();
+        }
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.0.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-n", "--allow-dirty"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--yes"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "v1.0.1").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.0.1"));
+        assert_eq!(cargo::read_version(&dir.join("crate-b/Cargo.toml")).unwrap().as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn require_consistent_refuses_drifted_members() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let member = |name: &str, version: &str| {
+            fs::create_dir_all(dir.join("crates").join(name)).unwrap();
+            fs::write(
+                dir.join("crates").join(name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n{}\n", name, version),
+            )
+            .unwrap();
+        };
+        member("crate-a", "version.workspace = true");
+        member("crate-b", "version = \"2.0.0\"");
+        member("crate-c", "version = \"2.1.0\"");
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.0.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-n", "--allow-dirty", "--require-consistent"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("  - crate-b (crates/crate-b): 2.0.0\n  - crate-c (crates/crate-c): 2.1.0"), "{}", err);
+
+        member("crate-c", "version = \"2.0.0\"");
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+    }
+
+    /// --version-key bumps the version kept under a custom key, leaving package.version alone
+    #[test]
+    fn version_key_bumps_custom_key() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"test-pkg\"\nversion = \"0.0.0\"\n\n[package.metadata.release]\nversion = \"2.4.0\"\n",
+        )
+        .unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v2.4.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let key = "package.metadata.release.version";
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--no-lock", "--version-key", key]).unwrap();
+        process_directory(dir, &cli, BumpType::Minor).unwrap();
+
+        assert!(git::tag_exists(dir, "v2.5.0").unwrap());
+        assert_eq!(cargo::read_version_at(&dir.join("Cargo.toml"), key).unwrap().as_deref(), Some("2.5.0"));
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("0.0.0"));
+    }
+
+    /// --version-file bumps a plain VERSION file in a repo without a Cargo.toml
+    #[test]
+    fn version_file_without_cargo_toml() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(dir.join("VERSION"), "1.2.3\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--version-file", "--minor"]).unwrap();
+        process_directory(dir, &cli, BumpType::Minor).unwrap();
+
+        assert!(git::tag_exists(dir, "v1.3.0").unwrap());
+        assert_eq!(fs::read_to_string(dir.join("VERSION")).unwrap(), "1.3.0\n");
+        assert!(!dir.join("Cargo.toml").exists());
+
+        // Without the flag a missing Cargo.toml is still an error
+        let cli = Cli::try_parse_from(["bump"]).unwrap();
+        assert!(process_directory(dir, &cli, BumpType::Patch).is_err());
+    }
+
+    /// --branch refuses other branches, and a detached HEAD is always refused
+    #[test]
+    fn branch_guard_and_detached_head() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        git_output(dir, &["checkout", "-b", "feature"]);
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--branch", "main"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("Refusing to bump on branch 'feature'"), "{}", err);
+
+        git_output(dir, &["checkout", "--detach"]);
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("HEAD is detached"), "{}", err);
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+    }
+
+    /// --calver moves an old CalVer version to the current month, counter reset
+    #[test]
+    fn calver_bumps_to_current_month() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("2020.1.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v2020.1.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--calver"]).unwrap();
+        process_directory(dir, &cli, cli.bump_type()).unwrap();
+
+        let expected = version::calver_start(chrono::Local::now().date_naive());
+        let expected = version::format_cargo_version(&expected);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some(expected.clone()));
+        assert!(git::tag_exists(dir, &format!("v{}", expected)).unwrap());
+    }
+
+    /// A failing --pre-hook aborts before any edits; --post-hook runs once the tag exists
+    #[test]
+    fn hooks_run_around_the_release() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--pre-hook", "exit 1"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("pre-hook"), "{}", err);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.3".to_string()));
+
+        // The post-hook writes outside the repo so the release tree stays clean
+        let out = TempDir::new().unwrap();
+        let out_file = out.path().join("post-hook.out");
+        let post_hook = format!("git tag -l \"$BUMP_TAG\" > {}", out_file.display());
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--pre-hook", "true", "--post-hook", &post_hook]);
+        process_directory(dir, &cli.unwrap(), BumpType::Patch).unwrap();
+        assert_eq!(fs::read_to_string(&out_file).unwrap(), "v1.2.4\n");
+    }
+
+    /// --fetch-tags sees a tag a teammate already pushed, and only warns when the remote is unreachable
+    #[test]
+    fn fetch_tags_catches_pushed_tag() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let remote = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        git_output(remote.path(), &["init", "--bare"]);
+        git_output(dir, &["remote", "add", "origin", &remote.path().display().to_string()]);
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        // Unreachable remote: warn and carry on with the local check
+        let cli = Cli::try_parse_from(["bump", "-n", "--allow-dirty", "--fetch-tags", "--remote", "nowhere"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        // A teammate already released v1.2.4 from this commit
+        git_output(dir, &["tag", "-a", "v1.2.4", "-m", "Release v1.2.4"]);
+        git_output(dir, &["push", "origin", "HEAD", "v1.2.4"]);
+        git_output(dir, &["tag", "-d", "v1.2.4"]);
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--fetch-tags"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("v1.2.4"), "{}", err);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.3".to_string()));
+    }
+
+    #[test]
+    fn shallow_clone_warns_or_unshallows() {
+        let origin = TempDir::new().unwrap();
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(origin.path());
+        create_cargo_toml(origin.path(), Some("1.2.3"));
+        create_initial_commit(origin.path());
+        create_git_tag(origin.path(), "v1.2.3");
+        git_output(origin.path(), &["commit", "--allow-empty", "-m", "Add feature"]);
+        let url = format!("file://{}", origin.path().display());
+        git_output(dir, &["clone", "--quiet", "--depth", "1", "--no-tags", &url, "."]);
+
+        let cli = Cli::try_parse_from(["bump", "-n"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+        assert!(captured.stderr.contains("shallow clone"), "{}", captured.stderr);
+
+        let cli = Cli::try_parse_from(["bump", "-n", "--unshallow"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+        assert!(!captured.stderr.contains("shallow clone"), "{}", captured.stderr);
+        assert!(!git::is_shallow(dir).unwrap());
+        assert!(git::tag_exists(dir, "v1.2.3").unwrap());
+    }
+
+    /// A first release another clone already pushed is found with --fetch-tags; without it the remote is never asked
+    #[test]
+    fn initial_tag_already_on_remote_needs_fetch_tags() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let remote = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        git_output(remote.path(), &["init", "--bare"]);
+        git_output(dir, &["remote", "add", "origin", &remote.path().display().to_string()]);
+
+        // Another clone already released v0.4.0
+        create_git_tag(dir, "v0.4.0");
+        git_output(dir, &["push", "origin", "HEAD", "v0.4.0"]);
+        git_output(dir, &["tag", "-d", "v0.4.0"]);
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        // No network without the flag, so even an unreachable remote doesn't matter
+        git_output(dir, &["remote", "add", "offline", &dir.join("missing").display().to_string()]);
+        let cli = Cli::try_parse_from(["bump", "-n", "--allow-dirty", "--remote", "offline"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--fetch-tags"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+        assert!(git::tag_exists(dir, "v0.4.1").unwrap());
+    }
+
+    /// --check-remote refuses a branch behind its upstream, and --check-remote=warn carries on
+    #[test]
+    fn check_remote_refuses_when_behind() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let remote = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        git_output(remote.path(), &["init", "--bare"]);
+        git_output(dir, &["remote", "add", "origin", &remote.path().display().to_string()]);
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+        git_output(dir, &["commit", "-am", "Teammate change"]);
+        git_output(dir, &["push", "-u", "origin", "HEAD"]);
+        git_output(dir, &["reset", "--hard", "HEAD~1"]);
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--check-remote"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("1 commit(s) behind"), "{}", err);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.3".to_string()));
+
+        let cli = Cli::try_parse_from(["bump", "-n", "--check-remote=warn"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+    }
+
+    /// --amend folds the release into an unpushed HEAD, and refuses a tagged HEAD
+    #[test]
+    fn amend_folds_release_into_head() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        // HEAD is the tagged release
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--amend"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("HEAD is already tagged"), "{}", err);
+
+        git_output(dir, &["commit", "-am", "feat: new feature"]);
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature, tweaked").unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(git_output(dir, &["rev-list", "--count", "HEAD"]), "2");
+        assert_eq!(git_output(dir, &["log", "-1", "--pretty=%s"]), "Bump version to v1.2.4");
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// --no-lock writes Cargo.toml but leaves Cargo.lock alone
+    #[test]
+    fn no_lock_skips_lockfile_sync() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        fs::write(dir.join("Cargo.lock"), "# stale\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--no-lock"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.4".to_string()));
+        assert_eq!(fs::read_to_string(dir.join("Cargo.lock")).unwrap(), "# stale\n");
+    }
+
+    /// --tag-template sets the tag annotation separately from the commit message
+    #[test]
+    fn tag_template_sets_annotation() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--tag-template", "Release {version}"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(git_output(dir, &["log", "-1", "--pretty=%s"]), "Bump version to v1.2.4");
+        assert_eq!(git_output(dir, &["tag", "-l", "--format=%(contents:subject)", "v1.2.4"]), "Release 1.2.4");
+    }
+
+    /// --stamp footers the tag annotation only; the commit message stays clean
+    #[test]
+    fn stamp_footers_tag_annotation_only() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--stamp"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(git_output(dir, &["log", "-1", "--pretty=%B"]), "Bump version to v1.2.4");
+        let annotation = git_output(dir, &["tag", "-l", "--format=%(contents)", "v1.2.4"]);
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert!(annotation.starts_with("Bump version to v1.2.4\n\nDate: "), "{}", annotation);
+        assert!(annotation.contains(&today), "{}", annotation);
+        assert!(annotation.contains(&format!("Released-by: {}", ledger::current_user())), "{}", annotation);
+    }
+
+    /// --changelog prepends the commits since the last tag and commits CHANGELOG.md with the release
+    #[test]
+    fn changelog_is_generated_and_committed() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+        git_output(dir, &["commit", "-am", "feat: new feature"]);
+
+        let cli = Cli::try_parse_from(["bump", "--changelog", "--minor"]).unwrap();
+        process_directory(dir, &cli, BumpType::Minor).unwrap();
+
+        let changelog = fs::read_to_string(dir.join("CHANGELOG.md")).unwrap();
+        assert!(changelog.starts_with("## v1.3.0 ("), "{}", changelog);
+        assert!(changelog.contains("### Features\n\n- feat: new feature\n"), "{}", changelog);
+        assert!(!changelog.contains("Initial commit"), "MUST only include commits since the last tag");
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// A refused release on an already tagged HEAD leaves no CHANGELOG.md behind
+    #[test]
+    fn changelog_not_written_when_head_is_tagged() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+
+        let cli = Cli::try_parse_from(["bump", "--changelog", "--minor"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Minor).unwrap_err();
+        assert!(err.to_string().contains("HEAD already has a tag"), "{}", err);
+        assert!(!dir.join("CHANGELOG.md").exists());
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// --auto picks minor from a feat: commit since the last tag
+    #[test]
+    fn auto_infers_bump_type_from_commits() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+        git_output(dir, &["commit", "-am", "feat: new feature"]);
+        fs::write(dir.join("README.md"), "# Test\n\nFixed").unwrap();
+        git_output(dir, &["commit", "-am", "fix: typo"]);
+
+        let cli = Cli::try_parse_from(["bump", "--auto"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "v1.3.0").unwrap());
+    }
+
+    /// A breaking change noted only in a commit's footer still makes --auto bump major
+    #[test]
+    fn auto_reads_breaking_change_footer() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nMoved config").unwrap();
+        git_output(dir, &["commit", "-am", "fix: move config\n\nBREAKING CHANGE: the old path is ignored"]);
+
+        let cli = Cli::try_parse_from(["bump", "--auto"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "v2.0.0").unwrap());
+    }
+
+    /// --jobs runs each directory on a worker and keeps its output together
+    #[test]
+    fn parallel_processing_groups_output_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let directories: Vec<PathBuf> = ["a", "b", "c"].iter().map(|name| tmp.path().join(name)).collect();
+        for (i, dir) in directories.iter().enumerate() {
+            fs::create_dir_all(dir).unwrap();
+            setup_git_repo(dir);
+            create_cargo_toml(dir, Some(&format!("1.{}.0", i)));
+            create_initial_commit(dir);
+            create_git_tag(dir, &format!("v1.{}.0", i));
+        }
+        fs::write(tmp.path().join("b/Cargo.toml"), "not toml [").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "--print-version", "-j", "3"]).unwrap();
+        let targets: Vec<(PathBuf, Cli)> = directories.iter().map(|dir| (dir.clone(), cli.clone())).collect();
+        let results = process_in_parallel(&targets, cli.jobs);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].1.stdout, "1.0.1\n");
+        assert!(results[1].0.is_err());
+        assert_eq!(results[2].1.stdout, "1.2.1\n");
+    }
+
+    /// --rollback-on-failure undoes the release commit when tagging fails
+    #[test]
+    fn rollback_on_failed_tag_restores_commit_and_version() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        fs::create_dir(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+        let status = Command::new("cargo")
+            .args(["generate-lockfile", "--offline"])
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        // A comment cargo would drop on re-resolving shows the lockfile came back as it was
+        let lockfile = fs::read_to_string(dir.join("Cargo.lock")).unwrap() + "# kept\n";
+        fs::write(dir.join("Cargo.lock"), &lockfile).unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+        fs::write(dir.join("notes.txt"), "wip").unwrap();
+        let head = git_output(dir, &["rev-parse", "HEAD"]);
+
+        // Signing with a key that doesn't exist makes git tag fail after the commit
+        let cli = Cli::try_parse_from([
+            "bump",
+            "-a",
+            "--dirty-files",
+            "src/**",
+            "--local-user",
+            "no-such-key@example.invalid",
+            "--rollback-on-failure",
+        ])
+        .unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+
+        assert!(format!("{:#}", err).contains("rolled back"), "unexpected error: {:#}", err);
+        assert_eq!(git_output(dir, &["rev-parse", "HEAD"]), head, "MUST reset the release commit");
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.2.3"));
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(fs::read_to_string(dir.join("README.md")).unwrap(), "# Test\n\nNew feature");
+        assert_eq!(fs::read_to_string(dir.join("Cargo.lock")).unwrap(), lockfile);
+        let status = git_output(dir, &["status", "--porcelain"]);
+        assert_eq!(status, "M README.md\n?? notes.txt", "MUST only restage restored files");
+    }
+
+    /// bump status reports the version, latest tag, commits since it and the tree state
+    #[test]
+    fn status_reports_version_tag_commits_and_tree() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        assert_eq!(
+            status_report(dir, "v").unwrap(),
+            "version: 1.2.3\ntag:     none\ncommits: 1 (no release yet)\ntree:    clean"
+        );
+
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+        git_output(dir, &["commit", "-am", "feat: new feature"]);
+        fs::write(dir.join("notes.txt"), "wip").unwrap();
+        assert_eq!(
+            status_report(dir, "v").unwrap(),
+            "version: 1.2.3\ntag:     v1.2.3 (matches Cargo.toml)\ncommits: 1 since v1.2.3\n\
+             tree:    dirty (1 uncommitted)"
+        );
+
+        create_cargo_toml(dir, Some("1.3.0"));
+        assert!(status_report(dir, "v").unwrap().contains("v1.2.3 (differs from Cargo.toml)"));
+        assert!(git::has_uncommitted_changes(dir).unwrap());
+    }
+
+    /// --delete-tag checks the tag exists and only deletes it locally without --remote
+    #[test]
+    fn delete_tag_removes_existing_tag() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.0.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.0.0");
+
+        let cli = cli::parse_from(["bump", "--delete-tag", "v1.0.0", "--dry-run"]).unwrap();
+        delete_release_tag(dir, "v1.0.0", &cli).unwrap();
+        assert!(git::tag_exists(dir, "v1.0.0").unwrap(), "MUST not delete on a dry run");
+
+        let cli = cli::parse_from(["bump", "--delete-tag", "v1.0.0"]).unwrap();
+        delete_release_tag(dir, "v1.0.0", &cli).unwrap();
+        assert!(!git::tag_exists(dir, "v1.0.0").unwrap());
+
+        let err = delete_release_tag(dir, "v1.0.0", &cli).unwrap_err();
+        assert!(err.to_string().contains("Tag v1.0.0 does not exist"), "{}", err);
+    }
+
+    /// bump init tags the committed Cargo.toml version as-is, once
+    #[test]
+    fn init_tags_current_version() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.2"));
+        create_initial_commit(dir);
+
+        let cli = Cli::try_parse_from(["bump", "init"]).unwrap();
+        let Some(cli::BumpCommand::Init(ref args)) = cli.command else {
+            panic!("expected init subcommand");
+        };
+        let cli = init_cli(&cli, args);
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, cli.bump_type()));
+        result.unwrap();
+        assert!(git::tag_exists(dir, "v0.4.2").unwrap());
+        assert_eq!(captured.stdout.trim(), "v0.4.2");
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("0.4.2"));
+        assert_eq!(git_output(dir, &["rev-list", "--count", "HEAD"]), "1", "MUST not commit");
+
+        let err = process_directory(dir, &cli, cli.bump_type()).unwrap_err();
+        assert!(err.to_string().contains("Tag v0.4.2 already exists"), "{}", err);
+    }
+
+    /// bump init refuses an uncommitted version, since it never commits
+    #[test]
+    fn init_requires_committed_version() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.2"));
+        create_initial_commit(dir);
+        create_cargo_toml(dir, Some("1.0.0"));
+
+        let cli = Cli::try_parse_from(["bump", "init"]).unwrap();
+        let Some(cli::BumpCommand::Init(ref args)) = cli.command else {
+            panic!("expected init subcommand");
+        };
+        let cli = init_cli(&cli, args);
+        let err = process_directory(dir, &cli, cli.bump_type()).unwrap_err();
+        assert!(err.to_string().contains("commit the version change first"), "{}", err);
+        assert!(!git::tag_exists(dir, "v1.0.0").unwrap());
+    }
+
+    /// bump init in a version.workspace member tags the workspace version with the member's configured prefix
+    #[test]
+    fn init_member_uses_workspace_version_and_config() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"app\"]\n\n[workspace.package]\nversion = \"0.4.0\"\n",
+        )
+        .unwrap();
+        let app = dir.join("app");
+        fs::create_dir_all(&app).unwrap();
+        fs::write(app.join("Cargo.toml"), "[package]\nname = \"app\"\nversion.workspace = true\n").unwrap();
+        fs::write(app.join(".bump.toml"), "tag-prefix = \"app-v\"\n").unwrap();
+        create_initial_commit(dir);
+
+        let cli = Cli::try_parse_from(["bump", "init"]).unwrap();
+        let Some(cli::BumpCommand::Init(ref args)) = cli.command else {
+            panic!("expected init subcommand");
+        };
+        let cli = config::Config::load(&app).unwrap().apply(&init_cli(&cli, args));
+        process_directory(&app, &cli, cli.bump_type()).unwrap();
+        assert!(git::tag_exists(dir, "app-v0.4.0").unwrap());
+    }
+
+    /// With nothing to commit, the tag goes on HEAD and the output names it
+    #[test]
+    fn nothing_to_commit_names_tagged_head() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        let head = git_output(dir, &["rev-parse", "--short", "HEAD"]);
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--dry-run"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+        let expected = format!("No changes to commit; would tag existing HEAD {}: v0.4.0", head);
+        assert!(captured.stderr.contains(&expected), "{}", captured.stderr);
+
+        let cli = Cli::try_parse_from(["bump", "-a"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+        let expected = format!("No changes to commit; tagging existing HEAD {}", head);
+        assert!(captured.stderr.contains(&expected), "{}", captured.stderr);
+        assert_eq!(git_output(dir, &["rev-parse", "--short", "v0.4.0^{commit}"]), head);
+    }
+
+    /// bump undo deletes the tag, drops the release commit and restores the old version
+    #[test]
+    fn undo_reverts_last_release() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        let head = git_output(dir, &["rev-parse", "HEAD"]);
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+        assert!(git::tag_exists(dir, "v1.2.4").unwrap());
+
+        let cli = Cli::try_parse_from(["bump", "undo"]).unwrap();
+        let Some(cli::BumpCommand::Undo(ref args)) = cli.command else {
+            panic!("expected undo subcommand");
+        };
+        let (result, captured) = output::capture(|| undo(dir, args));
+        result.unwrap();
+        assert!(captured.stderr.contains("  - delete tag v1.2.4"), "{}", captured.stderr);
+
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+        assert!(git::tag_exists(dir, "v1.2.3").unwrap(), "MUST only remove the latest tag");
+        assert_eq!(git_output(dir, &["rev-parse", "HEAD"]), head);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.2.3"));
+    }
+
+    /// A remote that can't be asked might already have the tag, so undo needs --force
+    #[test]
+    fn undo_refuses_when_remote_is_unreachable() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        git_output(dir, &["remote", "add", "origin", &dir.join("missing").display().to_string()]);
+
+        let undo_with = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).unwrap();
+            let Some(cli::BumpCommand::Undo(ref args)) = cli.command else {
+                panic!("expected undo subcommand");
+            };
+            undo(dir, args)
+        };
+
+        let err = undo_with(&["bump", "undo"]).unwrap_err();
+        assert!(format!("{:#}", err).contains("--force"), "{:#}", err);
+        assert!(git::tag_exists(dir, "v1.2.3").unwrap());
+
+        undo_with(&["bump", "undo", "--force"]).unwrap();
+        assert!(!git::tag_exists(dir, "v1.2.3").unwrap());
+    }
+
+    /// Undoing a bump init or --tag-only release only deletes the tag; the tagged commit is the user's
+    #[test]
+    fn undo_after_init_keeps_tagged_commit() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        fs::write(dir.join("feature.txt"), "work").unwrap();
+        git_output(dir, &["add", "feature.txt"]);
+        git_output(dir, &["commit", "-m", "Real feature work"]);
+        let head = git_output(dir, &["rev-parse", "HEAD"]);
+
+        let cli = Cli::try_parse_from(["bump", "init"]).unwrap();
+        let Some(cli::BumpCommand::Init(ref args)) = cli.command else {
+            panic!("expected init subcommand");
+        };
+        let cli = init_cli(&cli, args);
+        process_directory(dir, &cli, cli.bump_type()).unwrap();
+        assert!(git::tag_exists(dir, "v0.4.0").unwrap());
+
+        let cli = Cli::try_parse_from(["bump", "undo"]).unwrap();
+        let Some(cli::BumpCommand::Undo(ref args)) = cli.command else {
+            panic!("expected undo subcommand");
+        };
+        undo(dir, args).unwrap();
+
+        assert!(!git::tag_exists(dir, "v0.4.0").unwrap());
+        assert_eq!(git_output(dir, &["rev-parse", "HEAD"]), head, "MUST keep the user's commit");
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// --print-version computes the next version without touching files or git
+    #[test]
+    fn print_version_makes_no_changes() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+
+        let cli = Cli::try_parse_from(["bump", "--print-version", "--minor"]).unwrap();
+        process_directory(dir, &cli, BumpType::Minor).unwrap();
+
+        assert!(!git::tag_exists(dir, "v1.3.0").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.2.3"));
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    /// Unrelated uncommitted changes are refused unless --allow-dirty
+    #[test]
+    fn dirty_tree_is_refused_without_allow_dirty() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nHalf-finished").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+        assert!(err.to_string().contains("README.md"), "unexpected error: {}", err);
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.2.3"));
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+        assert!(git::tag_exists(dir, "v1.2.4").unwrap());
+    }
+
+    /// Only the exact files the run writes may be dirty; another crate's Cargo.toml is unrelated work
+    #[test]
+    fn dirty_tree_allows_only_files_this_run_writes() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        fs::create_dir(dir.join("vendor")).unwrap();
+        fs::write(dir.join("vendor/Cargo.toml"), "[package]\nname = \"vendored\"\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("vendor/Cargo.toml"), "[package]\nname = \"vendored\"\nedition = \"2024\"\n").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+        assert!(err.to_string().contains("  - vendor/Cargo.toml"), "unexpected error: {}", err);
+
+        git_output(dir, &["checkout", "vendor/Cargo.toml"]);
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        fs::write(dir.join("Cargo.toml"), format!("{}\n[features]\n", manifest)).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+        assert!(git::tag_exists(dir, "v1.2.4").unwrap());
+    }
+
+    /// --dirty-files narrows the dirty-tree check to the paths that matter
+    #[test]
+    fn dirty_files_ignores_changes_outside_globs() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::create_dir(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "// half-finished").unwrap();
+
+        let args = ["bump", "-a", "--dirty-files", "src/**", "--dirty-files", "*.md"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+        assert!(err.to_string().contains("  - src/"), "unexpected error: {}", err);
+
+        fs::remove_dir_all(dir.join("src")).unwrap();
+        fs::create_dir(dir.join("generated")).unwrap();
+        fs::write(dir.join("generated/schema.json"), "{}").unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+        assert!(git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(git_output(dir, &["status", "--porcelain"]), "?? generated/", "MUST leave generated/ uncommitted");
+    }
+
+    /// --no-commit updates and stages the version but leaves committing to the user
+    #[test]
+    fn no_commit_stages_without_committing() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+
+        let cli = Cli::try_parse_from(["bump", "--no-commit"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.2.4"));
+        assert_eq!(git::get_staged_files(dir).unwrap(), vec!["Cargo.toml".to_string()]);
+        assert_eq!(git_output(dir, &["rev-list", "--count", "HEAD"]), "1");
+    }
+
+    /// --no-tag bumps and commits but leaves tagging to someone else
+    #[test]
+    fn no_tag_commits_without_tagging() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--no-tag"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("1.2.4"));
+        assert!(!git::has_uncommitted_changes(dir).unwrap());
+    }
+
+    /// An empty --tag-prefix works with bare `1.2.3` tags and ignores `v` tags
+    #[test]
+    fn empty_tag_prefix_uses_bare_tags() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v9.0.0");
+        create_git_tag(dir, "1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--tag-prefix", ""]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "1.2.4").unwrap());
+        assert!(!git::tag_exists(dir, "v1.2.4").unwrap());
+    }
+
+    #[test]
+    fn auto_namespace_tags_creates_namespaced_tag() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.3.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "test-pkg-v0.3.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--auto-namespace-tags"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "test-pkg-v0.3.1").unwrap());
+        assert!(!git::tag_exists(dir, "v0.3.1").unwrap());
+    }
+
+    // =========================================================================
+    // TAG ONLY
+    // =========================================================================
+
+    #[test]
+    fn tag_only_tags_head_without_committing() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        let head = git_output(dir, &["rev-parse", "HEAD"]);
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only"]).unwrap();
+        let outcome = process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(git::tag_exists(dir, "v0.4.0").unwrap());
+        assert_eq!(git_output(dir, &["rev-parse", "HEAD"]), head, "MUST NOT commit");
+        assert!(outcome.tagged && !outcome.committed);
+    }
+
+    /// --no-reminder drops the push hint that a release otherwise ends with
+    #[test]
+    fn no_reminder_drops_push_hint() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+        assert!(captured.stderr.contains("Run: git push --tags"), "{}", captured.stderr);
+
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--no-reminder"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+        assert!(git::tag_exists(dir, "v0.4.1").unwrap());
+        assert!(!captured.stderr.contains("Run: git push"), "{}", captured.stderr);
+    }
+
+    /// Each run reports what it did, for the multi-directory summary
+    #[test]
+    fn process_directory_reports_outcome() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let planned = Outcome {
+            previous: Some("1.2.3".to_string()),
+            version: Some("1.3.0".to_string()),
+            pending: true,
+            ..Outcome::default()
+        };
+        let cli = Cli::try_parse_from(["bump", "-n", "--allow-dirty"]).unwrap();
+        assert_eq!(process_directory(dir, &cli, BumpType::Minor).unwrap(), planned);
+
+        let cli = Cli::try_parse_from(["bump", "--allow-dirty", "-a"]).unwrap();
+        let released = process_directory(dir, &cli, BumpType::Minor).unwrap();
+        let done = Outcome {
+            committed: true,
+            tagged: true,
+            pending: false,
+            ..planned
+        };
+        assert_eq!(released, done);
+    }
+
+    /// --ref tags an older commit at its own Cargo.toml version without committing
+    #[test]
+    fn ref_back_tags_an_older_commit() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        let release = git_output(dir, &["rev-parse", "HEAD"]);
+        create_cargo_toml(dir, Some("0.5.0"));
+        git_output(dir, &["commit", "-am", "Start 0.5.0"]);
+        let head = git_output(dir, &["rev-parse", "HEAD"]);
+
+        let cli = Cli::try_parse_from(["bump", "--ref", "HEAD~1"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(git_output(dir, &["rev-list", "-n", "1", "v0.4.0"]), release);
+        assert!(!git::tag_exists(dir, "v0.5.0").unwrap());
+        assert_eq!(git_output(dir, &["rev-parse", "HEAD"]), head, "MUST NOT commit");
+
+        let cli = Cli::try_parse_from(["bump", "--ref", "no-such-ref"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+        assert!(err.to_string().contains("Cannot resolve 'no-such-ref'"), "{}", err);
+    }
+
+    #[test]
+    fn tag_only_existing_tag_requires_force() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only"]).unwrap();
+        let result = process_directory(dir, &cli, BumpType::Patch);
+        assert!(result.is_err(), "MUST refuse to re-create an existing tag without --force");
+    }
+
+    #[test]
+    fn tag_only_refuses_uncommitted_version() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_cargo_toml(dir, Some("0.5.0"));
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err().to_string();
+        assert!(err.contains("commit the version change first"), "{}", err);
+        assert!(!git::tag_exists(dir, "v0.5.0").unwrap());
+    }
+
+    #[test]
+    fn tag_only_force_refuses_when_head_manifest_differs() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.5.0");
+        // Working tree says 0.5.0 but HEAD still has 0.4.0 committed
+        create_cargo_toml(dir, Some("0.5.0"));
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only", "--force"]).unwrap();
+        let result = process_directory(dir, &cli, BumpType::Patch);
+        assert!(result.is_err(), "MUST refuse to move v0.5.0 onto a 0.4.0 commit");
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only", "--force", "--force-unsafe"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+    }
+
+    #[test]
+    fn tag_only_force_moves_tag_when_versions_match() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("README.md"), "# Fixed").unwrap();
+        Command::new("git")
+            .args(["commit", "-am", "Fix release"])
+            .current_dir(dir)
+            .output()
+            .expect("Failed to commit");
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only", "--force"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(
+            git_output(dir, &["rev-parse", "v0.4.0^{commit}"]),
+            git_output(dir, &["rev-parse", "HEAD"]),
+            "MUST move the tag to HEAD"
+        );
+    }
+
+    /// --push-tags after --tag-only --force moves the remote tag too, and pushes no other local tag
+    #[test]
+    fn tag_only_force_push_tags_moves_remote_tag() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let remote = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        git_output(remote.path(), &["init", "--bare"]);
+        git_output(dir, &["remote", "add", "origin", &remote.path().display().to_string()]);
+        git_output(dir, &["push", "origin", "HEAD", "v0.4.0"]);
+        fs::write(dir.join("README.md"), "# Fixed").unwrap();
+        git_output(dir, &["commit", "-am", "Fix release"]);
+        git_output(dir, &["tag", "scratch"]);
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only", "--force", "--push-tags"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(
+            git_output(remote.path(), &["rev-parse", "v0.4.0^{commit}"]),
+            git_output(dir, &["rev-parse", "HEAD"]),
+            "MUST move the remote tag to HEAD"
+        );
+        assert!(!git::tag_exists(remote.path(), "scratch").unwrap(), "MUST push only the release tag");
+    }
+
+    /// Four segments only go where Cargo doesn't read them: a VERSION file here, never package.version
+    #[test]
+    fn revision_bumps_fourth_segment() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        fs::write(dir.join("VERSION"), "1.2.3.4\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3.4");
+        fs::write(dir.join("README.md"), "# Changed").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--version-file", "--revision"]).unwrap();
+        process_directory(dir, &cli, cli.bump_type()).unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("VERSION")).unwrap(), "1.2.3.5\n");
+        assert_eq!(git_output(dir, &["tag", "--points-at", "HEAD"]), "v1.2.3.5");
+
+        // With a Cargo.toml, --version-file leaves the version in package.version
+        create_cargo_toml(dir, Some("1.2.3"));
+        let err = process_directory(dir, &cli, cli.bump_type()).unwrap_err().to_string();
+        assert!(err.contains("--revision"), "{}", err);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.3".to_string()));
+    }
+
+    /// Leaving 0.x explains what 1.0.0 commits to, without blocking a non-interactive run
+    #[test]
+    fn first_stable_release_is_noted() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.9.2"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.9.2");
+        fs::write(dir.join("README.md"), "# Changed").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--major"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, cli.bump_type()));
+        result.unwrap();
+
+        assert!(captured.stderr.contains("1.0.0 is the first stable release"), "{}", captured.stderr);
+        assert!(git::tag_exists(dir, "v1.0.0").unwrap());
+    }
+
+    /// --tag-only --push-tags tags HEAD and publishes only the tag
+    #[test]
+    fn tag_only_push_tags_publishes_tag() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let remote = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.7.0"));
+        create_initial_commit(dir);
+        git_output(remote.path(), &["init", "--bare"]);
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only", "--push-tags", "--remote", "upstream"]).unwrap();
+        let err = process_directory(dir, &cli, cli.bump_type()).unwrap_err();
+        assert!(err.to_string().contains("No remote named 'upstream'"), "{}", err);
+        assert!(!git::tag_exists(dir, "v0.7.0").unwrap(), "MUST check the remote before tagging");
+
+        git_output(dir, &["remote", "add", "upstream", &remote.path().display().to_string()]);
+        process_directory(dir, &cli, cli.bump_type()).unwrap();
+        assert!(git::tag_exists(remote.path(), "v0.7.0").unwrap());
+    }
+
+    /// --build tags the release with metadata, and the next bump starts from that tag
+    #[test]
+    fn build_metadata_is_tagged_and_bumped_from() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("README.md"), "# Changed").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--build", "ci.42"]).unwrap();
+        process_directory(dir, &cli, cli.bump_type()).unwrap();
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.4+ci.42".to_string()));
+        assert_eq!(git_output(dir, &["tag", "--points-at", "HEAD"]), "v1.2.4+ci.42");
+
+        fs::write(dir.join("README.md"), "# Changed again").unwrap();
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty"]).unwrap();
+        process_directory(dir, &cli, cli.bump_type()).unwrap();
+        assert_eq!(git_output(dir, &["tag", "--points-at", "HEAD"]), "v1.2.5");
+
+        let cli = Cli::try_parse_from(["bump", "--build", "ci_43"]).unwrap();
+        assert!(process_directory(dir, &cli, cli.bump_type()).is_err());
+    }
+
+    /// --author attributes the release commit to the given author
+    #[test]
+    fn author_is_used_for_release_commit() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("README.md"), "# Changed").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--author", "Release Bot <bot@example.com>"])
+            .unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(
+            git_output(dir, &["log", "-1", "--pretty=%an <%ae>"]),
+            "Release Bot <bot@example.com>"
+        );
+    }
+
+    /// An --author without the Name <email> form fails before Cargo.toml is touched
+    #[test]
+    fn invalid_author_is_rejected_before_changes() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--author", "bot@example.com"]).unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+        assert!(err.to_string().contains("Invalid author"));
+        assert!(fs::read_to_string(dir.join("Cargo.toml")).unwrap().contains("0.4.0"));
+    }
+
+    #[test]
+    fn set_version_writes_manifest_without_git() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        create_cargo_toml(dir, Some("0.2.0"));
+
+        let cli = Cli::try_parse_from(["bump", "set-version", "v1.4.0"]).unwrap();
+        let Some(cli::BumpCommand::SetVersion(ref args)) = cli.command else {
+            panic!("expected set-version subcommand");
+        };
+        set_version(dir, args).unwrap();
+
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.4.0".to_string()));
+        assert!(!dir.join(".git").exists());
+
+        let cli = Cli::try_parse_from(["bump", "set-version", "not-a-version"]).unwrap();
+        let Some(cli::BumpCommand::SetVersion(ref args)) = cli.command else {
+            panic!("expected set-version subcommand");
+        };
+        assert!(set_version(dir, args).is_err());
+    }
+
+    #[test]
+    fn update_readme_is_committed_with_release() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("README.md"), "```toml\ntest-pkg = \"0.4.0\"\n```\n").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--update-readme"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(git_output(dir, &["show", "HEAD:README.md"]), "```toml\ntest-pkg = \"0.4.1\"\n```");
+    }
+
+    /// On a clean, pushed tree the README this run rewrote is committed without asking for a message
+    #[test]
+    fn update_readme_on_clean_tree_needs_no_editor() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        let remote = TempDir::new().unwrap();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        fs::write(dir.join("README.md"), "```toml\ntest-pkg = \"0.4.0\"\n```\n").unwrap();
+        git_output(dir, &["add", "-A"]);
+        git_output(dir, &["commit", "-m", "Initial commit"]);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("feature.txt"), "New feature").unwrap();
+        git_output(dir, &["add", "feature.txt"]);
+        git_output(dir, &["commit", "-m", "Add feature"]);
+        git_output(remote.path(), &["init", "--bare"]);
+        git_output(dir, &["remote", "add", "origin", &remote.path().display().to_string()]);
+        git_output(dir, &["push", "-u", "origin", "HEAD"]);
+
+        let cli = Cli::try_parse_from(["bump", "--update-readme", "--no-lock"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        assert_eq!(git_output(dir, &["describe", "--tags", "--exact-match"]), "v0.4.1");
+        assert_eq!(git_output(dir, &["show", "HEAD:README.md"]), "```toml\ntest-pkg = \"0.4.1\"\n```");
+    }
+
+    /// A pyproject.toml whose two version locations disagree stops the release before anything is committed
+    #[test]
+    fn sync_pyproject_commits_version_and_refuses_disagreement() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        fs::write(dir.join("pyproject.toml"), "[project]\nname = \"test-pkg\"\nversion = \"0.4.0\"\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("README.md"), "# Changed").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--sync-pyproject"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+        assert!(git_output(dir, &["show", "HEAD:pyproject.toml"]).contains("version = \"0.4.1\""));
+
+        fs::write(dir.join("pyproject.toml"), "[project]\nversion = \"0.4.1\"\n\n[tool.poetry]\nversion = \"9.9.9\"\n")
+            .unwrap();
+        let err = process_directory(dir, &cli, BumpType::Patch).unwrap_err();
+        assert!(err.to_string().contains("make them agree"), "{}", err);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("0.4.1"));
+    }
+
+    /// --sync-npm writes the new version to package.json and commits it with Cargo.toml
+    #[test]
+    fn sync_npm_commits_matching_package_json() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.4.0"));
+        fs::write(dir.join("package.json"), "{\n  \"name\": \"test-pkg\",\n  \"version\": \"0.4.0\"\n}\n").unwrap();
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.4.0");
+        fs::write(dir.join("README.md"), "# Changed").unwrap();
+
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--sync-npm"]).unwrap();
+        process_directory(dir, &cli, BumpType::Patch).unwrap();
+
+        let committed = git_output(dir, &["show", "HEAD:package.json"]);
+        let package: serde_json::Value = serde_json::from_str(&committed).unwrap();
+        assert_eq!(package["version"], "0.4.1");
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap().as_deref(), Some("0.4.1"));
+        assert!(git::is_working_tree_clean(dir).unwrap());
+    }
+
+    // =========================================================================
+    // LEDGER
+    // =========================================================================
+
+    #[test]
+    fn ledger_records_release() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("repo");
+        fs::create_dir_all(&dir).unwrap();
+        let ledger_path = tmp.path().join("releases.jsonl");
+
+        setup_git_repo(&dir);
+        create_cargo_toml(&dir, Some("0.2.0"));
+        create_initial_commit(&dir);
+        create_git_tag(&dir, "v0.2.0");
+        fs::write(dir.join("README.md"), "# Test\n\nNew feature").unwrap();
+
+        let ledger_arg = ledger_path.to_string_lossy().to_string();
+        let cli = Cli::try_parse_from(["bump", "-a", "--allow-dirty", "--ledger", &ledger_arg]).unwrap();
+        process_directory(&dir, &cli, BumpType::Patch).unwrap();
+
+        let content = fs::read_to_string(&ledger_path).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(entry["old_version"], "0.2.0");
+        assert_eq!(entry["new_version"], "0.2.1");
+        assert_eq!(entry["tag"], "v0.2.1");
+        assert_eq!(entry["commit"], git_output(&dir, &["rev-parse", "HEAD"]));
+    }
+
+    #[test]
+    fn dry_run_shows_manifest_diff() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.2.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.2.0");
+
+        let cli = Cli::try_parse_from(["bump", "-n"]).unwrap();
+        let (result, captured) = output::capture(|| process_directory(dir, &cli, BumpType::Patch));
+        result.unwrap();
+
+        assert!(captured.stderr.contains("-version = \"0.2.0\"\n+version = \"0.2.1\""), "{}", captured.stderr);
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("0.2.0".to_string()));
+    }
+
+    #[test]
+    fn ledger_skipped_in_dry_run() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("repo");
+        fs::create_dir_all(&dir).unwrap();
+        let ledger_path = tmp.path().join("releases.jsonl");
+
+        setup_git_repo(&dir);
+        create_cargo_toml(&dir, Some("0.2.0"));
+        create_initial_commit(&dir);
+
+        let ledger_arg = ledger_path.to_string_lossy().to_string();
+        let cli = Cli::try_parse_from(["bump", "-n", "--ledger", &ledger_arg]).unwrap();
+        process_directory(&dir, &cli, BumpType::Patch).unwrap();
+
+        assert!(!ledger_path.exists());
+    }
+
+    // =========================================================================
+    // PROBE
+    // =========================================================================
+
+    #[test]
+    fn probe_checks_repo_and_manifest() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let cli = Cli::try_parse_from(["bump", "--probe"]).unwrap();
+
+        // Not a git repo yet
+        assert_eq!(probe(std::slice::from_ref(&dir), &cli)["ready"], false);
+
+        // Git repo without Cargo.toml
+        setup_git_repo(&dir);
+        assert_eq!(probe(std::slice::from_ref(&dir), &cli)["ready"], false);
+
+        create_cargo_toml(&dir, Some("0.1.0"));
+        let report = probe(std::slice::from_ref(&dir), &cli);
+        assert_eq!(report["ready"], true);
+        assert_eq!(report["directories"][0]["directory"], dir.display().to_string());
+        assert_eq!(report["directories"][0]["ready"], true);
+    }
+
+    // =========================================================================
+    // MESSAGE FILE
+    // =========================================================================
+
+    #[test]
+    fn message_file_strips_trailing_newline() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("msg.txt");
+        fs::write(&path, "Release notes\n\n- first change\n- second change\n").unwrap();
+
+        let message = read_message_file(&path).unwrap();
+        assert_eq!(message, "Release notes\n\n- first change\n- second change");
+
+        fs::write(&path, "msg\n\n").unwrap();
+        assert_eq!(read_message_file(&path).unwrap(), "msg\n");
+        fs::write(&path, "msg\r\n").unwrap();
+        assert_eq!(read_message_file(&path).unwrap(), "msg");
+    }
+
+    #[test]
+    fn message_file_missing_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let result = read_message_file(&tmp.path().join("missing.txt"));
+        assert!(result.is_err());
     }
 
-    fn create_git_tag(dir: &Path, tag: &str) {
-        Command::new("git")
-            .args(["tag", "-a", tag, "-m", tag])
-            .current_dir(dir)
-            .output()
-            .expect("Failed to create tag");
-    }
+    #[test]
+    fn message_file_empty_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("msg.txt");
+        fs::write(&path, "\n  \n").unwrap();
 
-    fn create_cargo_toml(dir: &Path, version: Option<&str>) {
-        let content = match version {
-            Some(v) => format!(
-                r#"[package]
-name = "test-pkg"
-version = "{}"
-"#,
-                v
-            ),
-            None => r#"[package]
-name = "test-pkg"
-"#
-            .to_string(),
-        };
-        fs::write(dir.join("Cargo.toml"), content).unwrap();
+        let result = read_message_file(&path);
+        assert!(result.is_err());
     }
 
     // =========================================================================
@@ -557,7 +4494,7 @@ name = "test-pkg"
         // NO TAGS
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST create tag v0.1.0
         assert_eq!(action.target_version, Version::new(0, 1, 0), "MUST create tag v0.1.0");
@@ -583,7 +4520,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.0"); // TAG MATCHES DEFAULT
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST bump to v0.1.1
         assert_eq!(
@@ -610,7 +4547,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.28"); // TAG IS HIGHER
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST bump from tag v0.1.28 to v0.1.29
         assert_eq!(
@@ -637,7 +4574,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.28"); // TAG IS HIGHER
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Minor).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Minor)).unwrap();
 
         // MUST minor bump from tag v0.1.28 to v0.2.0
         assert_eq!(
@@ -666,7 +4603,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.28"); // DOES NOT MATCH
 
         let cargo_path = dir.join("Cargo.toml");
-        let result = determine_version_action(dir, &cargo_path, BumpType::Patch);
+        let result = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch));
 
         // MUST ERROR
         assert!(
@@ -694,7 +4631,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.28"); // DOES NOT MATCH (higher)
 
         let cargo_path = dir.join("Cargo.toml");
-        let result = determine_version_action(dir, &cargo_path, BumpType::Patch);
+        let result = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch));
 
         // MUST ERROR
         assert!(
@@ -722,7 +4659,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.2.0"); // MATCHES
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST bump to v0.2.1
         assert_eq!(
@@ -747,7 +4684,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.5"); // MATCHES
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST bump to v0.1.6
         assert_eq!(
@@ -772,7 +4709,7 @@ name = "test-pkg"
         // NO TAGS
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST create tag v0.2.0
         assert_eq!(
@@ -802,7 +4739,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.5"); // MATCHES
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Minor).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Minor)).unwrap();
 
         // MUST bump to v0.2.0
         assert_eq!(
@@ -827,7 +4764,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.5"); // MATCHES
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Major).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Major)).unwrap();
 
         // MUST bump to v1.0.0
         assert_eq!(
@@ -839,6 +4776,369 @@ name = "test-pkg"
         assert!(!action.is_initial_tag);
     }
 
+    /// RULE 2h: Cargo.toml=1.3.0-rc.1 (managed), tag v1.3.0-rc.1, pre-release bump
+    /// → Bump to v1.3.0-rc.2
+    #[test]
+    fn rule_2h_cargo_managed_prerelease_bump() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.3.0-rc.1")); // ACTIVELY MANAGED PRE-RELEASE
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.3.0-rc.1"); // MATCHES
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions::new(BumpType::Pre(version::PreKind::Rc));
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+
+        // MUST bump to v1.3.0-rc.2
+        assert_eq!(
+            action.target_version,
+            version::parse_version("1.3.0-rc.2").unwrap(),
+            "MUST bump from v1.3.0-rc.1 to v1.3.0-rc.2"
+        );
+        assert!(action.needs_cargo_update);
+        assert!(!action.is_initial_tag);
+    }
+
+    // =========================================================================
+    // EXPLICIT TARGET (--set)
+    // =========================================================================
+
+    /// --set 1.0.0 with Cargo.toml=0.2.0, tag v0.2.0
+    /// → Jump straight to v1.0.0
+    #[test]
+    fn set_jumps_to_explicit_version() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.2.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.2.0");
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            set: Some(Version::new(1, 0, 0)),
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+
+        assert_eq!(action.target_version, Version::new(1, 0, 0));
+        assert!(action.needs_cargo_update);
+        assert!(!action.is_initial_tag);
+    }
+
+    /// --set must move forward from the latest tag, even when Cargo.toml is lower
+    #[test]
+    fn set_not_greater_than_current_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.1.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.5.0");
+
+        let cargo_path = dir.join("Cargo.toml");
+        for target in [Version::new(0, 5, 0), Version::new(0, 3, 0)] {
+            let options = VersionOptions {
+                set: Some(target),
+                ..VersionOptions::new(BumpType::Patch)
+            };
+            let result = determine_version_action(dir, &cargo_path, &options);
+            assert!(result.is_err(), "MUST refuse a target that isn't greater than v0.5.0");
+        }
+    }
+
+    // =========================================================================
+    // COMMIT MESSAGE
+    // =========================================================================
+
+    fn staged(files: &[&str]) -> Vec<String> {
+        files.iter().map(|f| f.to_string()).collect()
+    }
+
+    fn message_context(tag: &str) -> MessageContext<'_> {
+        MessageContext {
+            version: tag.trim_start_matches('v'),
+            tag,
+            previous: "none",
+            date: "2024-05-01",
+        }
+    }
+
+    #[test]
+    fn test_commit_message_uses_message_verbatim() {
+        let options = MessageOptions {
+            message: Some("Ship it".to_string()),
+            automatic: true,
+            ..MessageOptions::default()
+        };
+        let files = staged(&["src/main.rs"]);
+        assert_eq!(determine_commit_message(&options, &message_context("v1.2.4"), &files, false).unwrap(), "Ship it");
+    }
+
+    #[test]
+    fn test_commit_message_automatic_ignores_staged_files() {
+        let options = MessageOptions {
+            automatic: true,
+            ..MessageOptions::default()
+        };
+        let files = staged(&["src/main.rs", "Cargo.toml"]);
+        assert_eq!(
+            determine_commit_message(&options, &message_context("v1.2.4"), &files, false).unwrap(),
+            "Bump version to v1.2.4"
+        );
+    }
+
+    #[test]
+    fn test_commit_message_generated_for_version_only_changes() {
+        let options = MessageOptions::default();
+        assert_eq!(
+            determine_commit_message(&options, &message_context("v1.2.4"), &[], false).unwrap(),
+            "Release v1.2.4"
+        );
+
+        let files = staged(&["app/Cargo.toml", "Cargo.lock"]);
+        let options = MessageOptions {
+            owned_files: files.clone(),
+            ..options
+        };
+        assert_eq!(
+            determine_commit_message(&options, &message_context("v1.2.4"), &files, false).unwrap(),
+            "Bump version to v1.2.4"
+        );
+        assert_eq!(
+            determine_commit_message(&options, &message_context("v0.1.0"), &files, true).unwrap(),
+            "Release v0.1.0"
+        );
+    }
+
+    #[test]
+    fn test_commit_message_yes_skips_editor() {
+        let options = MessageOptions::from_cli(&Cli::try_parse_from(["bump", "--yes"]).unwrap());
+        let files = staged(&["src/main.rs", "Cargo.toml"]);
+
+        assert_eq!(
+            determine_commit_message(&options, &message_context("v1.2.4"), &files, false).unwrap(),
+            "Bump version to v1.2.4"
+        );
+        assert_eq!(
+            determine_commit_message(&options, &message_context("v0.1.0"), &files, true).unwrap(),
+            "Release v0.1.0"
+        );
+    }
+
+    #[test]
+    fn test_commit_message_without_terminal_asks_for_a_flag() {
+        let options = MessageOptions {
+            terminal: false,
+            ..MessageOptions::default()
+        };
+        let files = staged(&["src/main.rs", "Cargo.toml"]);
+
+        let err = determine_commit_message(&options, &message_context("v1.2.4"), &files, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not a terminal"), "{}", err);
+        assert!(err.contains("--message"), "{}", err);
+    }
+
+    #[test]
+    fn test_commit_message_template_fills_placeholders() {
+        let cli = Cli::try_parse_from(["bump", "--commit-template", "release {previous} -> {version} ({tag}, {date})"]);
+        let options = MessageOptions::from_cli(&cli.unwrap());
+        let context = MessageContext {
+            previous: "1.2.3",
+            ..message_context("v1.2.4")
+        };
+        assert_eq!(
+            determine_commit_message(&options, &context, &[], false).unwrap(),
+            "release 1.2.3 -> 1.2.4 (v1.2.4, 2024-05-01)"
+        );
+    }
+
+    #[cfg(not(feature = "libgit2"))]
+    #[test]
+    fn test_libgit2_backend_needs_feature() {
+        let err = git::set_backend(git::Backend::Libgit2).unwrap_err();
+        assert!(err.to_string().contains("--features libgit2"), "{}", err);
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(2, 0, false), EXIT_SUCCESS);
+        assert_eq!(exit_code(1, 1, false), EXIT_PARTIAL_FAILURE);
+        assert_eq!(exit_code(0, 2, false), EXIT_ALL_FAILED);
+        assert_eq!(exit_code(1, 0, true), EXIT_DRY_RUN_PENDING);
+        assert_eq!(exit_code(1, 1, true), EXIT_PARTIAL_FAILURE);
+    }
+
+    #[test]
+    fn test_completions_cover_flags() {
+        for shell in [clap_complete::Shell::Bash, clap_complete::Shell::Zsh, clap_complete::Shell::Fish] {
+            let mut script = Vec::new();
+            write_completions(shell, &mut script);
+            let script = String::from_utf8(script).unwrap();
+            for flag in ["dry-run", "tag-template", "completions", "calver"] {
+                assert!(script.contains(flag), "{:?} completions missing --{}", shell, flag);
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_summary() {
+        let files = vec!["Cargo.toml".to_string(), "CHANGELOG.md".to_string()];
+        let cli = Cli::try_parse_from(["bump"]).unwrap();
+        assert_eq!(plan_summary(&files, &cli, "v1.2.3"), "update Cargo.toml, CHANGELOG.md, then commit and tag v1.2.3");
+
+        let cli = Cli::try_parse_from(["bump", "--no-tag", "--push"]).unwrap();
+        assert_eq!(plan_summary(&[], &cli, "v1.2.3"), "commit v1.2.3 (no tag), then push to origin");
+
+        let cli = Cli::try_parse_from(["bump", "--no-commit"]).unwrap();
+        assert_eq!(plan_summary(&files[..1], &cli, "v1.2.3"), "update Cargo.toml, then stage the changes for v1.2.3");
+    }
+
+    #[test]
+    fn test_open_log_file() {
+        let tmp = TempDir::new().unwrap();
+        let log_file = tmp.path().join("nested/logs/release.log");
+        open_log_file(&log_file).unwrap();
+        assert!(log_file.is_file());
+
+        // A directory can't be created under a plain file
+        fs::write(tmp.path().join("blocker"), "").unwrap();
+        let err = open_log_file(&tmp.path().join("blocker/bump.log")).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to create log directory"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_json_log_line() {
+        log_directory(Path::new("/src/api"));
+        log_version("1.3.0");
+        let line = json_log_line(
+            &log::Record::builder()
+                .args(format_args!("Created tag: v1.3.0"))
+                .level(log::Level::Info)
+                .target("bump")
+                .build(),
+        );
+
+        let entry: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert!(!line.contains('\n'));
+        assert!(chrono::DateTime::parse_from_rfc3339(entry["timestamp"].as_str().unwrap()).is_ok());
+        assert_eq!(entry["level"], "INFO");
+        assert_eq!(entry["message"], "Created tag: v1.3.0");
+        assert_eq!(entry["directory"], "/src/api");
+        assert_eq!(entry["version"], "1.3.0");
+    }
+
+    #[test]
+    fn test_stamped() {
+        assert_eq!(
+            stamped("Release v1.2.3\n", "2024-05-01T10:00:00+02:00", "alice"),
+            "Release v1.2.3\n\nDate: 2024-05-01T10:00:00+02:00\nReleased-by: alice"
+        );
+    }
+
+    /// The combined plan shows versions, steps and files without touching the tree or the remote
+    #[test]
+    fn directory_plan_is_local_only() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("1.2.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v1.2.3");
+        fs::write(dir.join("Cargo.lock"), "version = 4\n").unwrap();
+        git_output(dir, &["add", "Cargo.lock"]);
+        git_output(dir, &["commit", "-m", "Add lockfile"]);
+        git_output(dir, &["remote", "add", "offline", &dir.join("missing").display().to_string()]);
+
+        let cli = Cli::try_parse_from([
+            "bump", "--minor", "--push-tags", "--remote", "offline", "--fetch-tags", "--check-remote", "--unshallow",
+        ])
+        .unwrap();
+        assert_eq!(
+            directory_plan(dir, &cli).unwrap(),
+            "1.2.3 -> 1.3.0: update Cargo.toml, Cargo.lock, then commit and tag v1.3.0, then push the tags to offline"
+        );
+        assert_eq!(cargo::read_version(&dir.join("Cargo.toml")).unwrap(), Some("1.2.3".to_string()));
+        assert!(!git::tag_exists(dir, "v1.3.0").unwrap());
+
+        let cli = Cli::try_parse_from(["bump", "--tag-only"]).unwrap();
+        assert_eq!(directory_plan(dir, &cli).unwrap(), "tag HEAD v1.2.3");
+    }
+
+    #[test]
+    fn test_parse_confirm() {
+        assert!(parse_confirm("y\n"));
+        assert!(parse_confirm("YES"));
+        assert!(!parse_confirm("\n"));
+        assert!(!parse_confirm(""));
+        assert!(!parse_confirm("nope"));
+    }
+
+    #[test]
+    fn test_parse_bump_choice() {
+        assert_eq!(parse_bump_choice("\n"), Some(BumpType::Patch));
+        assert_eq!(parse_bump_choice("1"), Some(BumpType::Major));
+        assert_eq!(parse_bump_choice("Minor\n"), Some(BumpType::Minor));
+        assert_eq!(parse_bump_choice("3"), Some(BumpType::Patch));
+        assert_eq!(parse_bump_choice("huge"), None);
+    }
+
+    // =========================================================================
+    // PROMOTE PRE-RELEASE (--release)
+    // =========================================================================
+
+    /// --release with Cargo.toml=2.0.0-rc.3, tag v2.0.0-rc.3
+    /// → Promote to v2.0.0 without incrementing
+    #[test]
+    fn release_promotes_prerelease() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("2.0.0-rc.3"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v2.0.0-rc.3");
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            release: true,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+
+        assert_eq!(action.target_version, Version::new(2, 0, 0), "MUST drop the -rc.3 suffix");
+        assert!(action.needs_cargo_update);
+        assert!(!action.is_initial_tag);
+    }
+
+    /// --release on a stable version has nothing to promote
+    #[test]
+    fn release_without_prerelease_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("2.0.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v2.0.0");
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            release: true,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let result = determine_version_action(dir, &cargo_path, &options);
+        assert!(result.is_err(), "MUST refuse to promote a stable version");
+    }
+
     // =========================================================================
     // RULE 3: Cargo.toml has NO version field
     // =========================================================================
@@ -856,7 +5156,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.5");
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST bump from tag v0.1.5 to v0.1.6
         assert_eq!(
@@ -882,7 +5182,7 @@ name = "test-pkg"
         // NO TAGS
 
         let cargo_path = dir.join("Cargo.toml");
-        let action = determine_version_action(dir, &cargo_path, BumpType::Patch).unwrap();
+        let action = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).unwrap();
 
         // MUST start at v0.1.0
         assert_eq!(action.target_version, Version::new(0, 1, 0), "MUST start at v0.1.0");
@@ -892,6 +5192,87 @@ name = "test-pkg"
         assert!(action.is_initial_tag, "MUST be initial tag");
     }
 
+    /// RULE 3c: NO version in Cargo.toml, NO tags, --initial given
+    /// → Start at the --initial version instead of v0.1.0
+    #[test]
+    fn rule_3c_no_version_anywhere_starts_at_initial() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, None); // NO VERSION FIELD
+        create_initial_commit(dir);
+        // NO TAGS
+
+        let cli = Cli::try_parse_from(["bump", "--initial", "1.0.0"]).unwrap();
+        let options = VersionOptions {
+            initial: initial_version(&cli, false).unwrap(),
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let action = determine_version_action(dir, &dir.join("Cargo.toml"), &options).unwrap();
+
+        // MUST start at the requested version, as an initial tag
+        assert_eq!(action.target_version, Version::new(1, 0, 0), "MUST start at v1.0.0");
+        assert!(action.is_initial_tag, "MUST be initial tag");
+
+        let cli = Cli::try_parse_from(["bump", "--initial", "one"]).unwrap();
+        let err = initial_version(&cli, false).unwrap_err().to_string();
+        assert!(err.contains("--initial"), "{}", err);
+    }
+
+    // =========================================================================
+    // DOWNGRADES: the new version must sort above the latest tag
+    // =========================================================================
+
+    /// DOWNGRADE: CalVer tag v2099.1.0 is in the future
+    /// → **ERROR**: this month's version would sort below the tag
+    #[test]
+    fn downgrade_below_latest_tag_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("2099.1.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v2099.1.0");
+
+        let options = VersionOptions {
+            scheme: VersionScheme::CalVer,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let err = determine_version_action(dir, &dir.join("Cargo.toml"), &options).unwrap_err().to_string();
+        assert!(err.contains("not greater than the latest tag v2099.1.0"), "{}", err);
+
+        let options = VersionOptions {
+            allow_downgrade: true,
+            ..options
+        };
+        assert!(determine_version_action(dir, &dir.join("Cargo.toml"), &options).is_ok());
+    }
+
+    /// DOWNGRADE: Cargo.toml=0.3.0 set below tag v0.5.0, with --allow-downgrade
+    /// → Bump from Cargo.toml instead of failing on the mismatch
+    #[test]
+    fn downgrade_allowed_bumps_from_cargo_toml() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.3.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.5.0");
+
+        let cargo_path = dir.join("Cargo.toml");
+        assert!(determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch)).is_err());
+
+        let options = VersionOptions {
+            allow_downgrade: true,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+        assert_eq!(action.target_version, Version::new(0, 3, 1));
+    }
+
     // =========================================================================
     // EDGE CASES: Cargo.toml higher than tag but tag doesn't match
     // =========================================================================
@@ -909,7 +5290,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.1.28"); // LOWER, DOES NOT MATCH
 
         let cargo_path = dir.join("Cargo.toml");
-        let result = determine_version_action(dir, &cargo_path, BumpType::Patch);
+        let result = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch));
 
         // MUST ERROR - this is a mismatch situation
         assert!(
@@ -931,7 +5312,7 @@ name = "test-pkg"
         create_git_tag(dir, "v0.9.0"); // DOES NOT MATCH
 
         let cargo_path = dir.join("Cargo.toml");
-        let result = determine_version_action(dir, &cargo_path, BumpType::Patch);
+        let result = determine_version_action(dir, &cargo_path, &VersionOptions::new(BumpType::Patch));
 
         // MUST ERROR
         assert!(
@@ -939,4 +5320,45 @@ name = "test-pkg"
             "MUST ERROR: Cargo.toml=1.0.0 does not match latest tag v0.9.0"
         );
     }
+
+    /// EDGE CASE: Cargo.toml=0.3.0, tag v0.1.28 exists, --from-tag
+    /// → Bump from the tag to 0.1.29 and overwrite Cargo.toml
+    #[test]
+    fn edge_from_tag_bumps_from_tag_over_cargo() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.3.0"));
+        create_initial_commit(dir);
+        create_git_tag(dir, "v0.1.28");
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            from_tag: true,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let action = determine_version_action(dir, &cargo_path, &options).unwrap();
+        assert_eq!(action.target_version, Version::new(0, 1, 29));
+        assert!(action.needs_cargo_update);
+    }
+
+    /// EDGE CASE: --from-tag with no tags → ERROR
+    #[test]
+    fn edge_from_tag_without_tag_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        setup_git_repo(dir);
+        create_cargo_toml(dir, Some("0.3.0"));
+        create_initial_commit(dir);
+
+        let cargo_path = dir.join("Cargo.toml");
+        let options = VersionOptions {
+            from_tag: true,
+            ..VersionOptions::new(BumpType::Patch)
+        };
+        let err = determine_version_action(dir, &cargo_path, &options).unwrap_err();
+        assert!(err.to_string().contains("--from-tag"), "{}", err);
+    }
 }
@@ -0,0 +1,86 @@
+use eyre::{Context, Result, bail};
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The npm manifest kept in step with Cargo.toml by --sync-npm
+const PACKAGE_JSON: &str = "package.json";
+
+/// Get the path to package.json in the given directory
+pub fn package_json_path(dir: &Path) -> PathBuf {
+    dir.join(PACKAGE_JSON)
+}
+
+/// The package.json contents with `"version"` set, keeping the key order, indentation and final newline
+pub fn set_version(content: &str, new_version: &str) -> Result<String> {
+    let mut package: Value = serde_json::from_str(content).context("Failed to parse package.json")?;
+    let Some(fields) = package.as_object_mut() else {
+        bail!("package.json is not a JSON object");
+    };
+    fields.insert("version".to_string(), Value::String(new_version.to_string()));
+
+    let mut updated = Vec::new();
+    let mut serializer = Serializer::with_formatter(&mut updated, PrettyFormatter::with_indent(indent(content)));
+    package
+        .serialize(&mut serializer)
+        .context("Failed to serialize package.json")?;
+
+    let mut updated = String::from_utf8(updated).context("package.json is not valid UTF-8")?;
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// The indentation of the first nested line (npm writes two spaces)
+fn indent(content: &str) -> &[u8] {
+    let indent = content
+        .lines()
+        .nth(1)
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .unwrap_or_default();
+    if indent.is_empty() { b"  " } else { indent.as_bytes() }
+}
+
+/// Set the version in package.json; returns false when there is no package.json to update
+pub fn write_version(dir: &Path, new_version: &str) -> Result<bool> {
+    let path = package_json_path(dir);
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let updated = set_version(&content, new_version).context(format!("Failed to update {}", path.display()))?;
+    fs::write(&path, updated).context(format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_version_keeps_key_order_and_indent() {
+        let content = "{\n    \"name\": \"app\",\n    \"version\": \"1.2.3\",\n    \"private\": true,\n    \
+                       \"scripts\": {\n        \"build\": \"tsc\"\n    }\n}\n";
+        let updated = set_version(content, "1.3.0").unwrap();
+        assert_eq!(updated, content.replace("1.2.3", "1.3.0"));
+    }
+
+    #[test]
+    fn test_set_version_adds_missing_version() {
+        let updated = set_version("{\n  \"name\": \"app\"\n}", "0.1.0").unwrap();
+        assert_eq!(updated, "{\n  \"name\": \"app\",\n  \"version\": \"0.1.0\"\n}");
+        assert!(set_version("[1, 2]", "0.1.0").is_err());
+    }
+
+    #[test]
+    fn test_write_version_missing_package_json() {
+        let dir = TempDir::new().unwrap();
+        assert!(!write_version(dir.path(), "1.0.0").unwrap());
+        assert!(!package_json_path(dir.path()).exists());
+    }
+}
@@ -0,0 +1,191 @@
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use similar::TextDiff;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Output collected from one directory while running in parallel (--jobs)
+#[derive(Debug, Default)]
+pub struct Captured {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+thread_local! {
+    /// Set while `capture` runs, so each worker thread buffers its own output
+    static CAPTURE: RefCell<Option<Captured>> = const { RefCell::new(None) };
+}
+
+/// Set once at startup from --quiet; drops status lines (results on stdout still print)
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Set once at startup from --json; the JSON summary then replaces the results on stdout
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Hold back result lines for the rest of the run, leaving stdout to the JSON summary (--json)
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+/// Silence status lines for the rest of the run (--quiet)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// When to color status lines (--color)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Set once at startup from --color, NO_COLOR and whether stderr is a terminal
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Whether to color: always/never are explicit; auto colors a terminal unless NO_COLOR is set
+pub fn use_color(choice: ColorChoice, no_color: bool, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal && !no_color,
+    }
+}
+
+/// Turn coloring of status lines on or off for the rest of the run
+pub fn set_color(enabled: bool) {
+    COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// Green, for a completed release step
+pub fn success_text(text: &str) -> String {
+    if COLOR.load(Ordering::Relaxed) { text.green().to_string() } else { text.to_string() }
+}
+
+/// The yellow `[dry-run]` marker
+pub fn dry_run_label() -> String {
+    if COLOR.load(Ordering::Relaxed) { "[dry-run]".yellow().to_string() } else { "[dry-run]".to_string() }
+}
+
+/// The red `Error:` marker
+pub fn error_label() -> String {
+    if COLOR.load(Ordering::Relaxed) { "Error:".red().to_string() } else { "Error:".to_string() }
+}
+
+/// A unified diff of a file's old and new contents, with added lines green and removed lines red
+pub fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    let diff = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{}", name), &format!("b/{}", name))
+        .to_string();
+    if !COLOR.load(Ordering::Relaxed) {
+        return diff;
+    }
+
+    diff.lines()
+        .map(|line| match line.chars().next() {
+            Some('+') if !line.starts_with("+++") => line.green().to_string(),
+            Some('-') if !line.starts_with("---") => line.red().to_string(),
+            Some('@') => line.cyan().to_string(),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Run `f`, collecting everything it prints through `status!`/`output!` instead of writing it
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Captured) {
+    CAPTURE.with(|c| *c.borrow_mut() = Some(Captured::default()));
+    let value = f();
+    let captured = CAPTURE.with(|c| c.borrow_mut().take()).unwrap_or_default();
+    (value, captured)
+}
+
+/// Write a status line to stderr, or to the capture buffer (nothing under --quiet)
+pub fn write_stderr(args: fmt::Arguments) {
+    if QUIET.load(Ordering::Relaxed) {
+        return;
+    }
+    CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+        Some(captured) => captured.stderr.push_str(&format!("{}\n", args)),
+        None => eprintln!("{}", args),
+    });
+}
+
+/// Write a result line to stdout, or to the capture buffer
+pub fn write_stdout(args: fmt::Arguments) {
+    CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+        Some(captured) => captured.stdout.push_str(&format!("{}\n", args)),
+        None if JSON.load(Ordering::Relaxed) => {}
+        None => println!("{}", args),
+    });
+}
+
+/// Print a status line (stderr)
+macro_rules! status {
+    ($($arg:tt)*) => {
+        $crate::output::write_stderr(format_args!($($arg)*))
+    };
+}
+
+/// Print a status line for a completed step, in green when coloring (stderr)
+macro_rules! success {
+    ($($arg:tt)*) => {
+        $crate::output::write_stderr(format_args!("{}", $crate::output::success_text(&format!($($arg)*))))
+    };
+}
+
+/// Print a status line describing what a dry run would do (stderr)
+macro_rules! dry_run {
+    ($($arg:tt)*) => {
+        $crate::output::write_stderr(format_args!("{} {}", $crate::output::dry_run_label(), format_args!($($arg)*)))
+    };
+}
+
+/// Print a result line such as the new tag (stdout)
+macro_rules! output {
+    ($($arg:tt)*) => {
+        $crate::output::write_stdout(format_args!($($arg)*))
+    };
+}
+
+pub(crate) use {dry_run, output, status, success};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_collects_output() {
+        let (value, captured) = capture(|| {
+            status!("bump: {} → {}", "1.2.3", "1.2.4");
+            output!("v1.2.4");
+            42
+        });
+
+        assert_eq!(value, 42);
+        assert_eq!(captured.stderr, "bump: 1.2.3 → 1.2.4\n");
+        assert_eq!(captured.stdout, "v1.2.4\n");
+    }
+
+    #[test]
+    fn test_use_color() {
+        assert!(use_color(ColorChoice::Auto, false, true));
+        assert!(!use_color(ColorChoice::Auto, true, true));
+        assert!(!use_color(ColorChoice::Auto, false, false));
+        assert!(use_color(ColorChoice::Always, true, false));
+        assert!(!use_color(ColorChoice::Never, false, true));
+    }
+
+    #[test]
+    fn test_unified_diff_shows_changed_line() {
+        let old = "[package]\nname = \"app\"\nversion = \"1.2.3\"\n";
+        let new = "[package]\nname = \"app\"\nversion = \"1.2.4\"\n";
+        let diff = unified_diff(old, new, "Cargo.toml");
+        assert!(diff.contains("--- a/Cargo.toml\n+++ b/Cargo.toml"), "{}", diff);
+        assert!(diff.contains("-version = \"1.2.3\"\n+version = \"1.2.4\""), "{}", diff);
+        assert!(unified_diff(old, old, "Cargo.toml").is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+use eyre::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Value};
+
+use crate::cargo;
+
+/// The Python project manifest kept in step with Cargo.toml by --sync-pyproject
+const PYPROJECT_TOML: &str = "pyproject.toml";
+
+/// Where pyproject.toml keeps the version: PEP 621 and Poetry layouts
+const VERSION_KEYS: &[(&str, &[&str])] = &[
+    ("[project].version", &["project"]),
+    ("[tool.poetry].version", &["tool", "poetry"]),
+];
+
+/// Get the path to pyproject.toml in the given directory
+pub fn pyproject_path(dir: &Path) -> PathBuf {
+    dir.join(PYPROJECT_TOML)
+}
+
+/// The version under whichever layout the document uses
+/// Errors if there is no version, or if both layouts have one and they disagree
+fn current_version(doc: &DocumentMut) -> Result<String> {
+    let mut found: Vec<(&str, &str)> = Vec::new();
+    for (name, path) in VERSION_KEYS {
+        let table = path.iter().try_fold(doc.as_item(), |item, part| item.get(part));
+        if let Some(version) = table.and_then(|t| t.get("version")).and_then(|v| v.as_str()) {
+            found.push((name, version));
+        }
+    }
+
+    match found.as_slice() {
+        [] => bail!("pyproject.toml has no [project].version or [tool.poetry].version to update"),
+        [(first, a), (second, b)] if a != b => {
+            bail!("pyproject.toml has {} = {} but {} = {}; make them agree first", first, a, second, b)
+        }
+        [(_, version), ..] => Ok(version.to_string()),
+    }
+}
+
+/// Read the version from pyproject.toml (checking it can be updated); None if there is no pyproject.toml
+pub fn read_version(dir: &Path) -> Result<Option<String>> {
+    let path = pyproject_path(dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let doc = content.parse::<DocumentMut>().context(format!("Failed to parse {}", path.display()))?;
+    Ok(Some(current_version(&doc)?))
+}
+
+/// The pyproject.toml contents with the version set under whichever layout it uses
+pub fn set_version(content: &str, new_version: &str) -> Result<String> {
+    let mut doc = content.parse::<DocumentMut>().context("Failed to parse pyproject.toml")?;
+    current_version(&doc)?;
+
+    for (_, path) in VERSION_KEYS {
+        // Item::get_mut would insert missing tables, so walk them as table-likes
+        let table = path
+            .iter()
+            .try_fold(doc.as_item_mut(), |item, part| item.as_table_like_mut()?.get_mut(part));
+        // Keep any comment or spacing around the old value
+        let version = table.and_then(|t| t.as_table_like_mut()?.get_mut("version")?.as_value_mut());
+        if let Some(Value::String(version)) = version {
+            let decor = version.decor().clone();
+            *version = toml_edit::Formatted::new(new_version.to_string());
+            *version.decor_mut() = decor;
+        }
+    }
+
+    Ok(cargo::with_original_line_endings(content, &doc.to_string()))
+}
+
+/// Set the version in pyproject.toml; returns false when there is no pyproject.toml to update
+pub fn write_version(dir: &Path, new_version: &str) -> Result<bool> {
+    let path = pyproject_path(dir);
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let updated = set_version(&content, new_version).context(format!("Failed to update {}", path.display()))?;
+    fs::write(&path, updated).context(format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_version_pep621_and_poetry() {
+        let pep621 = "[project]\nname = \"app\"\nversion = \"1.2.3\" # keep in sync\n";
+        assert_eq!(set_version(pep621, "1.3.0").unwrap(), pep621.replace("1.2.3", "1.3.0"));
+
+        let poetry = "[tool.poetry]\nname = \"app\"\nversion = \"1.2.3\"\n";
+        assert_eq!(set_version(poetry, "1.3.0").unwrap(), poetry.replace("1.2.3", "1.3.0"));
+
+        let both = format!("{}\n{}", pep621, poetry);
+        assert_eq!(set_version(&both, "1.3.0").unwrap(), both.replace("1.2.3", "1.3.0"));
+    }
+
+    #[test]
+    fn test_set_version_errors() {
+        let disagree = "[project]\nversion = \"1.2.3\"\n\n[tool.poetry]\nversion = \"1.0.0\"\n";
+        let err = set_version(disagree, "1.3.0").unwrap_err();
+        assert!(err.to_string().contains("make them agree"), "{}", err);
+
+        let dynamic = "[project]\nname = \"app\"\ndynamic = [\"version\"]\n";
+        assert!(set_version(dynamic, "1.3.0").is_err());
+    }
+
+    #[test]
+    fn test_write_version_missing_pyproject() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(read_version(dir.path()).unwrap(), None);
+        assert!(!write_version(dir.path(), "1.0.0").unwrap());
+        assert!(!pyproject_path(dir.path()).exists());
+    }
+}
@@ -0,0 +1,113 @@
+use eyre::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The README updated by --update-readme
+const README_FILE: &str = "README.md";
+
+/// Where the version shows up by default: this package's dependency snippets and static shields.io badges
+pub const DEFAULT_TEMPLATES: &[&str] = &[
+    "{name} = \"{version}\"",
+    "{name} = { version = \"{version}\"",
+    "version-{version}-",
+];
+
+/// Get the path to README.md in the given directory
+pub fn readme_path(dir: &Path) -> PathBuf {
+    dir.join(README_FILE)
+}
+
+/// Check every template marks where the version goes, before anything is written
+pub fn check_templates(templates: &[String]) -> Result<()> {
+    if let Some(template) = templates.iter().find(|t| !t.contains("{version}")) {
+        bail!("README template '{}' has no {{version}} placeholder", template);
+    }
+    Ok(())
+}
+
+/// Replace the previous version with the new one, only where it appears in the context of a template
+/// `{version}` marks the version and `{name}` the package name (templates using it are skipped without one)
+/// Returns the new content and how many occurrences changed
+pub fn replace_versions(
+    content: &str,
+    templates: &[String],
+    name: Option<&str>,
+    previous: &str,
+    new: &str,
+) -> (String, usize) {
+    let mut updated = content.to_string();
+    let mut replaced = 0;
+    for template in templates {
+        let template = match name {
+            Some(name) => template.replace("{name}", name),
+            None if template.contains("{name}") => continue,
+            None => template.clone(),
+        };
+
+        let old_text = template.replace("{version}", previous);
+        let new_text = template.replace("{version}", new);
+        replaced += updated.matches(&old_text).count();
+        updated = updated.replace(&old_text, &new_text);
+    }
+    (updated, replaced)
+}
+
+/// Rewrite the version references in README.md; a missing README counts as nothing to update
+pub fn update(dir: &Path, templates: &[String], name: Option<&str>, previous: &str, new: &str) -> Result<usize> {
+    let path = readme_path(dir);
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let (updated, replaced) = replace_versions(&content, templates, name, previous, new);
+    if replaced > 0 {
+        fs::write(&path, updated).context(format!("Failed to write {}", path.display()))?;
+    }
+    Ok(replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn defaults() -> Vec<String> {
+        DEFAULT_TEMPLATES.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_replace_versions_only_touches_template_matches() {
+        let content = "![version](https://img.shields.io/badge/version-1.2.3-blue)\n\n\
+                       ```toml\n[dependencies]\nmycrate = \"1.2.3\"\nserde = \"1.2.3\"\n\
+                       mycrate = { version = \"1.2.3\", features = [\"cli\"] }\n\
+                       tokio = { version = \"1.2.3\" }\n```\n\n\
+                       Changed in 1.2.3: nothing.\n";
+        let (updated, replaced) = replace_versions(content, &defaults(), Some("mycrate"), "1.2.3", "1.3.0");
+
+        assert_eq!(replaced, 3);
+        assert!(updated.contains("badge/version-1.3.0-blue"));
+        assert!(updated.contains("mycrate = \"1.3.0\""));
+        assert!(updated.contains("mycrate = { version = \"1.3.0\", features"));
+        assert!(updated.contains("serde = \"1.2.3\""), "MUST leave other crates alone");
+        assert!(updated.contains("tokio = { version = \"1.2.3\" }"), "MUST leave other crates' tables alone");
+        assert!(updated.contains("Changed in 1.2.3"), "MUST leave prose alone");
+    }
+
+    #[test]
+    fn test_replace_versions_custom_template() {
+        let templates = vec!["cargo install mycrate@{version}".to_string()];
+        let (updated, replaced) = replace_versions("cargo install mycrate@0.4.0\n", &templates, None, "0.4.0", "0.4.1");
+        assert_eq!(replaced, 1);
+        assert_eq!(updated, "cargo install mycrate@0.4.1\n");
+        assert!(check_templates(&templates).is_ok());
+        assert!(check_templates(&["no placeholder".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_update_missing_readme() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(update(dir.path(), &defaults(), None, "1.0.0", "1.0.1").unwrap(), 0);
+        assert!(!readme_path(dir.path()).exists());
+    }
+}
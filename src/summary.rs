@@ -0,0 +1,132 @@
+use eyre::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// What a run did in one directory
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Outcome {
+    pub previous: Option<String>,
+    pub version: Option<String>,
+    pub committed: bool,
+    pub tagged: bool,
+    /// A dry run found something to release (drives the dry-run exit code)
+    #[serde(skip)]
+    pub pending: bool,
+}
+
+/// One directory's entry in the end-of-run summary (--json prints these as an array)
+#[derive(Debug, Serialize)]
+pub struct Row {
+    pub directory: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    pub error: Option<String>,
+}
+
+impl Row {
+    pub fn new(directory: &Path, result: Result<Outcome>) -> Self {
+        let (outcome, error) = match result {
+            Ok(outcome) => (outcome, None),
+            Err(e) => (Outcome::default(), Some(format!("{:#}", e))),
+        };
+        Row {
+            directory: directory_label(directory),
+            outcome,
+            error,
+        }
+    }
+
+    /// "1.2.3 → 1.3.0", just the new version for an initial tag, or "-" when nothing was computed
+    fn version_change(&self) -> String {
+        match (&self.outcome.previous, &self.outcome.version) {
+            (Some(previous), Some(version)) => format!("{} → {}", previous, version),
+            (None, Some(version)) => version.clone(),
+            _ => "-".to_string(),
+        }
+    }
+
+    /// What happened: committed and/or tagged, or why it failed
+    fn result(&self) -> String {
+        if let Some(ref error) = self.error {
+            return format!("failed: {}", error);
+        }
+        match (self.outcome.committed, self.outcome.tagged) {
+            (true, true) => "committed and tagged",
+            (true, false) => "committed",
+            (false, true) => "tagged",
+            (false, false) => "not committed",
+        }
+        .to_string()
+    }
+}
+
+/// The name a directory is shown under: its last component, or the whole path
+pub fn directory_label(dir: &Path) -> String {
+    dir.file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.display().to_string())
+}
+
+/// Render the rows as aligned lines: directory, old → new version, and what happened
+pub fn table(rows: &[Row]) -> Vec<String> {
+    let cells: Vec<[String; 3]> = rows
+        .iter()
+        .map(|row| [row.directory.clone(), row.version_change(), row.result()])
+        .collect();
+    let width = |column: usize| cells.iter().map(|c| c[column].chars().count()).max().unwrap_or(0);
+    let (directory_width, version_width) = (width(0), width(1));
+
+    cells
+        .iter()
+        .map(|[directory, version, result]| {
+            format!("{:<directory_width$}  {:<version_width$}  {}", directory, version, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::eyre;
+
+    fn rows() -> Vec<Row> {
+        let released = Outcome {
+            previous: Some("1.2.3".to_string()),
+            version: Some("1.3.0".to_string()),
+            committed: true,
+            tagged: true,
+            ..Outcome::default()
+        };
+        let planned = Outcome {
+            version: Some("0.1.0".to_string()),
+            ..Outcome::default()
+        };
+        vec![
+            Row::new(Path::new("/src/api"), Ok(released)),
+            Row::new(Path::new("/src/frontend"), Ok(planned)),
+            Row::new(Path::new("/src/cli"), Err(eyre!("Tag v2.0.0 already exists"))),
+        ]
+    }
+
+    #[test]
+    fn test_table_aligns_columns() {
+        assert_eq!(
+            table(&rows()),
+            vec![
+                "api       1.2.3 → 1.3.0  committed and tagged",
+                "frontend  0.1.0          not committed",
+                "cli       -              failed: Tag v2.0.0 already exists",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rows_serialize_flat() {
+        let json = serde_json::to_value(rows()).unwrap();
+        assert_eq!(json[0]["directory"], "api");
+        assert_eq!(json[0]["previous"], "1.2.3");
+        assert_eq!(json[0]["tagged"], true);
+        assert_eq!(json[0]["error"], serde_json::Value::Null);
+        assert_eq!(json[2]["error"], "Tag v2.0.0 already exists");
+    }
+}
@@ -0,0 +1,61 @@
+/// Values substituted into commit message templates
+#[derive(Debug)]
+pub struct MessageContext<'a> {
+    /// The new version, without prefix (`{version}`)
+    pub version: &'a str,
+    /// The new tag, with prefix (`{tag}`)
+    pub tag: &'a str,
+    /// The version being replaced, or `none` for an initial tag (`{previous}`)
+    pub previous: &'a str,
+    /// Today's date as YYYY-MM-DD (`{date}`)
+    pub date: &'a str,
+}
+
+/// Substitute `{version}`, `{tag}`, `{previous}` and `{date}` in a template
+/// Unknown placeholders are left as they are
+pub fn format_message(template: &str, context: &MessageContext) -> String {
+    template
+        .replace("{version}", context.version)
+        .replace("{tag}", context.tag)
+        .replace("{previous}", context.previous)
+        .replace("{date}", context.date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> MessageContext<'static> {
+        MessageContext {
+            version: "1.2.4",
+            tag: "v1.2.4",
+            previous: "1.2.3",
+            date: "2024-05-01",
+        }
+    }
+
+    #[test]
+    fn test_format_message_version() {
+        assert_eq!(format_message("Bump to {version}", &context()), "Bump to 1.2.4");
+    }
+
+    #[test]
+    fn test_format_message_tag() {
+        assert_eq!(format_message("chore: release {tag}", &context()), "chore: release v1.2.4");
+    }
+
+    #[test]
+    fn test_format_message_previous() {
+        assert_eq!(format_message("{previous} → {version}", &context()), "1.2.3 → 1.2.4");
+    }
+
+    #[test]
+    fn test_format_message_date() {
+        assert_eq!(format_message("Release {tag} ({date})", &context()), "Release v1.2.4 (2024-05-01)");
+    }
+
+    #[test]
+    fn test_format_message_leaves_unknown_placeholders() {
+        assert_eq!(format_message("{tag} {branch}", &context()), "v1.2.4 {branch}");
+    }
+}
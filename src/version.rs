@@ -1,5 +1,35 @@
-use eyre::{Result, bail};
-use semver::Version;
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use eyre::{Result, bail, eyre};
+use semver::{BuildMetadata, Prerelease, Version};
+
+/// Pre-release channels, in ascending order (alpha < beta < rc)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum PreKind {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl PreKind {
+    /// The identifier used in the version suffix (e.g. `rc` in `1.3.0-rc.1`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PreKind::Alpha => "alpha",
+            PreKind::Beta => "beta",
+            PreKind::Rc => "rc",
+        }
+    }
+
+    fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "alpha" => Some(PreKind::Alpha),
+            "beta" => Some(PreKind::Beta),
+            "rc" => Some(PreKind::Rc),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum BumpType {
@@ -7,64 +37,294 @@ pub enum BumpType {
     Minor,
     #[default]
     Patch,
+    Pre(PreKind),
+    /// Bump only the fourth segment of a `MAJOR.MINOR.PATCH.REVISION` version
+    Revision,
 }
 
 impl BumpType {
-    pub fn from_cli(major: bool, minor: bool) -> Self {
-        match (major, minor) {
-            (true, _) => BumpType::Major,
-            (_, true) => BumpType::Minor,
+    pub fn from_cli(major: bool, minor: bool, patch: bool, pre: Option<PreKind>, revision: bool) -> Self {
+        match (major, minor, patch, pre, revision) {
+            (true, _, _, _, _) => BumpType::Major,
+            (_, true, _, _, _) => BumpType::Minor,
+            (_, _, true, _, _) => BumpType::Patch,
+            (_, _, _, Some(kind), _) => BumpType::Pre(kind),
+            (_, _, _, _, true) => BumpType::Revision,
             _ => BumpType::Patch,
         }
     }
 }
 
+/// How versions advance: by bump type (SemVer) or by the calendar (CalVer, `YYYY.M.N`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionScheme {
+    #[default]
+    SemVer,
+    CalVer,
+}
+
+/// The tag prefix used when none is configured
+pub const DEFAULT_TAG_PREFIX: &str = "v";
+
+/// Build metadata identifier that carries the fourth `.REVISION` segment (`1.2.3.4` is kept as `1.2.3+rev.4`)
+const REVISION_BUILD: &str = "rev.";
+
+/// Infer the bump type from a Conventional Commits style subject line
+/// `type!:` or `BREAKING CHANGE` means major, `feat` means minor, anything else patch
+pub fn infer_bump_type_from_subject(subject: &str) -> BumpType {
+    if subject.contains("BREAKING CHANGE") || subject.contains("BREAKING-CHANGE") {
+        return BumpType::Major;
+    }
+
+    // Header is `type(scope)!: description`; no colon means no recognizable marker
+    let Some((header, _)) = subject.trim().split_once(':') else {
+        return BumpType::Patch;
+    };
+
+    if header.ends_with('!') {
+        return BumpType::Major;
+    }
+
+    let kind = header.split('(').next().unwrap_or(header).trim().to_ascii_lowercase();
+    match kind.as_str() {
+        "feat" => BumpType::Minor,
+        _ => BumpType::Patch,
+    }
+}
+
+/// Infer the bump type from a full commit message: its subject line, plus any
+/// `BREAKING CHANGE:` (or `BREAKING-CHANGE:`) footer, which means major on its own
+pub fn infer_bump_type_from_message(message: &str) -> BumpType {
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or_default();
+    if lines.any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")) {
+        return BumpType::Major;
+    }
+    infer_bump_type_from_subject(subject)
+}
+
+/// Infer the bump type from the commit messages since the last release
+/// Precedence is major > minor > patch: the strongest change wins; no conventional commits means patch
+pub fn infer_bump_type(messages: &[String]) -> BumpType {
+    let inferred: Vec<BumpType> = messages.iter().map(|m| infer_bump_type_from_message(m)).collect();
+    if inferred.contains(&BumpType::Major) {
+        BumpType::Major
+    } else if inferred.contains(&BumpType::Minor) {
+        BumpType::Minor
+    } else {
+        BumpType::Patch
+    }
+}
+
 /// Parse a version string into a semver Version
 pub fn parse_version(version_str: &str) -> Result<Version> {
-    let version_str = version_str.strip_prefix('v').unwrap_or(version_str);
-    let version = Version::parse(version_str)?;
+    parse_semver(version_str.strip_prefix('v').unwrap_or(version_str), false)
+}
 
-    // Error if pre-release or build metadata present
-    if !version.pre.is_empty() {
-        bail!("Pre-release versions are not supported: {}", version_str);
+/// Parse a version string that may carry a fourth `.REVISION` segment (`1.2.3.4`)
+/// Only for versions kept outside package.version, which Cargo limits to three segments
+pub fn parse_revision_version(version_str: &str) -> Result<Version> {
+    parse_semver(version_str.strip_prefix('v').unwrap_or(version_str), true)
+}
+
+/// Parse a bare semver string, including any `+build` metadata (e.g. `1.2.3+ci.42`)
+/// With `revisions`, a fourth `.REVISION` segment (`1.2.3.4`) is accepted and kept as the revision
+fn parse_semver(version_str: &str, revisions: bool) -> Result<Version> {
+    if revisions && let Some(version) = parse_four_segment(version_str) {
+        return Ok(version);
     }
-    if !version.build.is_empty() {
-        bail!("Build metadata versions are not supported: {}", version_str);
+    Ok(Version::parse(version_str)?)
+}
+
+/// Parse --build metadata: dot-separated identifiers of ASCII letters, digits and hyphens
+pub fn parse_build(metadata: &str) -> Result<BuildMetadata> {
+    if metadata.is_empty() {
+        bail!("Build metadata can't be empty");
     }
+    if metadata.starts_with(REVISION_BUILD) {
+        bail!("Build metadata '{}' is reserved for revisions; use --revision instead", metadata);
+    }
+    BuildMetadata::new(metadata).map_err(|e| {
+        eyre!(
+            "Invalid build metadata '{}': {} (use dot-separated [0-9A-Za-z-] identifiers, e.g. ci.42)",
+            metadata,
+            e
+        )
+    })
+}
 
-    Ok(version)
+/// Parse `MAJOR.MINOR.PATCH.REVISION` (with an optional pre-release suffix)
+/// The revision is stored in the build metadata under the reserved `rev.` identifier
+fn parse_four_segment(version_str: &str) -> Option<Version> {
+    // Build metadata can't be combined with a revision (`1.2.3+ci.42` is a three-segment version)
+    if version_str.contains('+') {
+        return None;
+    }
+    let (core, pre) = match version_str.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version_str, None),
+    };
+    let (semver_core, revision) = core.rsplit_once('.')?;
+    if semver_core.matches('.').count() != 2 || revision.parse::<u64>().ok()?.to_string() != revision {
+        return None;
+    }
+
+    let mut version = Version::parse(semver_core).ok()?;
+    if let Some(pre) = pre {
+        version.pre = Prerelease::new(pre).ok()?;
+    }
+    version.build = revision_build(revision.parse().ok()?);
+    Some(version)
+}
+
+/// Build metadata holding a revision
+fn revision_build(revision: u64) -> BuildMetadata {
+    BuildMetadata::new(&format!("{}{}", REVISION_BUILD, revision)).expect("revision is a valid identifier")
+}
+
+/// The fourth segment of a `MAJOR.MINOR.PATCH.REVISION` version, if it has one
+pub fn revision(version: &Version) -> Option<u64> {
+    version.build.as_str().strip_prefix(REVISION_BUILD)?.parse().ok()
+}
+
+/// Parse a git tag into a semver Version by stripping the tag prefix (which may be empty)
+/// Four-segment tags only count with `revisions` (see parse_revision_version)
+pub fn parse_tag(tag: &str, prefix: &str, revisions: bool) -> Result<Version> {
+    let Some(version_str) = tag.strip_prefix(prefix) else {
+        bail!("Tag {} does not start with prefix '{}'", tag, prefix);
+    };
+    parse_semver(version_str, revisions)
+}
+
+/// Split a pre-release like `rc.2` into its channel and counter
+fn prerelease_parts(version: &Version) -> Option<(PreKind, u64)> {
+    let (identifier, counter) = version.pre.as_str().split_once('.')?;
+    Some((PreKind::from_identifier(identifier)?, counter.parse().ok()?))
+}
+
+/// Build a `<kind>.<counter>` pre-release identifier
+fn prerelease(kind: PreKind, counter: u64) -> Prerelease {
+    Prerelease::new(&format!("{}.{}", kind.as_str(), counter)).expect("pre-release identifier is valid")
 }
 
-/// Bump a version according to the bump type
-pub fn bump_version(version: &Version, bump_type: BumpType) -> Version {
+/// Bump a version according to the bump type, advancing a major, minor or patch segment by `by` (--by)
+/// Pre-release and revision bumps always take a single step
+/// Any bump but a revision bump drops the revision segment
+/// Fails when the bumped segment would overflow (e.g. a huge --by)
+pub fn bump_version(version: &Version, bump_type: BumpType, by: u64) -> Result<Version> {
+    let advance = |segment: u64| {
+        segment
+            .checked_add(by)
+            .ok_or_else(|| eyre!("Bumping {} by {} overflows the version", format_cargo_version(version), by))
+    };
     let mut new_version = version.clone();
+    if bump_type != BumpType::Revision {
+        new_version.build = BuildMetadata::EMPTY;
+    }
 
     match bump_type {
         BumpType::Major => {
-            new_version.major += 1;
+            new_version.major = advance(version.major)?;
             new_version.minor = 0;
             new_version.patch = 0;
+            new_version.pre = Prerelease::EMPTY;
         }
         BumpType::Minor => {
-            new_version.minor += 1;
+            new_version.minor = advance(version.minor)?;
             new_version.patch = 0;
+            new_version.pre = Prerelease::EMPTY;
         }
         BumpType::Patch => {
-            new_version.patch += 1;
+            new_version.patch = advance(version.patch)?;
+            new_version.pre = Prerelease::EMPTY;
+        }
+        BumpType::Pre(kind) => {
+            // Same channel advances the counter (rc.1 → rc.2), a later channel restarts it (alpha.2 → beta.1)
+            let counter = match prerelease_parts(version) {
+                Some((current_kind, counter)) if current_kind == kind => counter + 1,
+                _ => 1,
+            };
+            new_version.pre = prerelease(kind, counter);
+
+            // From a stable version or a later channel, start a pre-release of the next patch
+            if new_version <= *version {
+                new_version.patch += 1;
+                new_version.pre = prerelease(kind, 1);
+            }
+        }
+        BumpType::Revision => {
+            // A three-segment version gains a revision (1.2.3 → 1.2.3.1)
+            let next = revision(version).map_or(1, |r| r + 1);
+            new_version.build = revision_build(next);
+            new_version.pre = Prerelease::EMPTY;
         }
     }
 
-    new_version
+    Ok(new_version)
+}
+
+/// Whether a release leaves 0.x for a stable 1.0+ version, committing to SemVer's compatibility rules
+pub fn crosses_stable_boundary(from: &Version, to: &Version) -> bool {
+    from.major == 0 && to.major >= 1
+}
+
+/// The first CalVer version of the given date's month (`YYYY.M.0`)
+pub fn calver_start(today: NaiveDate) -> Version {
+    Version::new(today.year() as u64, today.month() as u64, 0)
+}
+
+/// Bump a CalVer version: major/minor are year/month and patch counts releases within the month
+/// The counter resets to 0 when the year or month rolls over
+pub fn bump_calver(version: &Version, today: NaiveDate) -> Version {
+    let start = calver_start(today);
+    if version.major == start.major && version.minor == start.minor {
+        Version::new(version.major, version.minor, version.patch + 1)
+    } else {
+        start
+    }
+}
+
+/// Promote a pre-release to its stable version (2.0.0-rc.3 → 2.0.0)
+pub fn promote_release(version: &Version) -> Result<Version> {
+    if version.pre.is_empty() {
+        bail!(
+            "{} is not a pre-release; nothing to promote with --release",
+            format_cargo_version(version)
+        );
+    }
+
+    let mut released = version.clone();
+    released.pre = Prerelease::EMPTY;
+    Ok(released)
+}
+
+/// Format the version number with any revision, pre-release and build suffix
+/// (e.g. `1.3.0-rc.1`, `1.2.3.4`, `1.2.3+ci.42`)
+fn format_version_number(version: &Version) -> String {
+    let mut formatted = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    let revision = revision(version);
+    if let Some(revision) = revision {
+        formatted.push_str(&format!(".{}", revision));
+    }
+    if !version.pre.is_empty() {
+        formatted.push('-');
+        formatted.push_str(version.pre.as_str());
+    }
+    if revision.is_none() && !version.build.is_empty() {
+        formatted.push('+');
+        formatted.push_str(version.build.as_str());
+    }
+    formatted
 }
 
 /// Format version for Cargo.toml (no 'v' prefix)
 pub fn format_cargo_version(version: &Version) -> String {
-    format!("{}.{}.{}", version.major, version.minor, version.patch)
+    format_version_number(version)
 }
 
-/// Format version for git tag (with 'v' prefix)
-pub fn format_tag(version: &Version) -> String {
-    format!("v{}.{}.{}", version.major, version.minor, version.patch)
+/// Format version for git tag (with the tag prefix, usually 'v')
+pub fn format_tag(version: &Version, prefix: &str) -> String {
+    format!("{}{}", prefix, format_version_number(version))
 }
 
 #[cfg(test)]
@@ -73,10 +333,52 @@ mod tests {
 
     #[test]
     fn test_bump_type_from_cli() {
-        assert_eq!(BumpType::from_cli(false, false), BumpType::Patch);
-        assert_eq!(BumpType::from_cli(true, false), BumpType::Major);
-        assert_eq!(BumpType::from_cli(false, true), BumpType::Minor);
-        assert_eq!(BumpType::from_cli(true, true), BumpType::Major); // major takes precedence
+        assert_eq!(BumpType::from_cli(false, false, false, None, false), BumpType::Patch);
+        assert_eq!(BumpType::from_cli(false, false, true, None, false), BumpType::Patch);
+        assert_eq!(BumpType::from_cli(true, false, false, None, false), BumpType::Major);
+        assert_eq!(BumpType::from_cli(false, true, false, None, false), BumpType::Minor);
+        assert_eq!(BumpType::from_cli(true, true, false, None, false), BumpType::Major); // major takes precedence
+        assert_eq!(BumpType::from_cli(false, false, false, Some(PreKind::Rc), false), BumpType::Pre(PreKind::Rc));
+        assert_eq!(BumpType::from_cli(false, false, false, None, true), BumpType::Revision);
+    }
+
+    #[test]
+    fn test_infer_bump_type_from_subject() {
+        assert_eq!(infer_bump_type_from_subject("feat: add streaming API"), BumpType::Minor);
+        assert_eq!(infer_bump_type_from_subject("feat(api): add streaming"), BumpType::Minor);
+        assert_eq!(infer_bump_type_from_subject("fix: handle empty input"), BumpType::Patch);
+        assert_eq!(infer_bump_type_from_subject("feat!: drop old API"), BumpType::Major);
+        assert_eq!(infer_bump_type_from_subject("refactor(core)!: rename types"), BumpType::Major);
+        assert_eq!(
+            infer_bump_type_from_subject("feat: new config BREAKING CHANGE: removes flag"),
+            BumpType::Major
+        );
+    }
+
+    #[test]
+    fn test_infer_bump_type_from_message_footer() {
+        let message = "fix: move config\n\nThe file now lives in ~/.config.\n\nBREAKING CHANGE: old path is ignored";
+        assert_eq!(infer_bump_type_from_message(message), BumpType::Major);
+        assert_eq!(infer_bump_type_from_message("feat: a\n\nBREAKING-CHANGE: b"), BumpType::Major);
+        assert_eq!(infer_bump_type_from_message("feat: a\n\nMentions a BREAKING CHANGE: in prose"), BumpType::Minor);
+        assert_eq!(infer_bump_type_from_message("Update README\n\nfeat: not a header"), BumpType::Patch);
+    }
+
+    #[test]
+    fn test_infer_bump_type_takes_strongest_change() {
+        let messages = |subjects: &[&str]| subjects.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(infer_bump_type(&messages(&["fix: a", "feat: b", "docs: c"])), BumpType::Minor);
+        assert_eq!(infer_bump_type(&messages(&["feat: a", "fix!: b"])), BumpType::Major);
+        assert_eq!(infer_bump_type(&messages(&["Update README", "fix: a"])), BumpType::Patch);
+        assert_eq!(infer_bump_type(&[]), BumpType::Patch);
+    }
+
+    #[test]
+    fn test_infer_bump_type_from_subject_defaults_to_patch() {
+        assert_eq!(infer_bump_type_from_subject("Update README"), BumpType::Patch);
+        assert_eq!(infer_bump_type_from_subject(""), BumpType::Patch);
+        assert_eq!(infer_bump_type_from_subject("chore: bump deps"), BumpType::Patch);
     }
 
     #[test]
@@ -96,49 +398,196 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_version_prerelease_error() {
-        let result = parse_version("1.0.0-alpha");
-        assert!(result.is_err());
+    fn test_parse_version_prerelease() {
+        let v = parse_version("v1.3.0-rc.1").unwrap();
+        assert_eq!(v.major, 1);
+        assert_eq!(v.minor, 3);
+        assert_eq!(v.patch, 0);
+        assert_eq!(v.pre.as_str(), "rc.1");
+    }
+
+    #[test]
+    fn test_parse_version_build_metadata() {
+        let v = parse_version("v1.0.0+ci.42").unwrap();
+        assert_eq!(v.build.as_str(), "ci.42");
+        assert_eq!(revision(&v), None);
+        assert_eq!(format_tag(&v, DEFAULT_TAG_PREFIX), "v1.0.0+ci.42");
+        assert_eq!(format_cargo_version(&v), "1.0.0+ci.42");
+        assert_eq!(format_cargo_version(&bump_version(&v, BumpType::Patch, 1).unwrap()), "1.0.1");
+    }
+
+    #[test]
+    fn test_parse_build() {
+        assert_eq!(parse_build("ci.42").unwrap().as_str(), "ci.42");
+        assert_eq!(parse_build("sha-5114f85").unwrap().as_str(), "sha-5114f85");
+        assert!(parse_build("").is_err());
+        assert!(parse_build("ci_42").is_err());
+        assert!(parse_build("ci..42").is_err());
+        assert!(parse_build("rev.4").is_err());
     }
 
     #[test]
-    fn test_parse_version_build_metadata_error() {
-        let result = parse_version("1.0.0+build123");
-        assert!(result.is_err());
+    fn test_parse_version_four_segments() {
+        assert!(parse_version("1.2.3.4").is_err(), "MUST keep Cargo versions to three segments");
+        let v = parse_revision_version("v1.2.3.4").unwrap();
+        assert_eq!((v.major, v.minor, v.patch), (1, 2, 3));
+        assert_eq!(revision(&v), Some(4));
+        assert_eq!(format_cargo_version(&v), "1.2.3.4");
+        assert_eq!(format_tag(&v, DEFAULT_TAG_PREFIX), "v1.2.3.4");
+        assert_eq!(format_cargo_version(&parse_revision_version("1.2.3.4-rc.1").unwrap()), "1.2.3.4-rc.1");
+        assert!(parse_revision_version("1.2.3.04").is_err());
+        assert!(parse_revision_version("1.2.3.4.5").is_err());
+        let build = parse_revision_version("1.2.3+4").unwrap();
+        assert_eq!(revision(&build), None, "MUST not read build metadata as a revision");
+        assert!(parse_revision_version("1.2.3.5").unwrap() > v);
+        assert!(parse_revision_version("1.2.3.10").unwrap() > parse_revision_version("1.2.3.9").unwrap());
+    }
+
+    #[test]
+    fn test_bump_revision() {
+        let v = parse_revision_version("1.2.3.4").unwrap();
+        assert_eq!(format_cargo_version(&bump_version(&v, BumpType::Revision, 1).unwrap()), "1.2.3.5");
+        let stable = Version::new(1, 2, 3);
+        assert_eq!(format_cargo_version(&bump_version(&stable, BumpType::Revision, 1).unwrap()), "1.2.3.1");
+        assert_eq!(format_cargo_version(&bump_version(&v, BumpType::Patch, 1).unwrap()), "1.2.4");
+        assert_eq!(format_cargo_version(&bump_version(&v, BumpType::Minor, 1).unwrap()), "1.3.0");
+    }
+
+    #[test]
+    fn test_crosses_stable_boundary() {
+        let zero = Version::new(0, 9, 3);
+        assert!(crosses_stable_boundary(&zero, &bump_version(&zero, BumpType::Major, 1).unwrap()));
+        assert!(!crosses_stable_boundary(&zero, &bump_version(&zero, BumpType::Minor, 1).unwrap()));
+        assert!(!crosses_stable_boundary(&Version::new(1, 4, 0), &Version::new(2, 0, 0)));
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_bump_calver_same_month_increments_counter() {
+        let version = Version::new(2024, 3, 0);
+        assert_eq!(bump_calver(&version, date(2024, 3, 15)), Version::new(2024, 3, 1));
+        assert_eq!(bump_calver(&Version::new(2024, 3, 7), date(2024, 3, 31)), Version::new(2024, 3, 8));
+    }
+
+    #[test]
+    fn test_bump_calver_month_rollover_resets_counter() {
+        assert_eq!(bump_calver(&Version::new(2024, 3, 4), date(2024, 4, 1)), Version::new(2024, 4, 0));
+    }
+
+    #[test]
+    fn test_bump_calver_year_rollover_resets_counter() {
+        assert_eq!(bump_calver(&Version::new(2024, 12, 2), date(2025, 1, 2)), Version::new(2025, 1, 0));
+        // Same month number in a new year still rolls over
+        assert_eq!(bump_calver(&Version::new(2024, 3, 2), date(2025, 3, 2)), Version::new(2025, 3, 0));
+    }
+
+    #[test]
+    fn test_bump_calver_from_semver_starts_current_month() {
+        assert_eq!(bump_calver(&Version::new(1, 4, 2), date(2024, 3, 15)), Version::new(2024, 3, 0));
+        assert_eq!(calver_start(date(2024, 11, 5)), Version::new(2024, 11, 0));
     }
 
     #[test]
     fn test_bump_patch() {
         let v = Version::new(1, 2, 3);
-        let bumped = bump_version(&v, BumpType::Patch);
+        let bumped = bump_version(&v, BumpType::Patch, 1).unwrap();
         assert_eq!(bumped, Version::new(1, 2, 4));
     }
 
     #[test]
     fn test_bump_patch_rollover() {
         let v = Version::new(1, 2, 9);
-        let bumped = bump_version(&v, BumpType::Patch);
+        let bumped = bump_version(&v, BumpType::Patch, 1).unwrap();
         assert_eq!(bumped, Version::new(1, 2, 10));
 
         let v = Version::new(1, 2, 99);
-        let bumped = bump_version(&v, BumpType::Patch);
+        let bumped = bump_version(&v, BumpType::Patch, 1).unwrap();
         assert_eq!(bumped, Version::new(1, 2, 100));
     }
 
     #[test]
     fn test_bump_minor() {
         let v = Version::new(1, 2, 3);
-        let bumped = bump_version(&v, BumpType::Minor);
+        let bumped = bump_version(&v, BumpType::Minor, 1).unwrap();
         assert_eq!(bumped, Version::new(1, 3, 0));
     }
 
     #[test]
     fn test_bump_major() {
         let v = Version::new(1, 2, 3);
-        let bumped = bump_version(&v, BumpType::Major);
+        let bumped = bump_version(&v, BumpType::Major, 1).unwrap();
         assert_eq!(bumped, Version::new(2, 0, 0));
     }
 
+    #[test]
+    fn test_bump_by() {
+        let v = parse_version("1.2.3-rc.1").unwrap();
+        assert_eq!(bump_version(&v, BumpType::Patch, 5).unwrap(), Version::new(1, 2, 8));
+        assert_eq!(bump_version(&v, BumpType::Minor, 3).unwrap(), Version::new(1, 5, 0));
+        assert_eq!(bump_version(&v, BumpType::Major, 2).unwrap(), Version::new(3, 0, 0));
+        assert_eq!(bump_version(&v, BumpType::Pre(PreKind::Rc), 4).unwrap(), parse_version("1.2.3-rc.2").unwrap());
+
+        let err = bump_version(&v, BumpType::Minor, u64::MAX).unwrap_err();
+        assert!(err.to_string().contains("overflows the version"), "{}", err);
+    }
+
+    #[test]
+    fn test_bump_pre_from_stable_starts_next_patch() {
+        let v = Version::new(1, 2, 3);
+        let bumped = bump_version(&v, BumpType::Pre(PreKind::Rc), 1).unwrap();
+        assert_eq!(bumped, parse_version("1.2.4-rc.1").unwrap());
+    }
+
+    #[test]
+    fn test_bump_pre_same_kind_increments_counter() {
+        let v = parse_version("1.3.0-rc.1").unwrap();
+        let bumped = bump_version(&v, BumpType::Pre(PreKind::Rc), 1).unwrap();
+        assert_eq!(bumped, parse_version("1.3.0-rc.2").unwrap());
+
+        let v = parse_version("1.3.0-rc.9").unwrap();
+        let bumped = bump_version(&v, BumpType::Pre(PreKind::Rc), 1).unwrap();
+        assert_eq!(bumped, parse_version("1.3.0-rc.10").unwrap());
+    }
+
+    #[test]
+    fn test_bump_pre_later_kind_restarts_counter() {
+        let v = parse_version("1.3.0-alpha.2").unwrap();
+        let bumped = bump_version(&v, BumpType::Pre(PreKind::Beta), 1).unwrap();
+        assert_eq!(bumped, parse_version("1.3.0-beta.1").unwrap());
+    }
+
+    #[test]
+    fn test_bump_pre_earlier_kind_moves_to_next_patch() {
+        let v = parse_version("1.3.0-rc.2").unwrap();
+        let bumped = bump_version(&v, BumpType::Pre(PreKind::Alpha), 1).unwrap();
+        assert_eq!(bumped, parse_version("1.3.1-alpha.1").unwrap());
+    }
+
+    #[test]
+    fn test_bump_stable_from_prerelease_clears_suffix() {
+        let v = parse_version("1.3.0-rc.2").unwrap();
+        assert_eq!(bump_version(&v, BumpType::Patch, 1).unwrap(), Version::new(1, 3, 1));
+        assert_eq!(bump_version(&v, BumpType::Minor, 1).unwrap(), Version::new(1, 4, 0));
+        assert_eq!(bump_version(&v, BumpType::Major, 1).unwrap(), Version::new(2, 0, 0));
+    }
+
+    #[test]
+    fn test_promote_release() {
+        let v = parse_version("2.0.0-rc.3").unwrap();
+        assert_eq!(promote_release(&v).unwrap(), Version::new(2, 0, 0));
+        assert!(promote_release(&Version::new(2, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_format_prerelease() {
+        let v = parse_version("1.3.0-rc.1").unwrap();
+        assert_eq!(format_cargo_version(&v), "1.3.0-rc.1");
+        assert_eq!(format_tag(&v, DEFAULT_TAG_PREFIX), "v1.3.0-rc.1");
+    }
+
     #[test]
     fn test_format_cargo_version() {
         let v = Version::new(1, 2, 3);
@@ -148,6 +597,22 @@ mod tests {
     #[test]
     fn test_format_tag() {
         let v = Version::new(1, 2, 3);
-        assert_eq!(format_tag(&v), "v1.2.3");
+        assert_eq!(format_tag(&v, DEFAULT_TAG_PREFIX), "v1.2.3");
+    }
+
+    #[test]
+    fn test_format_tag_namespaced() {
+        let v = Version::new(1, 2, 3);
+        assert_eq!(format_tag(&v, "mycrate-v"), "mycrate-v1.2.3");
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(parse_tag("v1.2.3", DEFAULT_TAG_PREFIX, false).unwrap(), Version::new(1, 2, 3));
+        assert_eq!(parse_tag("mycrate-v1.2.3", "mycrate-v", false).unwrap(), Version::new(1, 2, 3));
+        assert!(parse_tag("othercrate-v1.2.3", "mycrate-v", false).is_err());
+        assert_eq!(parse_tag("release-1.2.3", "release-", false).unwrap(), Version::new(1, 2, 3));
+        assert_eq!(parse_tag("1.2.3", "", false).unwrap(), Version::new(1, 2, 3));
+        assert!(parse_tag("v1.2.3", "", false).is_err());
     }
 }
@@ -0,0 +1,71 @@
+use eyre::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The plain-text version file used by non-Cargo projects
+const VERSION_FILE: &str = "VERSION";
+
+/// Get the path to the VERSION file in the given directory
+pub fn version_file_path(dir: &Path) -> PathBuf {
+    dir.join(VERSION_FILE)
+}
+
+/// Read the bare version (e.g. `1.2.3`) from the VERSION file
+/// Returns None if the file doesn't exist or is empty
+pub fn read_version_file(dir: &Path) -> Result<Option<String>> {
+    let path = version_file_path(dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).context(format!("Failed to read {}", path.display()))?;
+    let version = content.trim();
+    Ok((!version.is_empty()).then(|| version.to_string()))
+}
+
+/// Write the version to the VERSION file, keeping its trailing newline and line ending (new files get `\n`)
+pub fn write_version_file(dir: &Path, new_version: &str) -> Result<()> {
+    let path = version_file_path(dir);
+    let line_ending = match fs::read_to_string(&path) {
+        Ok(content) if content.ends_with("\r\n") => "\r\n",
+        Ok(content) if !content.ends_with('\n') => "",
+        _ => "\n",
+    };
+
+    let content = format!("{}{}", new_version, line_ending);
+    fs::write(&path, content).context(format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_version_file() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(read_version_file(dir.path()).unwrap(), None);
+
+        fs::write(version_file_path(dir.path()), "1.2.3\n").unwrap();
+        assert_eq!(read_version_file(dir.path()).unwrap(), Some("1.2.3".to_string()));
+    }
+
+    #[test]
+    fn test_write_version_file_preserves_trailing_newline() {
+        let dir = TempDir::new().unwrap();
+        let path = version_file_path(dir.path());
+
+        write_version_file(dir.path(), "0.1.0").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "0.1.0\n");
+
+        fs::write(&path, "1.2.3").unwrap();
+        write_version_file(dir.path(), "1.2.4").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1.2.4");
+
+        fs::write(&path, "1.2.4\r\n").unwrap();
+        write_version_file(dir.path(), "1.2.5").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1.2.5\r\n");
+    }
+}
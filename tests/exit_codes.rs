@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let output = Command::new("git").args(args).current_dir(dir).output().unwrap();
+    assert!(output.status.success(), "git {:?} failed", args);
+}
+
+/// A repository with Cargo.toml at 1.2.3, tagged, plus an uncommitted change
+fn release_ready_repo(dir: &Path) {
+    git(dir, &["init"]);
+    git(dir, &["config", "user.email", "test@test.com"]);
+    git(dir, &["config", "user.name", "Test"]);
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"app\"\nversion = \"1.2.3\"\n").unwrap();
+    fs::write(dir.join("README.md"), "# App").unwrap();
+    git(dir, &["add", "-A"]);
+    git(dir, &["commit", "-m", "Initial commit"]);
+    git(dir, &["tag", "-a", "v1.2.3", "-m", "Release v1.2.3"]);
+    fs::write(dir.join("README.md"), "# App\n\nNew feature").unwrap();
+}
+
+/// Run bump with a throwaway HOME so its log file stays out of the real one
+fn bump(home: &Path, args: &[&str]) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_bump"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_DATA_HOME", home.join("data"))
+        .output()
+        .unwrap()
+        .status
+        .code()
+        .unwrap()
+}
+
+#[test]
+fn exit_codes_distinguish_outcomes() {
+    let home = TempDir::new().unwrap();
+    let good = TempDir::new().unwrap();
+    let bad = TempDir::new().unwrap();
+    release_ready_repo(good.path());
+    let good_dir = good.path().to_str().unwrap();
+    let bad_dir = bad.path().to_str().unwrap();
+
+    assert_eq!(bump(home.path(), &["-n", "--allow-dirty", good_dir]), 3, "dry run with a release pending");
+    assert_eq!(bump(home.path(), &["-a", bad_dir]), 2, "every directory failed");
+    assert_eq!(bump(home.path(), &["-a", "--allow-dirty", good_dir, bad_dir]), 1, "some directories failed");
+
+    fs::write(good.path().join("README.md"), "# App\n\nAnother feature").unwrap();
+    assert_eq!(bump(home.path(), &["-a", "--allow-dirty", good_dir]), 0, "released");
+}
+
+#[test]
+fn exit_codes_for_setup_errors_and_idle_dry_runs() {
+    let home = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    release_ready_repo(repo.path());
+    let dir = repo.path().to_str().unwrap();
+
+    // Moving v1.2.3 onto the commit it already marks has nothing to do
+    assert_eq!(bump(home.path(), &["-n", "--tag-only", "--force", dir]), 0, "dry run with nothing pending");
+
+    fs::write(repo.path().join(".bump.toml"), "not = [valid").unwrap();
+    assert_eq!(bump(home.path(), &["-n", dir]), 2, "error before any directory ran");
+}